@@ -5,6 +5,7 @@ use crate::{
     ParseError, date_time,
     fixed_string::{self, FixedStringN},
     nullable::{self, NullableTypeName},
+    type_name::TypeName,
     type_name_parser::Rule,
 };
 
@@ -75,6 +76,33 @@ impl TryFrom<Pair<'_, Rule>> for LowCardinalityDataType {
     }
 }
 
+impl From<LowCardinalityDataType> for TypeName {
+    fn from(data_type: LowCardinalityDataType) -> Self {
+        match data_type {
+            LowCardinalityDataType::UInt8 => Self::UInt8,
+            LowCardinalityDataType::UInt16 => Self::UInt16,
+            LowCardinalityDataType::UInt32 => Self::UInt32,
+            LowCardinalityDataType::UInt64 => Self::UInt64,
+            LowCardinalityDataType::Int8 => Self::Int8,
+            LowCardinalityDataType::Int16 => Self::Int16,
+            LowCardinalityDataType::Int32 => Self::Int32,
+            LowCardinalityDataType::Int64 => Self::Int64,
+            LowCardinalityDataType::Float32 => Self::Float32,
+            LowCardinalityDataType::Float64 => Self::Float64,
+            LowCardinalityDataType::String => Self::String,
+            LowCardinalityDataType::FixedString(n) => Self::FixedString(n),
+            LowCardinalityDataType::Date => Self::Date,
+            LowCardinalityDataType::DateTime(timezone) => Self::DateTime(timezone),
+            LowCardinalityDataType::Ipv4 => Self::Ipv4,
+            LowCardinalityDataType::Ipv6 => Self::Ipv6,
+            //
+            //
+            //
+            LowCardinalityDataType::Nullable(type_name) => Self::Nullable(type_name),
+        }
+    }
+}
+
 pub(crate) fn get_data_type(
     mut low_cardinality_pairs: Pairs<'_, Rule>,
 ) -> Result<LowCardinalityDataType, ParseError> {
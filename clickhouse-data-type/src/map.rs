@@ -128,6 +128,56 @@ impl TryFrom<Pair<'_, Rule>> for MapValue {
     }
 }
 
+impl From<MapKey> for TypeName {
+    fn from(map_key: MapKey) -> Self {
+        match map_key {
+            MapKey::UInt8 => Self::UInt8,
+            MapKey::UInt16 => Self::UInt16,
+            MapKey::UInt32 => Self::UInt32,
+            MapKey::UInt64 => Self::UInt64,
+            MapKey::UInt256 => Self::UInt256,
+            MapKey::Int8 => Self::Int8,
+            MapKey::Int16 => Self::Int16,
+            MapKey::Int32 => Self::Int32,
+            MapKey::Int64 => Self::Int64,
+            MapKey::Int128 => Self::Int128,
+            MapKey::Int256 => Self::Int256,
+            MapKey::Float32 => Self::Float32,
+            MapKey::Float64 => Self::Float64,
+            MapKey::Decimal(precision, scale) => Self::Decimal(precision, scale),
+            MapKey::String => Self::String,
+            MapKey::FixedString(n) => Self::FixedString(n),
+        }
+    }
+}
+
+impl From<MapValue> for TypeName {
+    fn from(map_value: MapValue) -> Self {
+        match map_value {
+            MapValue::UInt8 => Self::UInt8,
+            MapValue::UInt16 => Self::UInt16,
+            MapValue::UInt32 => Self::UInt32,
+            MapValue::UInt64 => Self::UInt64,
+            MapValue::UInt256 => Self::UInt256,
+            MapValue::Int8 => Self::Int8,
+            MapValue::Int16 => Self::Int16,
+            MapValue::Int32 => Self::Int32,
+            MapValue::Int64 => Self::Int64,
+            MapValue::Int128 => Self::Int128,
+            MapValue::Int256 => Self::Int256,
+            MapValue::Float32 => Self::Float32,
+            MapValue::Float64 => Self::Float64,
+            MapValue::Decimal(precision, scale) => Self::Decimal(precision, scale),
+            MapValue::String => Self::String,
+            MapValue::FixedString(n) => Self::FixedString(n),
+            //
+            //
+            //
+            MapValue::Array(type_name) => Self::Array(type_name),
+        }
+    }
+}
+
 pub(crate) fn get_map_key_and_map_value(
     mut map_pairs: Pairs<'_, Rule>,
 ) -> Result<(MapKey, MapValue), ParseError> {
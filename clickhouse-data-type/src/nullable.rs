@@ -7,6 +7,7 @@ use crate::{
     decimal::{self, DecimalPrecision, DecimalScale},
     r#enum::{self, Enum8, Enum16},
     fixed_string::{self, FixedStringN},
+    type_name::TypeName,
     type_name_parser::Rule,
 };
 
@@ -101,6 +102,45 @@ impl TryFrom<Pair<'_, Rule>> for NullableTypeName {
     }
 }
 
+impl TryFrom<NullableTypeName> for TypeName {
+    type Error = ParseError;
+
+    fn try_from(type_name: NullableTypeName) -> Result<Self, Self::Error> {
+        match type_name {
+            NullableTypeName::Nothing => Err(ParseError::ValueInvalid(
+                "nullable nothing has no type name".to_string(),
+            )),
+            //
+            NullableTypeName::UInt8 => Ok(Self::UInt8),
+            NullableTypeName::UInt16 => Ok(Self::UInt16),
+            NullableTypeName::UInt32 => Ok(Self::UInt32),
+            NullableTypeName::UInt64 => Ok(Self::UInt64),
+            NullableTypeName::UInt256 => Ok(Self::UInt256),
+            NullableTypeName::Int8 => Ok(Self::Int8),
+            NullableTypeName::Int16 => Ok(Self::Int16),
+            NullableTypeName::Int32 => Ok(Self::Int32),
+            NullableTypeName::Int64 => Ok(Self::Int64),
+            NullableTypeName::Int128 => Ok(Self::Int128),
+            NullableTypeName::Int256 => Ok(Self::Int256),
+            NullableTypeName::Float32 => Ok(Self::Float32),
+            NullableTypeName::Float64 => Ok(Self::Float64),
+            NullableTypeName::Decimal(precision, scale) => Ok(Self::Decimal(precision, scale)),
+            NullableTypeName::String => Ok(Self::String),
+            NullableTypeName::FixedString(n) => Ok(Self::FixedString(n)),
+            NullableTypeName::Uuid => Ok(Self::Uuid),
+            NullableTypeName::Date => Ok(Self::Date),
+            NullableTypeName::DateTime(timezone) => Ok(Self::DateTime(timezone)),
+            NullableTypeName::DateTime64(precision, timezone) => {
+                Ok(Self::DateTime64(precision, timezone))
+            }
+            NullableTypeName::Enum8(inner) => Ok(Self::Enum8(inner)),
+            NullableTypeName::Enum16(inner) => Ok(Self::Enum16(inner)),
+            NullableTypeName::Ipv4 => Ok(Self::Ipv4),
+            NullableTypeName::Ipv6 => Ok(Self::Ipv6),
+        }
+    }
+}
+
 pub(crate) fn get_type_name(
     mut nullable_pairs: Pairs<'_, Rule>,
 ) -> Result<NullableTypeName, ParseError> {
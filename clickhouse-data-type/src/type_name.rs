@@ -1,4 +1,4 @@
-use core::str::FromStr;
+use core::{fmt, str::FromStr};
 
 use chrono_tz::Tz;
 use pest::{Parser as _, iterators::Pair};
@@ -74,6 +74,106 @@ impl FromStr for TypeName {
     }
 }
 
+impl fmt::Display for TypeName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UInt8 => write!(f, "UInt8"),
+            Self::UInt16 => write!(f, "UInt16"),
+            Self::UInt32 => write!(f, "UInt32"),
+            Self::UInt64 => write!(f, "UInt64"),
+            Self::UInt256 => write!(f, "UInt256"),
+            Self::Int8 => write!(f, "Int8"),
+            Self::Int16 => write!(f, "Int16"),
+            Self::Int32 => write!(f, "Int32"),
+            Self::Int64 => write!(f, "Int64"),
+            Self::Int128 => write!(f, "Int128"),
+            Self::Int256 => write!(f, "Int256"),
+            Self::Float32 => write!(f, "Float32"),
+            Self::Float64 => write!(f, "Float64"),
+            Self::Decimal(precision, scale) => write!(f, "Decimal({}, {})", precision.0, scale.0),
+            Self::String => write!(f, "String"),
+            Self::FixedString(n) => write!(f, "FixedString({})", n.0),
+            Self::Uuid => write!(f, "UUID"),
+            Self::Date => write!(f, "Date"),
+            Self::DateTime(None) => write!(f, "DateTime"),
+            Self::DateTime(Some(tz)) => write!(f, "DateTime('{}')", tz.name()),
+            Self::DateTime64(precision, None) => write!(f, "DateTime64({})", precision.0),
+            Self::DateTime64(precision, Some(tz)) => {
+                write!(f, "DateTime64({}, '{}')", precision.0, tz.name())
+            }
+            Self::Enum8(map) => {
+                write!(f, "Enum8(")?;
+                fmt_enum(f, map.iter().map(|(k, v)| (k, i16::from(*v))))?;
+                write!(f, ")")
+            }
+            Self::Enum16(map) => {
+                write!(f, "Enum16(")?;
+                fmt_enum(f, map.iter().map(|(k, v)| (k, *v)))?;
+                write!(f, ")")
+            }
+            Self::Ipv4 => write!(f, "IPv4"),
+            Self::Ipv6 => write!(f, "IPv6"),
+            //
+            //
+            //
+            Self::LowCardinality(data_type) => {
+                write!(f, "LowCardinality({})", Self::from(data_type.to_owned()))
+            }
+            Self::Nullable(NullableTypeName::Nothing) => write!(f, "Nullable(Nothing)"),
+            Self::Nullable(type_name) => match Self::try_from(type_name.to_owned()) {
+                Ok(type_name) => write!(f, "Nullable({type_name})"),
+                Err(_) => Err(fmt::Error),
+            },
+            Self::Point => write!(f, "Point"),
+            Self::Ring => write!(f, "Ring"),
+            Self::Polygon => write!(f, "Polygon"),
+            Self::MultiPolygon => write!(f, "MultiPolygon"),
+            //
+            //
+            //
+            Self::Array(type_name) => write!(f, "Array({type_name})"),
+            Self::Tuple(type_names) => {
+                write!(f, "Tuple(")?;
+                for (i, type_name) in type_names.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{type_name}")?;
+                }
+                write!(f, ")")
+            }
+            Self::Map(map_key, map_value) => write!(
+                f,
+                "Map({}, {})",
+                Self::from(map_key.to_owned()),
+                Self::from(map_value.to_owned())
+            ),
+        }
+    }
+}
+
+// Ordered by value, as the server prints them.
+fn fmt_enum<'a>(
+    f: &mut fmt::Formatter<'_>,
+    iter: impl Iterator<Item = (&'a String, i16)>,
+) -> fmt::Result {
+    let mut items: Vec<_> = iter.collect();
+    items.sort_by_key(|(_, v)| *v);
+
+    for (i, (k, v)) in items.into_iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(
+            f,
+            "'{}' = {}",
+            k.replace('\\', "\\\\").replace('\'', "\\'"),
+            v
+        )?;
+    }
+    Ok(())
+}
+
 impl TypeName {
     pub(crate) fn from_pair(pair: Pair<'_, Rule>) -> Result<TypeName, ParseError> {
         match pair.as_rule() {
@@ -688,6 +788,79 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_from_inner_type_name() -> Result<(), Box<dyn std::error::Error>> {
+        match "Nullable(String)".parse()? {
+            TypeName::Nullable(type_name) => {
+                assert_eq!(TypeName::try_from(type_name)?, TypeName::String)
+            }
+            x => panic!("{x:?}"),
+        }
+        match "Nullable(Nothing)".parse()? {
+            TypeName::Nullable(type_name) => assert!(TypeName::try_from(type_name).is_err()),
+            x => panic!("{x:?}"),
+        }
+
+        match "LowCardinality(Nullable(String))".parse()? {
+            TypeName::LowCardinality(data_type) => assert_eq!(
+                TypeName::from(data_type),
+                TypeName::Nullable(NullableTypeName::String)
+            ),
+            x => panic!("{x:?}"),
+        }
+
+        match "Map(FixedString(2), Array(String))".parse()? {
+            TypeName::Map(map_key, map_value) => {
+                assert_eq!(
+                    TypeName::from(map_key),
+                    TypeName::FixedString(FixedStringN(2))
+                );
+                assert_eq!(
+                    TypeName::from(map_value),
+                    TypeName::Array(TypeName::String.into())
+                );
+            }
+            x => panic!("{x:?}"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_display() -> Result<(), Box<dyn std::error::Error>> {
+        let contents = [
+            include_str!("../tests/files/array.txt"),
+            include_str!("../tests/files/date.txt"),
+            include_str!("../tests/files/datetime.txt"),
+            include_str!("../tests/files/datetime64.txt"),
+            include_str!("../tests/files/decimal.txt"),
+            include_str!("../tests/files/enum.txt"),
+            include_str!("../tests/files/fixedstring.txt"),
+            include_str!("../tests/files/float.txt"),
+            include_str!("../tests/files/int_uint.txt"),
+            include_str!("../tests/files/ipv4.txt"),
+            include_str!("../tests/files/ipv6.txt"),
+            include_str!("../tests/files/lowcardinality.txt"),
+            include_str!("../tests/files/map.txt"),
+            include_str!("../tests/files/nullable.txt"),
+            include_str!("../tests/files/string.txt"),
+            include_str!("../tests/files/tuple.txt"),
+            include_str!("../tests/files/uuid.txt"),
+        ];
+        for content in contents {
+            let line = content.lines().nth(2).unwrap();
+            for s in serde_json::from_str::<Vec<String>>(line)? {
+                assert_eq!(s.parse::<TypeName>()?.to_string(), s);
+            }
+        }
+
+        for s in ["Point", "Ring", "Polygon", "MultiPolygon"] {
+            assert_eq!(s.parse::<TypeName>()?.to_string(), s);
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_parse_map() -> Result<(), Box<dyn std::error::Error>> {
         let content = include_str!("../tests/files/map.txt");
@@ -0,0 +1,60 @@
+// Options shared by the CSV inputs and outputs, they must match the server settings.
+// https://clickhouse.com/docs/en/operations/settings/formats#format_csv_delimiter
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    delimiter: u8,
+    quote_style: CsvQuoteStyle,
+    null_representation: String,
+}
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote_style: CsvQuoteStyle::default(),
+            null_representation: r"\N".into(),
+        }
+    }
+}
+impl CsvOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_delimiter(&self) -> u8 {
+        self.delimiter
+    }
+    pub fn get_quote_style(&self) -> CsvQuoteStyle {
+        self.quote_style
+    }
+    pub fn get_null_representation(&self) -> &str {
+        &self.null_representation
+    }
+
+    /// format_csv_delimiter
+    pub fn set_delimiter(&mut self, val: u8) -> &mut Self {
+        self.delimiter = val;
+        self
+    }
+    /// Only used when writing, values are always read with double quotes.
+    pub fn set_quote_style(&mut self, val: CsvQuoteStyle) -> &mut Self {
+        self.quote_style = val;
+        self
+    }
+    /// format_csv_null_representation
+    pub fn set_null_representation(&mut self, val: impl Into<String>) -> &mut Self {
+        self.null_representation = val.into();
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CsvQuoteStyle {
+    /// Only values containing the delimiter, a quote or a line break.
+    #[default]
+    Necessary,
+    Always,
+    /// Everything but numbers and bools, like the server does.
+    NonNumeric,
+    Never,
+}
@@ -0,0 +1,272 @@
+// When a query fails after the response has started, the server appends the exception to the body.
+//
+// Older servers write it as plain text, e.g. "Code: 241. DB::Exception: Memory limit ...".
+// Newer servers send a `X-ClickHouse-Exception-Tag` header and frame it as
+// "__exception__\r\n<tag>\r\n<message>\n<message length> <tag>\r\n__exception__\r\n".
+
+pub const EXCEPTION_MARKER: &str = "__exception__";
+
+/// Splits a body into the data before a trailing exception and the exception message.
+///
+/// With a `tag`, only the framed exception is recognized, without one only the plain text on
+/// the last line.
+pub fn split_exception<'a>(slice: &'a [u8], tag: Option<&str>) -> (&'a [u8], Option<String>) {
+    if let Some(i) = find_framed(slice, tag) {
+        return (&slice[..i], Some(parse_framed(&slice[i..])));
+    }
+    // Without a tag only the last line can be the exception, earlier ones are rows.
+    if tag.is_none() {
+        let end = slice
+            .iter()
+            .rposition(|b| !b.is_ascii_whitespace())
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let i = slice[..end]
+            .iter()
+            .rposition(|b| *b == b'\n')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        if is_plain_exception_line(&slice[i..]) {
+            let message = String::from_utf8_lossy(&slice[i..]).trim().to_owned();
+            return (&slice[..i], Some(message));
+        }
+    }
+
+    (slice, None)
+}
+
+/// Like `split_exception`, but only a framed exception at the very end of the body is recognized.
+///
+/// For binary formats, whose data may contain anything that looks like text.
+pub fn split_trailing_exception<'a>(
+    slice: &'a [u8],
+    tag: Option<&str>,
+) -> (&'a [u8], Option<String>) {
+    let Some(tag) = tag else {
+        return (slice, None);
+    };
+    if !ends_with_exception_frame(slice, tag) {
+        return (slice, None);
+    }
+
+    let header = format!("{EXCEPTION_MARKER}\r\n{tag}\r\n");
+    match slice
+        .windows(header.len())
+        .rposition(|x| x == header.as_bytes())
+    {
+        Some(i) => (&slice[..i], Some(parse_framed(&slice[i..]))),
+        None => (slice, None),
+    }
+}
+
+/// Whether a line (without the trailing `\n`) starts a trailing exception in plain text.
+///
+/// For streaming, it is the exception only if it is the last line of the body, otherwise a row.
+/// A framed exception is found by `is_exception_marker_line` then `is_exception_tag_line`.
+pub fn is_exception_start_line(line: &[u8], tag: Option<&str>) -> bool {
+    tag.is_none() && is_plain_exception_line(line)
+}
+
+/// Whether a line (without the trailing `\n`) may start a framed exception.
+///
+/// It only does if the next line passes `is_exception_tag_line`, otherwise it is data.
+pub fn is_exception_marker_line(line: &[u8]) -> bool {
+    trim_cr(line) == EXCEPTION_MARKER.as_bytes()
+}
+
+/// Whether the line after a marker line (without the trailing `\n`) is the tag of the exception.
+///
+/// The server only frames exceptions when it sends the tag header, so without a `tag` it never is.
+pub fn is_exception_tag_line(line: &[u8], tag: Option<&str>) -> bool {
+    tag.is_some_and(|tag| trim_cr(line) == tag.as_bytes())
+}
+
+/// Whether `slice` ends with the closing line of the exception framed with `tag`.
+pub fn ends_with_exception_frame(slice: &[u8], tag: &str) -> bool {
+    slice.ends_with(format!(" {tag}\r\n{EXCEPTION_MARKER}\r\n").as_bytes())
+}
+
+fn find_framed(slice: &[u8], tag: Option<&str>) -> Option<usize> {
+    line_starts(slice).find(|i| {
+        let mut lines = slice[*i..].split(|b| *b == b'\n');
+        lines.next().is_some_and(is_exception_marker_line)
+            && lines
+                .next()
+                .is_some_and(|line| is_exception_tag_line(line, tag))
+    })
+}
+
+fn parse_framed(slice: &[u8]) -> String {
+    let s = String::from_utf8_lossy(slice);
+
+    // Skips the marker and the tag lines.
+    let mut rest = s.splitn(3, '\n').nth(2).unwrap_or_default().trim_end();
+    rest = rest
+        .strip_suffix(EXCEPTION_MARKER)
+        .unwrap_or(rest)
+        .trim_end();
+    // Drops the "<message length> <tag>" line.
+    if let Some((message, _)) = rest.rsplit_once('\n') {
+        rest = message;
+    }
+
+    rest.trim().to_owned()
+}
+
+// e.g. "Code: 60. DB::Exception: ..." or "Code: 60, e.displayText() = DB::Exception: ..."
+fn is_plain_exception_line(slice: &[u8]) -> bool {
+    let line = slice.split(|b| *b == b'\n').next().unwrap_or_default();
+
+    line.strip_prefix(b"Code: ")
+        .and_then(|x| x.first())
+        .map(|x| x.is_ascii_digit())
+        .unwrap_or(false)
+        && line.windows(13).any(|x| x == b"DB::Exception")
+}
+
+fn line_starts(slice: &[u8]) -> impl Iterator<Item = usize> + '_ {
+    core::iter::once(0).chain(
+        slice
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| **b == b'\n')
+            .map(|(i, _)| i + 1),
+    )
+}
+
+fn trim_cr(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_exception_plain() {
+        let body = b"1\ta\n2\tb\nCode: 241. DB::Exception: Memory limit (total) exceeded. (MEMORY_LIMIT_EXCEEDED) (version 23.3.1.1)\n";
+        let (data, message) = split_exception(body, None);
+        assert_eq!(data, b"1\ta\n2\tb\n");
+        assert_eq!(
+            message.unwrap(),
+            "Code: 241. DB::Exception: Memory limit (total) exceeded. (MEMORY_LIMIT_EXCEEDED) (version 23.3.1.1)"
+        );
+
+        let body =
+            b"Code: 395, e.displayText() = DB::Exception: Value passed to 'throwIf' function is non zero\n";
+        let (data, message) = split_exception(body, None);
+        assert!(data.is_empty());
+        assert!(message.unwrap().starts_with("Code: 395"));
+
+        let body = b"1\tCode: 1\n2\tb\n";
+        assert_eq!(split_exception(body, None), (&body[..], None));
+
+        // A row that looks like an exception, followed by more rows.
+        let body = b"Code: 60. DB::Exception: Table default.t doesn't exist\nfoo\n";
+        assert_eq!(split_exception(body, None), (&body[..], None));
+    }
+
+    #[test]
+    fn test_split_exception_framed() {
+        let body = b"1\ta\n__exception__\r\nxqrbqvoptnolbrdf\r\nCode: 395. DB::Exception: Value passed to 'throwIf' function is non-zero. (FUNCTION_THROW_IF_VALUE_IS_NON_ZERO)\n104 xqrbqvoptnolbrdf\r\n__exception__\r\n";
+        let expected = "Code: 395. DB::Exception: Value passed to 'throwIf' function is non-zero. (FUNCTION_THROW_IF_VALUE_IS_NON_ZERO)";
+
+        let (data, message) = split_exception(body, Some("xqrbqvoptnolbrdf"));
+        assert_eq!(data, b"1\ta\n");
+        assert_eq!(message.unwrap(), expected);
+
+        // Without a tag the frame is data, and the plain text exception in it is not the last line.
+        assert_eq!(split_exception(body, None), (&body[..], None));
+        let body_without_message = b"__exception__\nfoo\n";
+        assert_eq!(
+            split_exception(body_without_message, None),
+            (&body_without_message[..], None)
+        );
+
+        assert_eq!(
+            split_exception(body, Some("otherotherother0")),
+            (&body[..], None)
+        );
+
+        // With a tag, plain text that looks like an exception is data.
+        let body = b"Code: 1. DB::Exception: foo\n";
+        assert_eq!(split_exception(body, Some("tag")), (&body[..], None));
+    }
+
+    #[test]
+    fn test_split_trailing_exception() {
+        let frame = b"__exception__\r\nxqrbqvoptnolbrdf\r\nCode: 395. DB::Exception: foo\n29 xqrbqvoptnolbrdf\r\n__exception__\r\n";
+
+        let mut body = b"\x01\x02\nCode: 1. DB::Exception: bar\n".to_vec();
+        body.extend_from_slice(frame);
+        let (data, message) = split_trailing_exception(&body, Some("xqrbqvoptnolbrdf"));
+        assert_eq!(data, b"\x01\x02\nCode: 1. DB::Exception: bar\n");
+        assert_eq!(message.unwrap(), "Code: 395. DB::Exception: foo");
+
+        assert_eq!(split_trailing_exception(&body, None), (&body[..], None));
+
+        // Not at the end, or plain text, is data.
+        let mut body = frame.to_vec();
+        body.push(0);
+        assert_eq!(
+            split_trailing_exception(&body, Some("xqrbqvoptnolbrdf")),
+            (&body[..], None)
+        );
+        let body = b"\x01Code: 1. DB::Exception: bar\n";
+        assert_eq!(
+            split_trailing_exception(body, Some("tag")),
+            (&body[..], None)
+        );
+    }
+
+    #[test]
+    fn test_is_exception_start_line() {
+        assert!(is_exception_marker_line(b"__exception__\r"));
+        assert!(is_exception_marker_line(b"__exception__"));
+        assert!(!is_exception_marker_line(b"__exception__ "));
+        assert!(is_exception_tag_line(b"tag\r", Some("tag")));
+        assert!(!is_exception_tag_line(b"other", Some("tag")));
+        assert!(!is_exception_tag_line(b"xqrbqvoptnolbrdf\r", None));
+        assert!(!is_exception_tag_line(b"", None));
+        assert!(ends_with_exception_frame(
+            b"29 tag\r\n__exception__\r\n",
+            "tag"
+        ));
+        assert!(!ends_with_exception_frame(
+            b"29 tag\r\n__exception__",
+            "tag"
+        ));
+
+        assert!(!is_exception_start_line(b"__exception__", None));
+        assert!(is_exception_start_line(
+            b"Code: 60. DB::Exception: Table default.t doesn't exist",
+            None
+        ));
+        assert!(!is_exception_start_line(
+            b"Code: 60. DB::Exception: Table default.t doesn't exist",
+            Some("tag")
+        ));
+        assert!(!is_exception_start_line(b"[1, \"a\"]", None));
+    }
+
+    #[cfg(feature = "with-json")]
+    #[test]
+    fn test_deserialize_checked() {
+        use crate::output::{GeneralJsonCompactEachRowOutput, Output as _, OutputError};
+
+        let output = GeneralJsonCompactEachRowOutput::new(vec!["id".to_owned()]);
+
+        let (rows, _) = output.deserialize_checked(b"[1]\n[2]\n", None).unwrap();
+        assert_eq!(rows.len(), 2);
+
+        let body = b"[1]\nCode: 395. DB::Exception: foo\n";
+        assert!(matches!(
+            output.deserialize_checked(body, None),
+            Err(OutputError::Exception(message)) if message == "Code: 395. DB::Exception: foo"
+        ));
+        assert!(matches!(
+            output.deserialize_checked(body, Some("tag")),
+            Err(OutputError::Format(_))
+        ));
+    }
+}
@@ -19,6 +19,13 @@ pub enum FormatName {
     #[strum(serialize = "TSVWithNamesAndTypes")]
     TsvWithNamesAndTypes,
     //
+    #[strum(serialize = "CSV")]
+    Csv,
+    #[strum(serialize = "CSVWithNames")]
+    CsvWithNames,
+    #[strum(serialize = "CSVWithNamesAndTypes")]
+    CsvWithNamesAndTypes,
+    //
     #[strum(serialize = "JSONEachRow")]
     JsonEachRow,
     #[strum(serialize = "JSONStringsEachRow")]
@@ -39,4 +46,33 @@ pub enum FormatName {
     JsonCompactEachRowWithNames,
     #[strum(serialize = "JSONCompactStringsEachRowWithNames")]
     JsonCompactStringsEachRowWithNames,
+    #[strum(serialize = "JSONColumns")]
+    JsonColumns,
+    //
+    #[strum(serialize = "RowBinary")]
+    RowBinary,
+    #[strum(serialize = "RowBinaryWithNamesAndTypes")]
+    RowBinaryWithNamesAndTypes,
+    //
+    #[strum(serialize = "Native")]
+    Native,
+}
+
+impl FormatName {
+    /// Whether data serialized in parts can be concatenated into one body,
+    /// i.e. the format has no header and is not a single document.
+    pub fn is_concatenable(&self) -> bool {
+        matches!(
+            self,
+            Self::Tsv
+                | Self::TsvRaw
+                | Self::Csv
+                | Self::JsonEachRow
+                | Self::JsonStringsEachRow
+                | Self::JsonCompactEachRow
+                | Self::JsonCompactStringsEachRow
+                | Self::RowBinary
+                | Self::Native
+        )
+    }
 }
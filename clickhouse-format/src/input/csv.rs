@@ -0,0 +1,696 @@
+use core::fmt;
+
+use serde::{
+    Serialize,
+    ser::{self, Impossible},
+};
+
+use crate::{
+    csv_options::{CsvOptions, CsvQuoteStyle},
+    format_name::FormatName,
+    input::Input,
+};
+
+/// The fields of `T` are written in order, one per column.
+/// Nested values like Array or Tuple must be given as strings in the ClickHouse text form.
+pub struct CsvInput<T> {
+    rows: Vec<T>,
+    options: CsvOptions,
+}
+impl<T> CsvInput<T> {
+    pub fn new(rows: Vec<T>) -> Self {
+        Self {
+            rows,
+            options: CsvOptions::default(),
+        }
+    }
+
+    pub fn set_options(&mut self, options: CsvOptions) -> &mut Self {
+        self.options = options;
+        self
+    }
+}
+impl<T> From<Vec<T>> for CsvInput<T> {
+    fn from(rows: Vec<T>) -> Self {
+        Self::new(rows)
+    }
+}
+
+impl<T> Input for CsvInput<T>
+where
+    T: Serialize,
+{
+    type Error = CsvInputError;
+
+    fn format_name() -> FormatName {
+        FormatName::Csv
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Self::Error> {
+        let mut buf = vec![];
+
+        for row in &self.rows {
+            write_row(&mut buf, row, &self.options)?;
+        }
+
+        Ok(buf)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CsvInputError {
+    #[error("Custom {0}")]
+    Custom(String),
+    #[error("Unsupported {0}")]
+    Unsupported(&'static str),
+    #[error("NamesMismatch {0:?}")]
+    NamesMismatch(Vec<String>),
+}
+
+impl ser::Error for CsvInputError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+pub(crate) fn write_row<T>(
+    buf: &mut Vec<u8>,
+    row: &T,
+    options: &CsvOptions,
+) -> Result<(), CsvInputError>
+where
+    T: Serialize + ?Sized,
+{
+    let (fields, _) = serialize_row(row)?;
+
+    write_fields(buf, &fields, options);
+    Ok(())
+}
+
+/// Like `write_row`, but the keys of a struct or map row must be `names`, in order.
+pub(crate) fn write_row_with_names<'a, T>(
+    buf: &mut Vec<u8>,
+    row: &T,
+    names: impl Iterator<Item = &'a str>,
+    options: &CsvOptions,
+) -> Result<(), CsvInputError>
+where
+    T: Serialize + ?Sized,
+{
+    let (fields, keys) = serialize_row(row)?;
+    if !keys.is_empty() && !keys.iter().map(String::as_str).eq(names) {
+        return Err(CsvInputError::NamesMismatch(keys));
+    }
+
+    write_fields(buf, &fields, options);
+    Ok(())
+}
+
+/// The keys of a struct or map row, in order. Empty for a tuple or seq row.
+pub(crate) fn get_row_keys<T>(row: &T) -> Result<Vec<String>, CsvInputError>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_row(row).map(|(_, keys)| keys)
+}
+
+fn serialize_row<T>(row: &T) -> Result<(Vec<Field>, Vec<String>), CsvInputError>
+where
+    T: Serialize + ?Sized,
+{
+    let mut fields = vec![];
+    let mut keys = vec![];
+    row.serialize(RowSerializer {
+        fields: &mut fields,
+        keys: &mut keys,
+    })?;
+
+    Ok((fields, keys))
+}
+
+pub(crate) fn write_header<'a>(
+    buf: &mut Vec<u8>,
+    header: impl Iterator<Item = &'a str>,
+    options: &CsvOptions,
+) {
+    let fields = header
+        .map(|x| Field::Text(x.to_owned()))
+        .collect::<Vec<_>>();
+
+    write_fields(buf, &fields, options);
+}
+
+fn write_fields(buf: &mut Vec<u8>, fields: &[Field], options: &CsvOptions) {
+    let delimiter = options.get_delimiter();
+    let null = options.get_null_representation();
+
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            buf.push(delimiter);
+        }
+
+        let (value, quote) = match field {
+            // Never quoted, the server would read it as a string.
+            Field::Null => {
+                buf.extend_from_slice(null.as_bytes());
+                continue;
+            }
+            Field::Numeric(value) => (
+                value,
+                matches!(options.get_quote_style(), CsvQuoteStyle::Always),
+            ),
+            Field::Text(value) => (
+                value,
+                match options.get_quote_style() {
+                    CsvQuoteStyle::Always | CsvQuoteStyle::NonNumeric => true,
+                    CsvQuoteStyle::Never => false,
+                    CsvQuoteStyle::Necessary => {
+                        value == null
+                            || value.starts_with([' ', '\t'])
+                            || value.ends_with([' ', '\t'])
+                            || value
+                                .bytes()
+                                .any(|b| matches!(b, b'"' | b'\r' | b'\n') || b == delimiter)
+                    }
+                },
+            ),
+        };
+
+        if quote {
+            buf.push(b'"');
+            buf.extend_from_slice(value.replace('"', r#""""#).as_bytes());
+            buf.push(b'"');
+        } else {
+            buf.extend_from_slice(value.as_bytes());
+        }
+    }
+    buf.push(b'\n');
+}
+
+enum Field {
+    Null,
+    Numeric(String),
+    Text(String),
+}
+
+// Flattens the top level struct, tuple, seq or map into fields, and keeps the keys.
+struct RowSerializer<'a> {
+    fields: &'a mut Vec<Field>,
+    keys: &'a mut Vec<String>,
+}
+
+impl RowSerializer<'_> {
+    fn push<T>(&mut self, value: &T) -> Result<(), CsvInputError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.fields.push(value.serialize(FieldSerializer)?);
+        Ok(())
+    }
+}
+
+macro_rules! serialize_as_field {
+    ($($method:ident $ty:ty),*) => {
+        $(
+            fn $method(mut self, v: $ty) -> Result<(), CsvInputError> {
+                self.push(&v)
+            }
+        )*
+    };
+}
+
+impl<'a> ser::Serializer for RowSerializer<'a> {
+    type Ok = ();
+    type Error = CsvInputError;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Impossible<(), CsvInputError>;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), CsvInputError>;
+
+    serialize_as_field! {
+        serialize_bool bool,
+        serialize_i8 i8, serialize_i16 i16, serialize_i32 i32, serialize_i64 i64, serialize_i128 i128,
+        serialize_u8 u8, serialize_u16 u16, serialize_u32 u32, serialize_u64 u64, serialize_u128 u128,
+        serialize_f32 f32, serialize_f64 f64, serialize_char char, serialize_str &str
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), CsvInputError> {
+        self.fields.push(FieldSerializer.serialize_bytes(v)?);
+        Ok(())
+    }
+
+    fn serialize_none(mut self) -> Result<(), CsvInputError> {
+        self.push(&())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), CsvInputError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(mut self) -> Result<(), CsvInputError> {
+        self.push(&())
+    }
+
+    fn serialize_unit_struct(mut self, _name: &'static str) -> Result<(), CsvInputError> {
+        self.push(&())
+    }
+
+    fn serialize_unit_variant(
+        mut self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), CsvInputError> {
+        self.push(variant)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), CsvInputError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), CsvInputError>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(CsvInputError::Unsupported("enum"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, CsvInputError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, CsvInputError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, CsvInputError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, CsvInputError> {
+        Err(CsvInputError::Unsupported("enum"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, CsvInputError> {
+        Ok(self)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, CsvInputError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, CsvInputError> {
+        Err(CsvInputError::Unsupported("enum"))
+    }
+}
+
+impl ser::SerializeSeq for RowSerializer<'_> {
+    type Ok = ();
+    type Error = CsvInputError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), CsvInputError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<(), CsvInputError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for RowSerializer<'_> {
+    type Ok = ();
+    type Error = CsvInputError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), CsvInputError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<(), CsvInputError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for RowSerializer<'_> {
+    type Ok = ();
+    type Error = CsvInputError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), CsvInputError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<(), CsvInputError> {
+        Ok(())
+    }
+}
+
+// The values are written in iteration order.
+impl ser::SerializeMap for RowSerializer<'_> {
+    type Ok = ();
+    type Error = CsvInputError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), CsvInputError>
+    where
+        T: Serialize + ?Sized,
+    {
+        match key.serialize(FieldSerializer)? {
+            Field::Numeric(key) | Field::Text(key) => self.keys.push(key),
+            Field::Null => return Err(CsvInputError::Unsupported("null key")),
+        }
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), CsvInputError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<(), CsvInputError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for RowSerializer<'_> {
+    type Ok = ();
+    type Error = CsvInputError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), CsvInputError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.keys.push(key.to_owned());
+        self.push(value)
+    }
+
+    fn end(self) -> Result<(), CsvInputError> {
+        Ok(())
+    }
+}
+
+struct FieldSerializer;
+
+macro_rules! serialize_numeric {
+    ($($method:ident $ty:ty),*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<Field, CsvInputError> {
+                Ok(Field::Numeric(v.to_string()))
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for FieldSerializer {
+    type Ok = Field;
+    type Error = CsvInputError;
+
+    type SerializeSeq = Impossible<Field, CsvInputError>;
+    type SerializeTuple = Impossible<Field, CsvInputError>;
+    type SerializeTupleStruct = Impossible<Field, CsvInputError>;
+    type SerializeTupleVariant = Impossible<Field, CsvInputError>;
+    type SerializeMap = Impossible<Field, CsvInputError>;
+    type SerializeStruct = Impossible<Field, CsvInputError>;
+    type SerializeStructVariant = Impossible<Field, CsvInputError>;
+
+    serialize_numeric! {
+        serialize_bool bool,
+        serialize_i8 i8, serialize_i16 i16, serialize_i32 i32, serialize_i64 i64, serialize_i128 i128,
+        serialize_u8 u8, serialize_u16 u16, serialize_u32 u32, serialize_u64 u64, serialize_u128 u128,
+        serialize_f32 f32, serialize_f64 f64
+    }
+
+    fn serialize_char(self, v: char) -> Result<Field, CsvInputError> {
+        Ok(Field::Text(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Field, CsvInputError> {
+        Ok(Field::Text(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Field, CsvInputError> {
+        core::str::from_utf8(v)
+            .map(|x| Field::Text(x.to_owned()))
+            .map_err(|_| CsvInputError::Unsupported("non UTF-8 bytes"))
+    }
+
+    fn serialize_none(self) -> Result<Field, CsvInputError> {
+        Ok(Field::Null)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Field, CsvInputError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Field, CsvInputError> {
+        Ok(Field::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Field, CsvInputError> {
+        Ok(Field::Null)
+    }
+
+    // Enum8/Enum16 accept the names.
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Field, CsvInputError> {
+        Ok(Field::Text(variant.to_owned()))
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Field, CsvInputError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Field, CsvInputError>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(CsvInputError::Unsupported("enum"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, CsvInputError> {
+        Err(CsvInputError::Unsupported("nested seq"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, CsvInputError> {
+        Err(CsvInputError::Unsupported("nested tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, CsvInputError> {
+        Err(CsvInputError::Unsupported("nested tuple"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, CsvInputError> {
+        Err(CsvInputError::Unsupported("enum"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, CsvInputError> {
+        Err(CsvInputError::Unsupported("nested map"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, CsvInputError> {
+        Err(CsvInputError::Unsupported("nested struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, CsvInputError> {
+        Err(CsvInputError::Unsupported("enum"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{fs, path::PathBuf};
+
+    use crate::{
+        output::{CsvOutput, Output as _},
+        test_helpers::{TEST_STRINGS_ROW_1, TEST_STRINGS_ROW_2},
+    };
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        let file_path = PathBuf::new().join("tests/files/CSV.csv");
+        let content = fs::read_to_string(&file_path)?;
+
+        assert_eq!(
+            CsvInput::<()>::format_name(),
+            file_path
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .parse()
+                .unwrap()
+        );
+
+        let mut input = CsvInput::new(vec![&*TEST_STRINGS_ROW_1, &*TEST_STRINGS_ROW_2]);
+        let bytes = input.serialize()?;
+        assert_eq!(bytes, content.replace(r#""{}""#, "{}").as_bytes());
+
+        input.set_options(
+            CsvOptions::new()
+                .set_quote_style(CsvQuoteStyle::NonNumeric)
+                .to_owned(),
+        );
+        assert_eq!(input.serialize()?, content.as_bytes());
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_options() -> Result<(), Box<dyn std::error::Error>> {
+        #[derive(Serialize)]
+        enum Level {
+            Low,
+        }
+
+        let rows = vec![
+            (1_u8, Some("a;\"b\""), Level::Low, 1.5_f64, true),
+            (2, None, Level::Low, -0.25, false),
+        ];
+
+        let mut input = CsvInput::new(rows);
+        input.set_options(CsvOptions::new().set_delimiter(b';').to_owned());
+        assert_eq!(
+            input.serialize()?,
+            b"1;\"a;\"\"b\"\"\";Low;1.5;true\n2;\\N;Low;-0.25;false\n"
+        );
+
+        input.set_options(
+            CsvOptions::new()
+                .set_quote_style(CsvQuoteStyle::Always)
+                .set_null_representation("NULL")
+                .to_owned(),
+        );
+        assert_eq!(
+            input.serialize()?,
+            b"\"1\",\"a;\"\"b\"\"\",\"Low\",\"1.5\",\"true\"\n\"2\",NULL,\"Low\",\"-0.25\",\"false\"\n"
+        );
+
+        let (rows, _) = CsvOutput::<(u8, Option<String>)>::new()
+            .set_options(
+                CsvOptions::new()
+                    .set_delimiter(b';')
+                    .set_null_representation("NULL")
+                    .to_owned(),
+            )
+            .deserialize(
+                &CsvInput::new(vec![
+                    (1_u8, Some("x;y")),
+                    (2, None),
+                    (3, Some("NULL")),
+                    (4, Some("")),
+                ])
+                .set_options(
+                    CsvOptions::new()
+                        .set_delimiter(b';')
+                        .set_null_representation("NULL")
+                        .to_owned(),
+                )
+                .serialize()?,
+            )?;
+        assert_eq!(
+            rows,
+            vec![
+                (1, Some("x;y".into())),
+                (2, None),
+                (3, Some("NULL".into())),
+                (4, Some("".into()))
+            ]
+        );
+
+        assert!(matches!(
+            CsvInput::new(vec![(1_u8, vec![1_u8])]).serialize(),
+            Err(CsvInputError::Unsupported(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+use serde::Serialize;
+
+use crate::{csv_options::CsvOptions, format_name::FormatName, input::Input};
+
+use super::csv::{CsvInputError, get_row_keys, write_header, write_row_with_names};
+
+/// `T` is written in the order of its own fields, so `names` must be its keys in that order when
+/// it is a struct or map, `NamesMismatch` is returned otherwise.
+/// Rows from `From<Vec<T>>` have no `names`, the keys of the first row are written instead.
+pub struct CsvWithNamesInput<T> {
+    names: Vec<String>,
+    rows: Vec<T>,
+    options: CsvOptions,
+}
+impl<T> CsvWithNamesInput<T> {
+    pub fn new(names: Vec<String>, rows: Vec<T>) -> Self {
+        Self {
+            names,
+            rows,
+            options: CsvOptions::default(),
+        }
+    }
+
+    pub fn set_options(&mut self, options: CsvOptions) -> &mut Self {
+        self.options = options;
+        self
+    }
+}
+impl<T> From<Vec<T>> for CsvWithNamesInput<T> {
+    fn from(rows: Vec<T>) -> Self {
+        Self::new(vec![], rows)
+    }
+}
+
+impl<T> Input for CsvWithNamesInput<T>
+where
+    T: Serialize,
+{
+    type Error = CsvInputError;
+
+    fn format_name() -> FormatName {
+        FormatName::CsvWithNames
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Self::Error> {
+        let mut buf = vec![];
+
+        let keys;
+        let names = match self.rows.first() {
+            Some(row) if self.names.is_empty() => {
+                keys = get_row_keys(row)?;
+                if keys.is_empty() {
+                    return Err(CsvInputError::Unsupported("names of a tuple or seq row"));
+                }
+                &keys
+            }
+            _ => &self.names,
+        };
+
+        write_header(&mut buf, names.iter().map(String::as_str), &self.options);
+        for row in &self.rows {
+            write_row_with_names(
+                &mut buf,
+                row,
+                names.iter().map(String::as_str),
+                &self.options,
+            )?;
+        }
+
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{fs, path::PathBuf};
+
+    use crate::{
+        csv_options::CsvQuoteStyle,
+        test_helpers::{TEST_STRINGS_ROW_1, TEST_STRINGS_ROW_2},
+    };
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        let file_path = PathBuf::new().join("tests/files/CSVWithNames.csv");
+        let content = fs::read_to_string(&file_path)?;
+
+        assert_eq!(
+            CsvWithNamesInput::<()>::format_name(),
+            file_path
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .parse()
+                .unwrap()
+        );
+
+        let mut input = CsvWithNamesInput::new(
+            vec![
+                "array1".into(),
+                "array2".into(),
+                "tuple1".into(),
+                "tuple2".into(),
+                "map1".into(),
+            ],
+            vec![&*TEST_STRINGS_ROW_1, &*TEST_STRINGS_ROW_2],
+        );
+        input.set_options(
+            CsvOptions::new()
+                .set_quote_style(CsvQuoteStyle::NonNumeric)
+                .to_owned(),
+        );
+        assert_eq!(input.serialize()?, content.as_bytes());
+
+        let mut input = CsvWithNamesInput::from(vec![&*TEST_STRINGS_ROW_1, &*TEST_STRINGS_ROW_2]);
+        input.set_options(
+            CsvOptions::new()
+                .set_quote_style(CsvQuoteStyle::NonNumeric)
+                .to_owned(),
+        );
+        assert_eq!(input.serialize()?, content.as_bytes());
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_names_mismatch() {
+        #[derive(Serialize)]
+        struct Row {
+            id: u8,
+            name: &'static str,
+        }
+
+        let input = CsvWithNamesInput::new(
+            vec!["name".into(), "id".into()],
+            vec![Row { id: 1, name: "a" }],
+        );
+        assert!(matches!(
+            input.serialize(),
+            Err(CsvInputError::NamesMismatch(keys)) if keys == ["id", "name"]
+        ));
+
+        // Tuples have no keys to check.
+        let input = CsvWithNamesInput::new(vec!["name".into(), "id".into()], vec![("a", 1_u8)]);
+        assert_eq!(input.serialize().unwrap(), b"name,id\na,1\n");
+        assert!(
+            CsvWithNamesInput::from(vec![("a", 1_u8)])
+                .serialize()
+                .is_err()
+        );
+    }
+}
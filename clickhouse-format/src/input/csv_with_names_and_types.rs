@@ -0,0 +1,128 @@
+use serde::Serialize;
+
+use crate::{csv_options::CsvOptions, format_name::FormatName, input::Input};
+
+use super::csv::{CsvInputError, write_header, write_row_with_names};
+
+/// `T` is written in the order of its own fields, so the names of `columns`, given as name and
+/// type, must be its keys in that order when it is a struct or map, `NamesMismatch` is returned
+/// otherwise.
+pub struct CsvWithNamesAndTypesInput<T> {
+    columns: Vec<(String, String)>,
+    rows: Vec<T>,
+    options: CsvOptions,
+}
+impl<T> CsvWithNamesAndTypesInput<T> {
+    pub fn new(columns: Vec<(String, String)>, rows: Vec<T>) -> Self {
+        Self {
+            columns,
+            rows,
+            options: CsvOptions::default(),
+        }
+    }
+
+    pub fn set_options(&mut self, options: CsvOptions) -> &mut Self {
+        self.options = options;
+        self
+    }
+}
+
+impl<T> Input for CsvWithNamesAndTypesInput<T>
+where
+    T: Serialize,
+{
+    type Error = CsvInputError;
+
+    fn format_name() -> FormatName {
+        FormatName::CsvWithNamesAndTypes
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Self::Error> {
+        let mut buf = vec![];
+
+        write_header(
+            &mut buf,
+            self.columns.iter().map(|(name, _)| name.as_str()),
+            &self.options,
+        );
+        write_header(
+            &mut buf,
+            self.columns.iter().map(|(_, type_)| type_.as_str()),
+            &self.options,
+        );
+        for row in &self.rows {
+            write_row_with_names(
+                &mut buf,
+                row,
+                self.columns.iter().map(|(name, _)| name.as_str()),
+                &self.options,
+            )?;
+        }
+
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{fs, path::PathBuf};
+
+    use crate::{
+        csv_options::CsvQuoteStyle,
+        output::{CsvWithNamesAndTypesOutput, Output as _},
+        test_helpers::{TEST_STRINGS_ROW_1, TEST_STRINGS_ROW_2, TestStringsRow},
+    };
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        let file_path = PathBuf::new().join("tests/files/CSVWithNamesAndTypes.csv");
+        let content = fs::read_to_string(&file_path)?;
+
+        assert_eq!(
+            CsvWithNamesAndTypesInput::<()>::format_name(),
+            file_path
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .parse()
+                .unwrap()
+        );
+
+        let mut input = CsvWithNamesAndTypesInput::new(
+            vec![
+                ("array1".into(), "Array(UInt8)".into()),
+                ("array2".into(), "Array(String)".into()),
+                ("tuple1".into(), "Tuple(UInt8, String)".into()),
+                ("tuple2".into(), "Tuple(UInt8, Nullable(String))".into()),
+                ("map1".into(), "Map(String, String)".into()),
+            ],
+            vec![&*TEST_STRINGS_ROW_1, &*TEST_STRINGS_ROW_2],
+        );
+        input.set_options(
+            CsvOptions::new()
+                .set_quote_style(CsvQuoteStyle::NonNumeric)
+                .to_owned(),
+        );
+        let bytes = input.serialize()?;
+        assert_eq!(bytes, content.as_bytes());
+
+        let (rows, info) =
+            CsvWithNamesAndTypesOutput::<TestStringsRow>::new().deserialize(&bytes)?;
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows.last().unwrap(), &*TEST_STRINGS_ROW_2);
+        assert_eq!(info.get("tuple1"), Some(&"Tuple(UInt8, String)".to_owned()));
+
+        let input = CsvWithNamesAndTypesInput::new(
+            vec![("array1".into(), "Array(UInt8)".into())],
+            vec![&*TEST_STRINGS_ROW_1],
+        );
+        assert!(matches!(
+            input.serialize(),
+            Err(CsvInputError::NamesMismatch(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use serde::{Serialize, ser::Error as _};
+
+use crate::{format_name::FormatName, input::Input};
+
+use super::json_compact_each_row::write_row;
+
+/// One `(name, values)` per column, every column must have the same number of values.
+pub struct JsonColumnsInput<T> {
+    columns: Vec<(String, Vec<T>)>,
+}
+impl<T> JsonColumnsInput<T> {
+    pub fn new(columns: Vec<(String, Vec<T>)>) -> Self {
+        Self { columns }
+    }
+}
+impl<T> From<Vec<(String, Vec<T>)>> for JsonColumnsInput<T> {
+    fn from(columns: Vec<(String, Vec<T>)>) -> Self {
+        Self::new(columns)
+    }
+}
+
+impl<T> Input for JsonColumnsInput<T>
+where
+    T: Serialize,
+{
+    type Error = serde_json::Error;
+
+    fn format_name() -> FormatName {
+        FormatName::JsonColumns
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Self::Error> {
+        if let Some((first_name, first)) = self.columns.first()
+            && let Some((name, values)) = self
+                .columns
+                .iter()
+                .find(|(_, values)| values.len() != first.len())
+        {
+            return Err(serde_json::Error::custom(format!(
+                "column {name} has {} values, but column {first_name} has {}",
+                values.len(),
+                first.len()
+            )));
+        }
+
+        let mut buf = vec![];
+
+        buf.extend_from_slice(b"{\n");
+        for (i, (name, values)) in self.columns.iter().enumerate() {
+            buf.push(b'\t');
+            serde_json::to_writer(&mut buf, name)?;
+            buf.extend_from_slice(b": ");
+            write_row(&mut buf, values)?;
+            // write_row ends with a line break, the separator goes before it.
+            if i < self.columns.len() - 1 {
+                buf.pop();
+                buf.extend_from_slice(b",\n");
+            }
+        }
+        buf.extend_from_slice(b"}\n");
+
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{fs, path::PathBuf};
+
+    use indexmap::IndexMap;
+    use serde_json::{Value, json};
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        let file_path = PathBuf::new().join("tests/files/JSONColumns.txt");
+        let content = fs::read_to_string(&file_path)?;
+
+        assert_eq!(
+            JsonColumnsInput::<()>::format_name(),
+            file_path
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .parse()
+                .unwrap()
+        );
+
+        let columns: Vec<(String, Vec<Value>)> = vec![
+            ("array1".into(), vec![json!([1, 2]), json!([3, 4])]),
+            ("array2".into(), vec![json!(["a", "b"]), json!(["c", "d"])]),
+            ("tuple1".into(), vec![json!([1, "a"]), json!([2, "b"])]),
+            ("tuple2".into(), vec![json!([1, null]), json!([2, "b"])]),
+            (
+                "map1".into(),
+                vec![json!({"1": "Ready", "2": "Steady", "3": "Go"}), json!({})],
+            ),
+        ];
+        let bytes = JsonColumnsInput::new(columns.clone()).serialize()?;
+        assert_eq!(bytes, content.as_bytes());
+
+        let parsed: IndexMap<String, Vec<Value>> = serde_json::from_slice(&bytes)?;
+        assert_eq!(parsed.into_iter().collect::<Vec<_>>(), columns);
+
+        assert_eq!(JsonColumnsInput::<()>::new(vec![]).serialize()?, b"{\n}\n");
+
+        let err = JsonColumnsInput::new(vec![
+            ("id".to_owned(), vec![1, 2]),
+            ("name".to_owned(), vec![1]),
+        ])
+        .serialize()
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "column name has 1 values, but column id has 2"
+        );
+
+        Ok(())
+    }
+}
@@ -11,6 +11,11 @@ impl<T> JsonCompactEachRowInput<T> {
         Self { rows }
     }
 }
+impl<T> From<Vec<Vec<T>>> for JsonCompactEachRowInput<T> {
+    fn from(rows: Vec<Vec<T>>) -> Self {
+        Self::new(rows)
+    }
+}
 
 impl<T> Input for JsonCompactEachRowInput<T>
 where
@@ -24,30 +29,34 @@ where
 
     fn serialize(&self) -> Result<Vec<u8>, Self::Error> {
         let mut buf = vec![];
-        let mut ser_buf = Vec::with_capacity(128);
 
         for row in &self.rows {
-            buf.push(b'[');
-            for (i, item) in row.iter().enumerate() {
-                ser_buf.clear();
-                let mut ser = Serializer::with_formatter(&mut ser_buf, CompactFormatter);
-                item.serialize(&mut ser)?;
-
-                buf.extend_from_slice(ser.into_inner());
-
-                if i < (row.len() - 1) {
-                    buf.extend_from_slice(b", ");
-                }
-            }
-            buf.push(b']');
-
-            buf.push(b'\n');
+            write_row(&mut buf, row)?;
         }
 
         Ok(buf)
     }
 }
 
+// Same layout as the server, `[v1, v2]` with compact values.
+pub(crate) fn write_row<T>(buf: &mut Vec<u8>, row: &[T]) -> Result<(), serde_json::Error>
+where
+    T: Serialize,
+{
+    buf.push(b'[');
+    for (i, item) in row.iter().enumerate() {
+        if i > 0 {
+            buf.extend_from_slice(b", ");
+        }
+        item.serialize(&mut Serializer::with_formatter(&mut *buf, CompactFormatter))?;
+    }
+    buf.push(b']');
+
+    buf.push(b'\n');
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,103 @@
+use serde::Serialize;
+
+use crate::{format_name::FormatName, input::Input};
+
+use super::json_compact_each_row::write_row;
+
+pub struct JsonCompactEachRowWithNamesInput<T> {
+    names: Vec<String>,
+    rows: Vec<Vec<T>>,
+}
+impl<T> JsonCompactEachRowWithNamesInput<T> {
+    pub fn new(names: Vec<String>, rows: Vec<Vec<T>>) -> Self {
+        Self { names, rows }
+    }
+}
+
+impl<T> Input for JsonCompactEachRowWithNamesInput<T>
+where
+    T: Serialize,
+{
+    type Error = serde_json::Error;
+
+    fn format_name() -> FormatName {
+        FormatName::JsonCompactEachRowWithNames
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Self::Error> {
+        let mut buf = vec![];
+
+        write_row(&mut buf, &self.names)?;
+        for row in &self.rows {
+            write_row(&mut buf, row)?;
+        }
+
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{fs, path::PathBuf};
+
+    use serde_json::{Value, json};
+
+    use crate::{
+        output::{JsonCompactEachRowWithNamesOutput, Output as _},
+        test_helpers::{TEST_ROW_1, TestRow},
+    };
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        let file_path = PathBuf::new().join("tests/files/JSONCompactEachRowWithNames.txt");
+        let content = fs::read_to_string(&file_path)?;
+
+        assert_eq!(
+            JsonCompactEachRowWithNamesInput::<()>::format_name(),
+            file_path
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .parse()
+                .unwrap()
+        );
+
+        let rows: Vec<Vec<Value>> = vec![
+            vec![
+                json!([1, 2]),
+                json!(["a", "b"]),
+                json!([1, "a"]),
+                json!([1, null]),
+                json!({"1": "Ready", "2": "Steady", "3": "Go"}),
+            ],
+            vec![
+                json!([3, 4]),
+                json!(["c", "d"]),
+                json!([2, "b"]),
+                json!([2, "b"]),
+                json!({}),
+            ],
+        ];
+        let bytes = JsonCompactEachRowWithNamesInput::new(
+            vec![
+                "array1".into(),
+                "array2".into(),
+                "tuple1".into(),
+                "tuple2".into(),
+                "map1".into(),
+            ],
+            rows,
+        )
+        .serialize()?;
+        assert_eq!(bytes, content.as_bytes());
+
+        let (rows, info) =
+            JsonCompactEachRowWithNamesOutput::<TestRow>::new().deserialize(&bytes)?;
+        assert_eq!(rows.first().unwrap(), &*TEST_ROW_1);
+        assert_eq!(info, vec!["array1", "array2", "tuple1", "tuple2", "map1"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use serde::Serialize;
+
+use crate::{format_name::FormatName, input::Input};
+
+use super::json_compact_each_row::write_row;
+
+pub struct JsonCompactEachRowWithNamesAndTypesInput<T> {
+    columns: Vec<(String, String)>,
+    rows: Vec<Vec<T>>,
+}
+impl<T> JsonCompactEachRowWithNamesAndTypesInput<T> {
+    pub fn new(columns: Vec<(String, String)>, rows: Vec<Vec<T>>) -> Self {
+        Self { columns, rows }
+    }
+}
+
+impl<T> Input for JsonCompactEachRowWithNamesAndTypesInput<T>
+where
+    T: Serialize,
+{
+    type Error = serde_json::Error;
+
+    fn format_name() -> FormatName {
+        FormatName::JsonCompactEachRowWithNamesAndTypes
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Self::Error> {
+        let mut buf = vec![];
+
+        let (names, types): (Vec<_>, Vec<_>) = self
+            .columns
+            .iter()
+            .map(|(name, type_)| (name, type_))
+            .unzip();
+        write_row(&mut buf, &names)?;
+        write_row(&mut buf, &types)?;
+        for row in &self.rows {
+            write_row(&mut buf, row)?;
+        }
+
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{fs, path::PathBuf};
+
+    use serde_json::{Value, json};
+
+    use crate::{
+        output::{JsonCompactEachRowWithNamesAndTypesOutput, Output as _},
+        test_helpers::{TEST_ROW_1, TEST_ROW_2, TestRow},
+    };
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        let file_path = PathBuf::new().join("tests/files/JSONCompactEachRowWithNamesAndTypes.txt");
+        let content = fs::read_to_string(&file_path)?;
+
+        assert_eq!(
+            JsonCompactEachRowWithNamesAndTypesInput::<()>::format_name(),
+            file_path
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .parse()
+                .unwrap()
+        );
+
+        let rows: Vec<Vec<Value>> = vec![
+            vec![
+                json!([1, 2]),
+                json!(["a", "b"]),
+                json!([1, "a"]),
+                json!([1, null]),
+                json!({"1": "Ready", "2": "Steady", "3": "Go"}),
+            ],
+            vec![
+                json!([3, 4]),
+                json!(["c", "d"]),
+                json!([2, "b"]),
+                json!([2, "b"]),
+                json!({}),
+            ],
+        ];
+        let bytes = JsonCompactEachRowWithNamesAndTypesInput::new(
+            vec![
+                ("array1".into(), "Array(UInt8)".into()),
+                ("array2".into(), "Array(String)".into()),
+                ("tuple1".into(), "Tuple(UInt8, String)".into()),
+                ("tuple2".into(), "Tuple(UInt8, Nullable(String))".into()),
+                ("map1".into(), "Map(String, String)".into()),
+            ],
+            rows,
+        )
+        .serialize()?;
+        assert_eq!(bytes, content.as_bytes());
+
+        let (rows, info) =
+            JsonCompactEachRowWithNamesAndTypesOutput::<TestRow>::new().deserialize(&bytes)?;
+        assert_eq!(rows.first().unwrap(), &*TEST_ROW_1);
+        assert_eq!(rows.last().unwrap(), &*TEST_ROW_2);
+        assert_eq!(info.get("map1"), Some(&"Map(String, String)".to_owned()));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use serde::Serialize;
+
+use crate::{format_name::FormatName, input::Input};
+
+pub struct JsonEachRowInput<T> {
+    rows: Vec<T>,
+}
+impl<T> JsonEachRowInput<T> {
+    pub fn new(rows: Vec<T>) -> Self {
+        Self { rows }
+    }
+}
+impl<T> From<Vec<T>> for JsonEachRowInput<T> {
+    fn from(rows: Vec<T>) -> Self {
+        Self::new(rows)
+    }
+}
+
+impl<T> Input for JsonEachRowInput<T>
+where
+    T: Serialize,
+{
+    type Error = serde_json::Error;
+
+    fn format_name() -> FormatName {
+        FormatName::JsonEachRow
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Self::Error> {
+        let mut buf = vec![];
+
+        for row in &self.rows {
+            serde_json::to_writer(&mut buf, row)?;
+            buf.push(b'\n');
+        }
+
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{fs, path::PathBuf};
+
+    use crate::{
+        output::{JsonEachRowOutput, Output as _},
+        test_helpers::{TEST_ROW_1, TEST_ROW_2, TestRow},
+    };
+
+    use serde_json::Value;
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        let file_path = PathBuf::new().join("tests/files/JSONEachRow.txt");
+        let content = fs::read_to_string(&file_path)?;
+
+        assert_eq!(
+            JsonEachRowInput::<()>::format_name(),
+            file_path
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .parse()
+                .unwrap()
+        );
+
+        let bytes = JsonEachRowInput::new(vec![&*TEST_ROW_1, &*TEST_ROW_2]).serialize()?;
+
+        // map1 is a HashMap, so compare values instead of bytes.
+        let lines = String::from_utf8(bytes.clone())?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<Value>, _>>()?;
+        let expected_lines = content
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<Value>, _>>()?;
+        assert_eq!(lines, expected_lines);
+
+        let (rows, _) = JsonEachRowOutput::<TestRow>::new().deserialize(&bytes)?;
+        assert_eq!(rows.first().unwrap(), &*TEST_ROW_1);
+        assert_eq!(rows.last().unwrap(), &*TEST_ROW_2);
+
+        Ok(())
+    }
+}
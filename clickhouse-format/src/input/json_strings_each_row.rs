@@ -0,0 +1,204 @@
+use core::fmt;
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{MapAccess, Visitor},
+    ser::{Error as _, SerializeMap as _},
+};
+use serde_json::Value;
+
+use crate::{format_name::FormatName, input::Input};
+
+/// `T` must be a struct or map. Numbers and bools are written as strings, nested values like
+/// Array or Tuple must be given as strings in the ClickHouse text form.
+pub struct JsonStringsEachRowInput<T> {
+    rows: Vec<T>,
+}
+impl<T> JsonStringsEachRowInput<T> {
+    pub fn new(rows: Vec<T>) -> Self {
+        Self { rows }
+    }
+}
+impl<T> From<Vec<T>> for JsonStringsEachRowInput<T> {
+    fn from(rows: Vec<T>) -> Self {
+        Self::new(rows)
+    }
+}
+
+impl<T> Input for JsonStringsEachRowInput<T>
+where
+    T: Serialize,
+{
+    type Error = serde_json::Error;
+
+    fn format_name() -> FormatName {
+        FormatName::JsonStringsEachRow
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Self::Error> {
+        let mut buf = vec![];
+
+        for row in &self.rows {
+            let Fields(fields) = serde_json::from_slice(&serde_json::to_vec(row)?)?;
+            let fields = fields
+                .into_iter()
+                .map(|(key, value)| stringify(value).map(|value| (key, value)))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            serde_json::to_writer(&mut buf, &Fields(fields))?;
+            buf.push(b'\n');
+        }
+
+        Ok(buf)
+    }
+}
+
+fn stringify(value: Value) -> Result<Value, serde_json::Error> {
+    match value {
+        Value::Null | Value::String(_) => Ok(value),
+        Value::Bool(v) => Ok(Value::String(v.to_string())),
+        Value::Number(v) => Ok(Value::String(v.to_string())),
+        Value::Array(_) | Value::Object(_) => Err(serde_json::Error::custom(
+            "nested value must be a string in the ClickHouse text form",
+        )),
+    }
+}
+
+// The fields of a row, in order, which `serde_json::Map` doesn't keep.
+struct Fields(Vec<(String, Value)>);
+
+impl<'de> Deserialize<'de> for Fields {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct FieldsVisitor;
+
+        impl<'de> Visitor<'de> for FieldsVisitor {
+            type Value = Fields;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a struct or map row")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut fields = vec![];
+                while let Some(field) = map.next_entry()? {
+                    fields.push(field);
+                }
+                Ok(Fields(fields))
+            }
+        }
+
+        deserializer.deserialize_map(FieldsVisitor)
+    }
+}
+
+impl Serialize for Fields {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (key, value) in &self.0 {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{collections::HashMap, fs, path::PathBuf};
+
+    use crate::{
+        output::{JsonStringsEachRowOutput, Output as _},
+        test_helpers::{TEST_STRINGS_ROW_1, TEST_STRINGS_ROW_2, TestStringsRow},
+    };
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        let file_path = PathBuf::new().join("tests/files/JSONStringsEachRow.txt");
+        let content = fs::read_to_string(&file_path)?;
+
+        assert_eq!(
+            JsonStringsEachRowInput::<()>::format_name(),
+            file_path
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .parse()
+                .unwrap()
+        );
+
+        let bytes = JsonStringsEachRowInput::new(vec![&*TEST_STRINGS_ROW_1, &*TEST_STRINGS_ROW_2])
+            .serialize()?;
+        assert_eq!(bytes, content.as_bytes());
+
+        let (rows, _) = JsonStringsEachRowOutput::<TestStringsRow>::new().deserialize(&bytes)?;
+        assert_eq!(rows.first().unwrap(), &*TEST_STRINGS_ROW_1);
+        assert_eq!(rows.last().unwrap(), &*TEST_STRINGS_ROW_2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_scalars() -> Result<(), Box<dyn std::error::Error>> {
+        #[derive(Serialize)]
+        struct Row {
+            id: u64,
+            score: f64,
+            is_active: bool,
+            name: Option<&'static str>,
+        }
+
+        let bytes = JsonStringsEachRowInput::new(vec![
+            Row {
+                id: 1,
+                score: 1.5,
+                is_active: true,
+                name: Some("a"),
+            },
+            Row {
+                id: u64::MAX,
+                score: -0.25,
+                is_active: false,
+                name: None,
+            },
+        ])
+        .serialize()?;
+        assert_eq!(
+            bytes,
+            format!(
+                "{}\n{}\n",
+                r#"{"id":"1","score":"1.5","is_active":"true","name":"a"}"#,
+                r#"{"id":"18446744073709551615","score":"-0.25","is_active":"false","name":null}"#
+            )
+            .as_bytes()
+        );
+
+        let (rows, _) = JsonStringsEachRowOutput::<HashMap<String, Option<String>>>::new()
+            .deserialize(&bytes)?;
+        assert_eq!(rows[0].get("id"), Some(&Some("1".to_owned())));
+        assert_eq!(rows[1].get("is_active"), Some(&Some("false".to_owned())));
+        assert_eq!(rows[1].get("name"), Some(&None));
+
+        assert!(
+            JsonStringsEachRowInput::new(vec![(1, "a")])
+                .serialize()
+                .is_err()
+        );
+        assert!(
+            JsonStringsEachRowInput::new(vec![HashMap::from([("array1", vec![1, 2])])])
+                .serialize()
+                .is_err()
+        );
+
+        Ok(())
+    }
+}
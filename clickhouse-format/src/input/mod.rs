@@ -1,10 +1,47 @@
 use crate::format_name::FormatName;
 
+#[cfg(feature = "with-csv")]
+pub mod csv;
+#[cfg(feature = "with-csv")]
+pub mod csv_with_names;
+#[cfg(feature = "with-csv")]
+pub mod csv_with_names_and_types;
+
+#[cfg(feature = "with-json")]
+pub mod json_columns;
 #[cfg(feature = "with-json")]
 pub mod json_compact_each_row;
+#[cfg(feature = "with-json")]
+pub mod json_compact_each_row_with_names;
+#[cfg(feature = "with-json")]
+pub mod json_compact_each_row_with_names_and_types;
+#[cfg(feature = "with-json")]
+pub mod json_each_row;
+#[cfg(feature = "with-json")]
+pub mod json_strings_each_row;
+
+#[cfg(feature = "with-native")]
+pub mod native;
+#[cfg(feature = "with-row-binary")]
+pub mod row_binary;
 
+#[cfg(feature = "with-native")]
+pub use self::native::NativeInput;
+#[cfg(feature = "with-row-binary")]
+pub use self::row_binary::RowBinaryInput;
+#[cfg(feature = "with-csv")]
+pub use self::{
+    csv::{CsvInput, CsvInputError},
+    csv_with_names::CsvWithNamesInput,
+    csv_with_names_and_types::CsvWithNamesAndTypesInput,
+};
 #[cfg(feature = "with-json")]
-pub use self::json_compact_each_row::JsonCompactEachRowInput;
+pub use self::{
+    json_columns::JsonColumnsInput, json_compact_each_row::JsonCompactEachRowInput,
+    json_compact_each_row_with_names::JsonCompactEachRowWithNamesInput,
+    json_compact_each_row_with_names_and_types::JsonCompactEachRowWithNamesAndTypesInput,
+    json_each_row::JsonEachRowInput, json_strings_each_row::JsonStringsEachRowInput,
+};
 
 pub trait Input {
     type Error: std::error::Error;
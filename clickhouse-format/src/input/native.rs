@@ -0,0 +1,100 @@
+use clickhouse_data_type::type_name::TypeName;
+use serde::Serialize;
+
+use crate::{
+    format_name::FormatName,
+    input::Input,
+    row_binary::{RowBinaryError, native::write_block},
+};
+
+/// The fields of `T` are written in order, one per column, and checked against the column types.
+pub struct NativeInput<T> {
+    columns: Vec<(String, TypeName)>,
+    rows: Vec<T>,
+}
+impl<T> NativeInput<T> {
+    pub fn new(columns: Vec<(String, TypeName)>, rows: Vec<T>) -> Self {
+        Self { columns, rows }
+    }
+}
+
+impl<T> Input for NativeInput<T>
+where
+    T: Serialize,
+{
+    type Error = RowBinaryError;
+
+    fn format_name() -> FormatName {
+        FormatName::Native
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Self::Error> {
+        let mut buf = vec![];
+        write_block(&mut buf, &self.columns, &self.rows)?;
+
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        output::{NativeOutput, Output as _},
+        test_helpers::{TEST_ROW_1, TEST_ROW_2, TestRow},
+    };
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(NativeInput::<()>::format_name(), "Native".parse().unwrap());
+
+        let columns = vec![
+            ("array1".to_owned(), "Array(UInt64)".parse()?),
+            ("array2".to_owned(), "Array(String)".parse()?),
+            ("tuple1".to_owned(), "Tuple(UInt64, String)".parse()?),
+            (
+                "tuple2".to_owned(),
+                "Tuple(UInt64, Nullable(String))".parse()?,
+            ),
+            ("map1".to_owned(), "Map(String, String)".parse()?),
+        ];
+        let bytes = NativeInput::new(columns, vec![&*TEST_ROW_1, &*TEST_ROW_2]).serialize()?;
+
+        let (rows, info) = NativeOutput::<TestRow>::new().deserialize(&bytes)?;
+        assert_eq!(
+            rows.iter().collect::<Vec<_>>(),
+            vec![&*TEST_ROW_1, &*TEST_ROW_2]
+        );
+        assert_eq!(
+            info.get("tuple2").unwrap(),
+            "Tuple(UInt64, Nullable(String))"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_columns_mismatch() -> Result<(), Box<dyn std::error::Error>> {
+        let columns = vec![
+            ("id".to_owned(), "UInt32".parse()?),
+            ("name".to_owned(), "Nullable(String)".parse()?),
+        ];
+
+        assert!(
+            NativeInput::new(columns.clone(), vec![(1_u32, Some("a")), (2, None)])
+                .serialize()
+                .is_ok()
+        );
+        for err in [
+            NativeInput::new(columns.clone(), vec![("1", Some("a"))]).serialize(),
+            NativeInput::new(columns.clone(), vec![(1_u64, Some("a"))]).serialize(),
+            NativeInput::new(columns.clone(), vec![(1_u32, "a")]).serialize(),
+            NativeInput::new(columns.clone(), vec![(1_u32,)]).serialize(),
+        ] {
+            assert!(matches!(err, Err(RowBinaryError::ColumnsMismatch(_))));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use serde::Serialize;
+
+use crate::{
+    format_name::FormatName,
+    input::Input,
+    row_binary::{RowBinaryError, to_writer},
+};
+
+pub struct RowBinaryInput<T> {
+    rows: Vec<T>,
+}
+impl<T> RowBinaryInput<T> {
+    pub fn new(rows: Vec<T>) -> Self {
+        Self { rows }
+    }
+}
+impl<T> From<Vec<T>> for RowBinaryInput<T> {
+    fn from(rows: Vec<T>) -> Self {
+        Self::new(rows)
+    }
+}
+
+impl<T> Input for RowBinaryInput<T>
+where
+    T: Serialize,
+{
+    type Error = RowBinaryError;
+
+    fn format_name() -> FormatName {
+        FormatName::RowBinary
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Self::Error> {
+        let mut buf = vec![];
+
+        for row in &self.rows {
+            to_writer(&mut buf, row)?;
+        }
+
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        output::{Output as _, RowBinaryOutput},
+        test_helpers::{TEST_ROW_1, TEST_ROW_2, TestRow},
+    };
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(
+            RowBinaryInput::<()>::format_name(),
+            "RowBinary".parse().unwrap()
+        );
+
+        let bytes = RowBinaryInput::new(vec![&*TEST_ROW_1, &*TEST_ROW_2]).serialize()?;
+
+        let (rows, _) = RowBinaryOutput::<TestRow>::new().deserialize(&bytes)?;
+        assert_eq!(
+            rows.iter().collect::<Vec<_>>(),
+            vec![&*TEST_ROW_1, &*TEST_ROW_2]
+        );
+
+        Ok(())
+    }
+}
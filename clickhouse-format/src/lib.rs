@@ -1,6 +1,12 @@
+#[cfg(feature = "with-csv")]
+pub mod csv_options;
+pub mod exception;
 pub mod format_name;
 pub mod input;
 pub mod output;
+pub mod progress;
+#[cfg(feature = "with-row-binary")]
+pub mod row_binary;
 
 #[cfg(test)]
 pub(crate) mod test_helpers;
@@ -0,0 +1,455 @@
+use core::{fmt, marker::PhantomData, str::FromStr};
+use std::io::Error as IoError;
+
+use csv::{ReaderBuilder, StringRecord, StringRecordsIntoIter};
+use indexmap::IndexMap;
+use serde::{
+    de::{
+        DeserializeOwned, Deserializer, Error as _, IntoDeserializer, Visitor,
+        value::{Error as DeError, MapDeserializer, SeqDeserializer},
+    },
+    forward_to_deserialize_any,
+};
+
+use crate::{csv_options::CsvOptions, format_name::FormatName};
+
+use super::{Output, OutputResult, StreamOutput};
+
+pub struct CsvOutput<T> {
+    names: Option<Vec<String>>,
+    types: Option<Vec<String>>,
+    options: CsvOptions,
+    phantom: PhantomData<T>,
+}
+impl<T> Default for CsvOutput<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> CsvOutput<T> {
+    pub fn new() -> Self {
+        Self::inner_new(None, None, CsvOptions::default())
+    }
+    pub fn with_names(names: Vec<String>) -> Self {
+        Self::inner_new(Some(names), None, CsvOptions::default())
+    }
+    pub fn with_names_and_types(names: Vec<String>, types: Vec<String>) -> Self {
+        Self::inner_new(Some(names), Some(types), CsvOptions::default())
+    }
+    pub(crate) fn inner_new(
+        names: Option<Vec<String>>,
+        types: Option<Vec<String>>,
+        options: CsvOptions,
+    ) -> Self {
+        Self {
+            names,
+            types,
+            options,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn set_options(&mut self, options: CsvOptions) -> &mut Self {
+        self.options = options;
+        self
+    }
+}
+
+impl<T> Output for CsvOutput<T>
+where
+    T: DeserializeOwned,
+{
+    type Row = T;
+    type Info = Option<IndexMap<String, String>>;
+
+    type Error = csv::Error;
+
+    fn format_name() -> FormatName {
+        FormatName::Csv
+    }
+
+    fn deserialize(&self, slice: &[u8]) -> OutputResult<Self::Row, Self::Info, Self::Error> {
+        let rdr = reader_builder(&self.options)
+            .has_headers(false)
+            .from_reader(slice);
+
+        self.deserialize_with_records(slice, rdr.into_records())
+    }
+}
+impl<T> CsvOutput<T>
+where
+    T: DeserializeOwned,
+{
+    pub(crate) fn deserialize_with_records(
+        &self,
+        slice: &[u8],
+        records: StringRecordsIntoIter<&[u8]>,
+    ) -> OutputResult<<Self as Output>::Row, <Self as Output>::Info, <Self as Output>::Error> {
+        let header = &self.names.to_owned().map(StringRecord::from);
+        let mut data: Vec<T> = vec![];
+        for record in records {
+            let record = record?;
+            let raw = &slice[record.position().map_or(0, |x| x.byte() as usize)..];
+            let row: T = deserialize_record(raw, record, header.as_ref(), &self.options)?;
+            data.push(row);
+        }
+
+        Ok((data, self.info().flatten()))
+    }
+}
+
+impl<T> StreamOutput for CsvOutput<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize_line(&mut self, line: &[u8]) -> Result<Option<Self::Row>, Self::Error> {
+        deserialize_line_with_names(line, self.names.as_deref(), &self.options)
+    }
+
+    fn info(&self) -> Option<Self::Info> {
+        Some(self.types.as_ref().and_then(|types| {
+            self.names
+                .to_owned()
+                .map(|x| x.into_iter().zip(types.to_owned()).collect())
+        }))
+    }
+
+    fn is_complete_line(&self, line: &[u8]) -> bool {
+        is_complete_record(line)
+    }
+}
+
+// ClickHouse writes line breaks inside quoted values as is, quotes in values are doubled.
+pub(crate) fn is_complete_record(line: &[u8]) -> bool {
+    line.iter().filter(|b| **b == b'"').count() % 2 == 0
+}
+
+pub(crate) fn reader_builder(options: &CsvOptions) -> ReaderBuilder {
+    let mut builder = ReaderBuilder::new();
+    builder.delimiter(options.get_delimiter());
+    builder
+}
+
+// NULL is only an unquoted field equal to the null representation, so the fields are
+// deserialized here, the csv crate reads every empty field as None.
+pub(crate) fn deserialize_record<T>(
+    raw: &[u8],
+    record: StringRecord,
+    header: Option<&StringRecord>,
+    options: &CsvOptions,
+) -> Result<T, csv::Error>
+where
+    T: DeserializeOwned,
+{
+    let null = options.get_null_representation();
+    let fields = record
+        .iter()
+        .zip(quoted_fields(raw, options.get_delimiter()))
+        .map(|(field, quoted)| Field((quoted || field != null).then_some(field)));
+
+    match header {
+        Some(header) => T::deserialize(MapDeserializer::new(header.iter().zip(fields))),
+        None => T::deserialize(SeqDeserializer::new(fields)),
+    }
+    .map_err(|err: DeError| IoError::other(err).into())
+}
+
+// Whether each field of the raw record starts with a quote, the csv crate does not tell.
+fn quoted_fields(raw: &[u8], delimiter: u8) -> impl Iterator<Item = bool> + '_ {
+    let mut bytes = raw.iter().copied().peekable();
+    let mut done = false;
+    core::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+
+        let quoted = bytes.peek() == Some(&b'"');
+        let mut in_quotes = false;
+        loop {
+            match bytes.next() {
+                Some(b'"') if quoted => in_quotes = !in_quotes,
+                Some(b'\n' | b'\r') | None if !in_quotes => {
+                    done = true;
+                    break;
+                }
+                Some(b) if b == delimiter && !in_quotes => break,
+                None => {
+                    done = true;
+                    break;
+                }
+                _ => {}
+            }
+        }
+        Some(quoted)
+    })
+}
+
+// None is NULL.
+struct Field<'a>(Option<&'a str>);
+
+impl<'a> Field<'a> {
+    fn value(&self) -> Result<&'a str, DeError> {
+        self.0
+            .ok_or_else(|| DeError::custom("NULL for a non Nullable value"))
+    }
+
+    fn parse<T>(&self) -> Result<T, DeError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.value()?.parse().map_err(DeError::custom)
+    }
+}
+
+impl<'de> IntoDeserializer<'de, DeError> for Field<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident $visit:ident),*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, DeError>
+            where
+                V: Visitor<'de>,
+            {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Field<'de> {
+    type Error = DeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Some(value) => visitor.visit_borrowed_str(value),
+            None => visitor.visit_none(),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_bool visit_bool,
+        deserialize_i8 visit_i8, deserialize_i16 visit_i16, deserialize_i32 visit_i32,
+        deserialize_i64 visit_i64, deserialize_i128 visit_i128,
+        deserialize_u8 visit_u8, deserialize_u16 visit_u16, deserialize_u32 visit_u32,
+        deserialize_u64 visit_u64, deserialize_u128 visit_u128,
+        deserialize_f32 visit_f32, deserialize_f64 visit_f64, deserialize_char visit_char
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_borrowed_str(
+            self.0
+                .ok_or_else(|| DeError::custom("NULL for a non Nullable value"))?,
+        )
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_borrowed_bytes(self.value()?.as_bytes())
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Some(_) => visitor.visit_some(self),
+            None => visitor.visit_none(),
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Some("") | None => visitor.visit_unit(),
+            Some(_) => Err(DeError::custom("expected an empty field")),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_enum(self.value()?.into_deserializer())
+    }
+
+    forward_to_deserialize_any! {
+        unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+pub(crate) fn read_line_record(
+    line: &[u8],
+    options: &CsvOptions,
+) -> Result<Option<StringRecord>, csv::Error> {
+    reader_builder(options)
+        .has_headers(false)
+        .from_reader(line)
+        .into_records()
+        .next()
+        .transpose()
+}
+
+pub(crate) fn deserialize_line_with_names<T>(
+    line: &[u8],
+    names: Option<&[String]>,
+    options: &CsvOptions,
+) -> Result<Option<T>, csv::Error>
+where
+    T: DeserializeOwned,
+{
+    let Some(record) = read_line_record(line, options)? else {
+        return Ok(None);
+    };
+
+    let header = names.map(StringRecord::from);
+    deserialize_record(line, record, header.as_ref(), options).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{collections::HashMap, fs, path::PathBuf};
+
+    use serde::Deserialize;
+
+    use crate::test_helpers::{TEST_STRINGS_ROW_1, TestStringsRow, stream_deserialize};
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        let file_path = PathBuf::new().join("tests/files/CSV.csv");
+        let content = fs::read_to_string(&file_path)?;
+
+        assert_eq!(
+            CsvOutput::<HashMap<String, String>>::format_name(),
+            file_path
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .parse()
+                .unwrap()
+        );
+
+        let (rows, info) = CsvOutput::<HashMap<String, String>>::with_names(vec![
+            "array1".into(),
+            "array2".into(),
+            "tuple1".into(),
+            "tuple2".into(),
+            "map1".into(),
+        ])
+        .deserialize(content.as_bytes())?;
+        assert_eq!(rows.first().unwrap().get("tuple1").unwrap(), "(1,'a')");
+        assert_eq!(info, None);
+
+        let (rows, info) = CsvOutput::<TestStringsRow>::new().deserialize(content.as_bytes())?;
+        assert_eq!(rows.first().unwrap(), &*TEST_STRINGS_ROW_1);
+        assert_eq!(info, None);
+
+        let (rows, _) = stream_deserialize(CsvOutput::<TestStringsRow>::new(), content.as_bytes())?;
+        assert_eq!(rows.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_options() -> Result<(), Box<dyn std::error::Error>> {
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Row {
+            id: u8,
+            name: Option<String>,
+            note: String,
+        }
+
+        let mut output = CsvOutput::<Row>::new();
+        output.set_options(
+            CsvOptions::new()
+                .set_delimiter(b';')
+                .set_null_representation("NULL")
+                .to_owned(),
+        );
+
+        let (rows, _) =
+            output.deserialize(b"1;\"a;b\";\"x\"\n2;NULL;\"NULL\"\n3;\"NULL\";\"\"\n4;\"\";\n")?;
+        assert_eq!(
+            rows,
+            vec![
+                Row {
+                    id: 1,
+                    name: Some("a;b".into()),
+                    note: "x".into()
+                },
+                Row {
+                    id: 2,
+                    name: None,
+                    note: "NULL".into()
+                },
+                Row {
+                    id: 3,
+                    name: Some("NULL".into()),
+                    note: "".into()
+                },
+                Row {
+                    id: 4,
+                    name: Some("".into()),
+                    note: "".into()
+                },
+            ]
+        );
+
+        assert!(output.deserialize(b"5;NULL;NULL\n").is_err());
+
+        let (rows, _) = stream_deserialize(output, b"2;NULL;\"y\"\n3;\"a\nb;\"\"c\"\"\";\"\n\"\n")?;
+        assert_eq!(rows.first().unwrap().name, None);
+        assert_eq!(rows[1].name.as_deref(), Some("a\nb;\"c\""));
+        assert_eq!(rows[1].note, "\n");
+
+        Ok(())
+    }
+}
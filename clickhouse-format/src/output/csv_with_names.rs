@@ -0,0 +1,147 @@
+use core::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+
+use crate::{csv_options::CsvOptions, format_name::FormatName};
+
+use super::{
+    Output, OutputResult, StreamOutput,
+    csv::{
+        CsvOutput, deserialize_line_with_names, is_complete_record, read_line_record,
+        reader_builder,
+    },
+};
+
+pub struct CsvWithNamesOutput<T> {
+    names: Option<Vec<String>>,
+    types: Option<Vec<String>>,
+    options: CsvOptions,
+    phantom: PhantomData<T>,
+}
+impl<T> Default for CsvWithNamesOutput<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> CsvWithNamesOutput<T> {
+    pub fn new() -> Self {
+        Self {
+            names: None,
+            types: None,
+            options: CsvOptions::default(),
+            phantom: PhantomData,
+        }
+    }
+    pub fn with_types(types: Vec<String>) -> Self {
+        Self {
+            names: None,
+            types: Some(types),
+            options: CsvOptions::default(),
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn set_options(&mut self, options: CsvOptions) -> &mut Self {
+        self.options = options;
+        self
+    }
+}
+
+impl<T> Output for CsvWithNamesOutput<T>
+where
+    T: DeserializeOwned,
+{
+    type Row = T;
+    type Info = Vec<String>;
+
+    type Error = csv::Error;
+
+    fn format_name() -> FormatName {
+        FormatName::CsvWithNames
+    }
+
+    fn deserialize(&self, slice: &[u8]) -> OutputResult<Self::Row, Self::Info, Self::Error> {
+        let mut rdr = reader_builder(&self.options).from_reader(slice);
+
+        let header = rdr.headers()?;
+        let names: Vec<String> = header.iter().map(ToOwned::to_owned).collect();
+
+        let records = rdr.into_records();
+
+        let info = names.clone();
+
+        CsvOutput::<T>::inner_new(Some(names), self.types.clone(), self.options.clone())
+            .deserialize_with_records(slice, records)
+            .map(|(rows, _)| (rows, info))
+    }
+}
+
+impl<T> StreamOutput for CsvWithNamesOutput<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize_line(&mut self, line: &[u8]) -> Result<Option<Self::Row>, Self::Error> {
+        let Some(names) = &self.names else {
+            self.names = read_line_record(line, &self.options)?
+                .map(|record| record.iter().map(ToOwned::to_owned).collect());
+            return Ok(None);
+        };
+
+        deserialize_line_with_names(line, Some(names), &self.options)
+    }
+
+    fn info(&self) -> Option<Self::Info> {
+        self.names.to_owned()
+    }
+
+    fn is_complete_line(&self, line: &[u8]) -> bool {
+        is_complete_record(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{collections::HashMap, fs, path::PathBuf};
+
+    use crate::test_helpers::{
+        TEST_STRINGS_ROW_1, TEST_STRINGS_ROW_2, TestStringsRow, stream_deserialize,
+    };
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        let file_path = PathBuf::new().join("tests/files/CSVWithNames.csv");
+        let content = fs::read_to_string(&file_path)?;
+
+        assert_eq!(
+            CsvWithNamesOutput::<HashMap<String, String>>::format_name(),
+            file_path
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .parse()
+                .unwrap()
+        );
+
+        let (rows, info) =
+            CsvWithNamesOutput::<HashMap<String, String>>::new().deserialize(content.as_bytes())?;
+        assert_eq!(rows.first().unwrap().get("tuple1").unwrap(), "(1,'a')");
+        assert_eq!(info, vec!["array1", "array2", "tuple1", "tuple2", "map1"]);
+
+        let (rows, info) =
+            CsvWithNamesOutput::<TestStringsRow>::new().deserialize(content.as_bytes())?;
+        assert_eq!(rows.first().unwrap(), &*TEST_STRINGS_ROW_1);
+        assert_eq!(info.len(), 5);
+
+        let (rows, info) = stream_deserialize(
+            CsvWithNamesOutput::<TestStringsRow>::new(),
+            content.as_bytes(),
+        )?;
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows.last().unwrap(), &*TEST_STRINGS_ROW_2);
+        assert_eq!(info.unwrap().len(), 5);
+
+        Ok(())
+    }
+}
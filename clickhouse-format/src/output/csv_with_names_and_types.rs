@@ -0,0 +1,168 @@
+use core::marker::PhantomData;
+use std::io::Error as IoError;
+
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
+
+use crate::{csv_options::CsvOptions, format_name::FormatName};
+
+use super::{
+    Output, OutputResult, StreamOutput,
+    csv::{
+        CsvOutput, deserialize_line_with_names, is_complete_record, read_line_record,
+        reader_builder,
+    },
+};
+
+pub struct CsvWithNamesAndTypesOutput<T> {
+    names: Option<Vec<String>>,
+    types: Option<Vec<String>>,
+    options: CsvOptions,
+    phantom: PhantomData<T>,
+}
+impl<T> Default for CsvWithNamesAndTypesOutput<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> CsvWithNamesAndTypesOutput<T> {
+    pub fn new() -> Self {
+        Self {
+            names: None,
+            types: None,
+            options: CsvOptions::default(),
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn set_options(&mut self, options: CsvOptions) -> &mut Self {
+        self.options = options;
+        self
+    }
+}
+
+impl<T> Output for CsvWithNamesAndTypesOutput<T>
+where
+    T: DeserializeOwned,
+{
+    type Row = T;
+    type Info = IndexMap<String, String>;
+
+    type Error = csv::Error;
+
+    fn format_name() -> FormatName {
+        FormatName::CsvWithNamesAndTypes
+    }
+
+    fn deserialize(&self, slice: &[u8]) -> OutputResult<Self::Row, Self::Info, Self::Error> {
+        let mut rdr = reader_builder(&self.options).from_reader(slice);
+
+        let header = rdr.headers()?;
+        let names: Vec<String> = header.iter().map(ToOwned::to_owned).collect();
+
+        let mut records = rdr.into_records();
+
+        let record = records.next().ok_or_else(|| IoError::other(""))??;
+        let types: Vec<String> = record.iter().map(ToOwned::to_owned).collect();
+
+        let info = names
+            .iter()
+            .zip(types.iter())
+            .map(|(name, type_)| (name.to_owned(), type_.to_owned()))
+            .collect();
+
+        CsvOutput::inner_new(Some(names), Some(types), self.options.clone())
+            .deserialize_with_records(slice, records)
+            .map(|(rows, _)| (rows, info))
+    }
+}
+
+impl<T> StreamOutput for CsvWithNamesAndTypesOutput<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize_line(&mut self, line: &[u8]) -> Result<Option<Self::Row>, Self::Error> {
+        let names = match (&self.names, &self.types) {
+            (Some(names), Some(_)) => names,
+            (Some(_), None) => {
+                self.types = read_line_record(line, &self.options)?
+                    .map(|record| record.iter().map(ToOwned::to_owned).collect());
+                return Ok(None);
+            }
+            (None, _) => {
+                self.names = read_line_record(line, &self.options)?
+                    .map(|record| record.iter().map(ToOwned::to_owned).collect());
+                return Ok(None);
+            }
+        };
+
+        deserialize_line_with_names(line, Some(names), &self.options)
+    }
+
+    fn info(&self) -> Option<Self::Info> {
+        match (&self.names, &self.types) {
+            (Some(names), Some(types)) => {
+                Some(names.iter().cloned().zip(types.iter().cloned()).collect())
+            }
+            _ => None,
+        }
+    }
+
+    fn is_complete_line(&self, line: &[u8]) -> bool {
+        is_complete_record(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{collections::HashMap, fs, path::PathBuf};
+
+    use crate::test_helpers::{
+        TEST_STRINGS_ROW_1, TEST_STRINGS_ROW_2, TestStringsRow, stream_deserialize,
+    };
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        let file_path = PathBuf::new().join("tests/files/CSVWithNamesAndTypes.csv");
+        let content = fs::read_to_string(&file_path)?;
+
+        assert_eq!(
+            CsvWithNamesAndTypesOutput::<HashMap<String, String>>::format_name(),
+            file_path
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .parse()
+                .unwrap()
+        );
+
+        let (rows, info) = CsvWithNamesAndTypesOutput::<HashMap<String, String>>::new()
+            .deserialize(content.as_bytes())?;
+        assert_eq!(rows.first().unwrap().get("tuple1").unwrap(), "(1,'a')");
+        assert_eq!(info.get("array1"), Some(&"Array(UInt8)".to_owned()));
+        assert_eq!(info.get("array2"), Some(&"Array(String)".to_owned()));
+        assert_eq!(info.get("tuple1"), Some(&"Tuple(UInt8, String)".to_owned()));
+        assert_eq!(
+            info.get("tuple2"),
+            Some(&"Tuple(UInt8, Nullable(String))".to_owned())
+        );
+        assert_eq!(info.get("map1"), Some(&"Map(String, String)".to_owned()));
+
+        let (rows, _) =
+            CsvWithNamesAndTypesOutput::<TestStringsRow>::new().deserialize(content.as_bytes())?;
+        assert_eq!(rows.first().unwrap(), &*TEST_STRINGS_ROW_1);
+
+        let (rows, info) = stream_deserialize(
+            CsvWithNamesAndTypesOutput::<TestStringsRow>::new(),
+            content.as_bytes(),
+        )?;
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows.last().unwrap(), &*TEST_STRINGS_ROW_2);
+        let info = info.unwrap();
+        assert_eq!(info.get("map1"), Some(&"Map(String, String)".to_owned()));
+
+        Ok(())
+    }
+}
@@ -9,7 +9,7 @@ use serde_json::Value;
 
 use crate::format_name::FormatName;
 
-use super::{Output, OutputResult};
+use super::{Output, OutputResult, StreamOutput};
 
 pub struct JsonCompactEachRowOutput<T> {
     names: Vec<String>,
@@ -65,6 +65,25 @@ where
     }
 }
 
+impl<T> StreamOutput for JsonCompactEachRowOutput<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize_line(&mut self, line: &[u8]) -> Result<Option<Self::Row>, Self::Error> {
+        let values: Vec<Value> = serde_json::from_slice(line)?;
+
+        let row: T = serde_json::from_value(Value::Object(
+            self.names.iter().cloned().zip(values).collect(),
+        ))?;
+
+        Ok(Some(row))
+    }
+
+    fn info(&self) -> Option<Self::Info> {
+        Some(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
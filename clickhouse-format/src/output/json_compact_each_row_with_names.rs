@@ -9,9 +9,10 @@ use serde_json::Value;
 
 use crate::format_name::FormatName;
 
-use super::{Output, OutputResult};
+use super::{Output, OutputResult, StreamOutput};
 
 pub struct JsonCompactEachRowWithNamesOutput<T> {
+    names: Option<Vec<String>>,
     phantom: PhantomData<T>,
 }
 impl<T> Default for JsonCompactEachRowWithNamesOutput<T> {
@@ -22,6 +23,7 @@ impl<T> Default for JsonCompactEachRowWithNamesOutput<T> {
 impl<T> JsonCompactEachRowWithNamesOutput<T> {
     pub fn new() -> Self {
         Self {
+            names: None,
             phantom: PhantomData,
         }
     }
@@ -74,6 +76,32 @@ where
     }
 }
 
+impl<T> StreamOutput for JsonCompactEachRowWithNamesOutput<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize_line(&mut self, line: &[u8]) -> Result<Option<Self::Row>, Self::Error> {
+        let names = match &self.names {
+            Some(names) => names,
+            None => {
+                self.names = Some(serde_json::from_slice(line)?);
+                return Ok(None);
+            }
+        };
+
+        let values: Vec<Value> = serde_json::from_slice(line)?;
+
+        let row: T =
+            serde_json::from_value(Value::Object(names.iter().cloned().zip(values).collect()))?;
+
+        Ok(Some(row))
+    }
+
+    fn info(&self) -> Option<Self::Info> {
+        self.names.to_owned()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
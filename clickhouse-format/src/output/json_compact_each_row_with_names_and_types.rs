@@ -10,9 +10,11 @@ use serde_json::Value;
 
 use crate::format_name::FormatName;
 
-use super::{Output, OutputResult};
+use super::{Output, OutputResult, StreamOutput};
 
 pub struct JsonCompactEachRowWithNamesAndTypesOutput<T> {
+    names: Option<Vec<String>>,
+    types: Option<Vec<String>>,
     phantom: PhantomData<T>,
 }
 impl<T> Default for JsonCompactEachRowWithNamesAndTypesOutput<T> {
@@ -23,6 +25,8 @@ impl<T> Default for JsonCompactEachRowWithNamesAndTypesOutput<T> {
 impl<T> JsonCompactEachRowWithNamesAndTypesOutput<T> {
     pub fn new() -> Self {
         Self {
+            names: None,
+            types: None,
             phantom: PhantomData,
         }
     }
@@ -79,13 +83,48 @@ where
     }
 }
 
+impl<T> StreamOutput for JsonCompactEachRowWithNamesAndTypesOutput<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize_line(&mut self, line: &[u8]) -> Result<Option<Self::Row>, Self::Error> {
+        let names = match (&self.names, &self.types) {
+            (Some(names), Some(_)) => names,
+            (Some(_), None) => {
+                self.types = Some(serde_json::from_slice(line)?);
+                return Ok(None);
+            }
+            (None, _) => {
+                self.names = Some(serde_json::from_slice(line)?);
+                return Ok(None);
+            }
+        };
+
+        let values: Vec<Value> = serde_json::from_slice(line)?;
+
+        let row: T =
+            serde_json::from_value(Value::Object(names.iter().cloned().zip(values).collect()))?;
+
+        Ok(Some(row))
+    }
+
+    fn info(&self) -> Option<Self::Info> {
+        match (&self.names, &self.types) {
+            (Some(names), Some(types)) => {
+                Some(names.iter().cloned().zip(types.iter().cloned()).collect())
+            }
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use std::{fs, path::PathBuf};
 
-    use crate::test_helpers::{TEST_ROW_1, TestRow};
+    use crate::test_helpers::{TEST_ROW_1, TEST_ROW_2, TestRow, stream_deserialize};
 
     #[test]
     fn simple() -> Result<(), Box<dyn std::error::Error>> {
@@ -117,4 +156,43 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn with_stream() -> Result<(), Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(
+            PathBuf::new().join("tests/files/JSONCompactEachRowWithNamesAndTypes.txt"),
+        )?;
+
+        let mut output = JsonCompactEachRowWithNamesAndTypesOutput::<TestRow>::new();
+        let mut lines = content.lines();
+        assert!(
+            output
+                .deserialize_line(lines.next().unwrap().as_bytes())?
+                .is_none()
+        );
+        assert!(output.info().is_none());
+        assert!(
+            output
+                .deserialize_line(lines.next().unwrap().as_bytes())?
+                .is_none()
+        );
+        assert_eq!(
+            output.info().unwrap().get("array1"),
+            Some(&"Array(UInt8)".to_owned())
+        );
+
+        let (rows, info) = stream_deserialize(
+            JsonCompactEachRowWithNamesAndTypesOutput::<TestRow>::new(),
+            content.as_bytes(),
+        )?;
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows.first().unwrap(), &*TEST_ROW_1);
+        assert_eq!(rows.last().unwrap(), &*TEST_ROW_2);
+        assert_eq!(
+            info.unwrap().get("array1"),
+            Some(&"Array(UInt8)".to_owned())
+        );
+
+        Ok(())
+    }
 }
@@ -5,7 +5,7 @@ use serde::de::DeserializeOwned;
 use crate::format_name::FormatName;
 
 use super::{
-    Output, OutputResult,
+    Output, OutputResult, StreamOutput,
     json_compact_each_row_with_names_and_types::JsonCompactEachRowWithNamesAndTypesOutput,
 };
 
@@ -43,6 +43,19 @@ where
     }
 }
 
+impl<T> StreamOutput for JsonCompactStringsEachRowWithNamesAndTypesOutput<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize_line(&mut self, line: &[u8]) -> Result<Option<Self::Row>, Self::Error> {
+        self.inner.deserialize_line(line)
+    }
+
+    fn info(&self) -> Option<Self::Info> {
+        self.inner.info()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -9,7 +9,7 @@ use serde_json::Value;
 
 use crate::format_name::FormatName;
 
-use super::{Output, OutputResult};
+use super::{Output, OutputResult, StreamOutput};
 
 pub struct JsonEachRowOutput<T> {
     phantom: PhantomData<T>,
@@ -62,6 +62,21 @@ where
     }
 }
 
+impl<T> StreamOutput for JsonEachRowOutput<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize_line(&mut self, line: &[u8]) -> Result<Option<Self::Row>, Self::Error> {
+        let row: T = serde_json::from_slice(line)?;
+
+        Ok(Some(row))
+    }
+
+    fn info(&self) -> Option<Self::Info> {
+        Some(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4,15 +4,16 @@ use std::{
     io::{BufRead as _, Error as IoError},
 };
 
-use serde::{Deserialize, de::DeserializeOwned};
-use serde_aux::field_attributes::deserialize_option_number_from_string;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
 use crate::format_name::FormatName;
+pub use crate::progress::JsonEachRowProgress;
 
-use super::{Output, OutputResult};
+use super::{Output, OutputResult, StreamOutput};
 
 pub struct JsonEachRowWithProgressOutput<T> {
+    progress: Option<JsonEachRowProgress>,
     phantom: PhantomData<T>,
 }
 impl<T> Default for JsonEachRowWithProgressOutput<T> {
@@ -23,6 +24,7 @@ impl<T> Default for JsonEachRowWithProgressOutput<T> {
 impl<T> JsonEachRowWithProgressOutput<T> {
     pub fn new() -> Self {
         Self {
+            progress: None,
             phantom: PhantomData,
         }
     }
@@ -37,6 +39,7 @@ pub enum JsonEachRowWithProgressOutputError {
     IoError(#[from] IoError),
     #[error("SerdeJsonError {0:?}")]
     SerdeJsonError(#[from] serde_json::Error),
+    #[deprecated(note = "progress lines may come between the rows, this is never returned")]
     #[error("ProgressInTheWrongPosition")]
     ProgressInTheWrongPosition,
     #[error("ProgressMissing")]
@@ -60,27 +63,18 @@ where
         let mut data: Vec<T> = vec![];
         let mut info = Option::<JsonEachRowProgress>::None;
 
+        // Progress lines may come between the rows, the last one is the final progress.
         for line in slice.lines() {
             let line = line?;
 
-            if info.is_some() {
-                return Err(JsonEachRowWithProgressOutputError::ProgressInTheWrongPosition);
-            }
-
             let line = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&line)?;
 
-            if let Some(_meta) = line.get("meta") {
-                continue;
-            } else if let Some(row) = line.get("row") {
+            if let Some(row) = line.get("row") {
                 let row: T = serde_json::from_value(row.clone())?;
                 data.push(row);
-                continue;
             } else if let Some(progress) = line.get("progress") {
                 let progress: JsonEachRowProgress = serde_json::from_value(progress.clone())?;
                 info = Some(progress);
-                break;
-            } else {
-                continue;
             }
         }
 
@@ -90,20 +84,28 @@ where
     }
 }
 
-#[derive(Deserialize, Debug, Clone)]
-pub struct JsonEachRowProgress {
-    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
-    pub read_rows: Option<usize>,
-    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
-    pub read_bytes: Option<usize>,
-    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
-    pub total_rows_to_read: Option<usize>,
-    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
-    pub written_rows: Option<usize>,
-    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
-    pub written_bytes: Option<usize>,
-    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
-    pub elapsed_ns: Option<usize>,
+impl<T> StreamOutput for JsonEachRowWithProgressOutput<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize_line(&mut self, line: &[u8]) -> Result<Option<Self::Row>, Self::Error> {
+        let line = serde_json::from_slice::<serde_json::Map<String, serde_json::Value>>(line)?;
+
+        if let Some(row) = line.get("row") {
+            let row: T = serde_json::from_value(row.clone())?;
+            Ok(Some(row))
+        } else if let Some(progress) = line.get("progress") {
+            let progress: JsonEachRowProgress = serde_json::from_value(progress.clone())?;
+            self.progress = Some(progress);
+            Ok(None)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn info(&self) -> Option<Self::Info> {
+        self.progress.to_owned()
+    }
 }
 
 #[cfg(test)]
@@ -112,7 +114,7 @@ mod tests {
 
     use std::{fs, path::PathBuf};
 
-    use crate::test_helpers::{TEST_ROW_1, TestRow};
+    use crate::test_helpers::{TEST_ROW_1, TestRow, stream_deserialize};
 
     #[test]
     fn simple() -> Result<(), Box<dyn std::error::Error>> {
@@ -144,4 +146,39 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn with_stream() -> Result<(), Box<dyn std::error::Error>> {
+        let content =
+            fs::read_to_string(PathBuf::new().join("tests/files/JSONEachRowWithProgress.txt"))?;
+
+        let (rows, info) = stream_deserialize(
+            JsonEachRowWithProgressOutput::<TestRow>::new(),
+            content.as_bytes(),
+        )?;
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows.first().unwrap(), &*TEST_ROW_1);
+        assert_eq!(info.unwrap().read_rows.unwrap(), 2);
+
+        // With progress lines between the rows.
+        let progress_line = content.lines().last().unwrap();
+        let interleaved = content
+            .lines()
+            .flat_map(|line| [progress_line, line])
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let (rows, info) = stream_deserialize(
+            JsonEachRowWithProgressOutput::<TestRow>::new(),
+            interleaved.as_bytes(),
+        )?;
+        assert_eq!(rows.len(), 2);
+        assert_eq!(info.unwrap().read_rows.unwrap(), 2);
+
+        let (rows, _) =
+            JsonEachRowWithProgressOutput::<TestRow>::new().deserialize(interleaved.as_bytes())?;
+        assert_eq!(rows.len(), 2);
+
+        Ok(())
+    }
 }
@@ -4,7 +4,7 @@ use serde::de::DeserializeOwned;
 
 use crate::format_name::FormatName;
 
-use super::{Output, OutputResult, json_each_row::JsonEachRowOutput};
+use super::{Output, OutputResult, StreamOutput, json_each_row::JsonEachRowOutput};
 
 type Inner<T> = JsonEachRowOutput<T>;
 
@@ -39,6 +39,19 @@ where
     }
 }
 
+impl<T> StreamOutput for JsonStringsEachRowOutput<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize_line(&mut self, line: &[u8]) -> Result<Option<Self::Row>, Self::Error> {
+        self.inner.deserialize_line(line)
+    }
+
+    fn info(&self) -> Option<Self::Info> {
+        self.inner.info()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4,7 +4,9 @@ use serde::de::DeserializeOwned;
 
 use crate::format_name::FormatName;
 
-use super::{Output, OutputResult, json_each_row_with_progress::JsonEachRowWithProgressOutput};
+use super::{
+    Output, OutputResult, StreamOutput, json_each_row_with_progress::JsonEachRowWithProgressOutput,
+};
 
 type Inner<T> = JsonEachRowWithProgressOutput<T>;
 
@@ -41,6 +43,19 @@ where
     }
 }
 
+impl<T> StreamOutput for JsonStringsEachRowWithProgressOutput<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize_line(&mut self, line: &[u8]) -> Result<Option<Self::Row>, Self::Error> {
+        self.inner.deserialize_line(line)
+    }
+
+    fn info(&self) -> Option<Self::Info> {
+        self.inner.info()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,4 +1,6 @@
-use crate::format_name::FormatName;
+#[cfg(feature = "with-row-binary")]
+use crate::exception::split_trailing_exception;
+use crate::{exception::split_exception, format_name::FormatName};
 
 //
 #[cfg(feature = "with-tsv")]
@@ -26,6 +28,20 @@ pub type TabSeparatedWithNamesOutput<T> = self::tsv_with_names::TsvWithNamesOutp
 pub type TabSeparatedWithNamesAndTypesOutput<T> =
     self::tsv_with_names_and_types::TsvWithNamesAndTypesOutput<T>;
 
+//
+#[cfg(feature = "with-csv")]
+pub mod csv;
+#[cfg(feature = "with-csv")]
+pub mod csv_with_names;
+#[cfg(feature = "with-csv")]
+pub mod csv_with_names_and_types;
+
+#[cfg(feature = "with-csv")]
+pub use self::{
+    csv::CsvOutput, csv_with_names::CsvWithNamesOutput,
+    csv_with_names_and_types::CsvWithNamesAndTypesOutput,
+};
+
 //
 #[cfg(feature = "with-json")]
 pub mod json;
@@ -95,6 +111,24 @@ pub use self::{
     },
 };
 
+//
+#[cfg(feature = "with-row-binary")]
+pub mod row_binary;
+#[cfg(feature = "with-row-binary")]
+pub mod row_binary_with_names_and_types;
+
+#[cfg(feature = "with-row-binary")]
+pub use self::{
+    row_binary::RowBinaryOutput, row_binary_with_names_and_types::RowBinaryWithNamesAndTypesOutput,
+};
+
+//
+#[cfg(feature = "with-native")]
+pub mod native;
+
+#[cfg(feature = "with-native")]
+pub use self::native::NativeOutput;
+
 pub trait Output {
     type Row;
     type Info;
@@ -102,5 +136,55 @@ pub trait Output {
 
     fn format_name() -> FormatName;
     fn deserialize(&self, slice: &[u8]) -> OutputResult<Self::Row, Self::Info, Self::Error>;
+
+    /// Like `deserialize`, but a trailing server exception is returned as `OutputError::Exception`
+    /// instead of failing inside the format.
+    fn deserialize_checked(
+        &self,
+        slice: &[u8],
+        exception_tag: Option<&str>,
+    ) -> OutputResult<Self::Row, Self::Info, OutputError<Self::Error>> {
+        let (slice, exception) = split_exception(slice, exception_tag);
+        if let Some(message) = exception {
+            return Err(OutputError::Exception(message));
+        }
+
+        self.deserialize(slice).map_err(OutputError::Format)
+    }
 }
 pub type OutputResult<Row, Info, Error> = Result<(Vec<Row>, Info), Error>;
+
+// `deserialize_checked` of the binary formats, only a tagged exception frame at the end counts.
+#[cfg(feature = "with-row-binary")]
+pub(crate) fn deserialize_binary_checked<O: Output>(
+    output: &O,
+    slice: &[u8],
+    exception_tag: Option<&str>,
+) -> OutputResult<O::Row, O::Info, OutputError<O::Error>> {
+    let (slice, exception) = split_trailing_exception(slice, exception_tag);
+    if let Some(message) = exception {
+        return Err(OutputError::Exception(message));
+    }
+
+    output.deserialize(slice).map_err(OutputError::Format)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum OutputError<E: std::error::Error> {
+    #[error("Exception {0}")]
+    Exception(String),
+    #[error("Format {0}")]
+    Format(E),
+}
+
+pub trait StreamOutput: Output {
+    fn deserialize_line(&mut self, line: &[u8]) -> Result<Option<Self::Row>, Self::Error>;
+    fn info(&self) -> Option<Self::Info>;
+
+    /// Whether `line` (without the trailing `\n`) is a whole row.
+    /// When it is not, the next line is appended to it after a `\n`.
+    fn is_complete_line(&self, line: &[u8]) -> bool {
+        let _ = line;
+        true
+    }
+}
@@ -0,0 +1,133 @@
+use core::marker::PhantomData;
+
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
+
+use crate::{
+    format_name::FormatName,
+    row_binary::{Deserializer, RowBinaryError, native::read_block, typed::RowDeserializer},
+};
+
+use super::{Output, OutputError, OutputResult, deserialize_binary_checked};
+
+/// Like `RowBinaryWithNamesAndTypesOutput`, the rows are checked against the column types.
+pub struct NativeOutput<T> {
+    phantom: PhantomData<T>,
+}
+impl<T> Default for NativeOutput<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> NativeOutput<T> {
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> Output for NativeOutput<T>
+where
+    T: DeserializeOwned,
+{
+    type Row = T;
+    type Info = IndexMap<String, String>;
+
+    type Error = RowBinaryError;
+
+    fn format_name() -> FormatName {
+        FormatName::Native
+    }
+
+    fn deserialize(&self, slice: &[u8]) -> OutputResult<Self::Row, Self::Info, Self::Error> {
+        let mut de = Deserializer::new(slice);
+
+        let mut data: Vec<T> = vec![];
+        let mut info = IndexMap::new();
+        while !de.is_empty() {
+            let block = read_block(&mut de)?;
+
+            for i in 0..block.num_rows {
+                data.push(T::deserialize(RowDeserializer::with_values(
+                    &block.row(i),
+                    &block.columns,
+                ))?);
+            }
+
+            if info.is_empty() {
+                info = block
+                    .columns
+                    .into_iter()
+                    .map(|column| (column.name, column.type_name))
+                    .collect();
+            }
+        }
+
+        Ok((data, info))
+    }
+
+    fn deserialize_checked(
+        &self,
+        slice: &[u8],
+        exception_tag: Option<&str>,
+    ) -> OutputResult<Self::Row, Self::Info, OutputError<Self::Error>> {
+        deserialize_binary_checked(self, slice, exception_tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde::Deserialize;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Row {
+        id: u8,
+        name: Option<String>,
+    }
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        // Two blocks of SELECT number AS id, toLowCardinality(if(id = 0, NULL, toString(id))) AS name
+        let mut bytes = vec![];
+        for (id, index) in [(0_u8, 0_u8), (1, 2)] {
+            bytes.extend_from_slice(b"\x02\x01");
+            bytes.extend_from_slice(b"\x02id\x05UInt8");
+            bytes.push(id);
+            bytes.extend_from_slice(b"\x04name\x20LowCardinality(Nullable(String))");
+            bytes.extend_from_slice(&1_u64.to_le_bytes());
+            bytes.extend_from_slice(&(1_u64 << 9).to_le_bytes());
+            bytes.extend_from_slice(&3_u64.to_le_bytes());
+            bytes.extend_from_slice(b"\x00\x00\x011");
+            bytes.extend_from_slice(&1_u64.to_le_bytes());
+            bytes.push(index);
+        }
+
+        let (rows, info) = NativeOutput::<Row>::new().deserialize(&bytes)?;
+        assert_eq!(
+            rows,
+            vec![
+                Row { id: 0, name: None },
+                Row {
+                    id: 1,
+                    name: Some("1".into())
+                }
+            ]
+        );
+        assert_eq!(info.get("id").unwrap(), "UInt8");
+
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct RowWithoutOption {
+            name: String,
+        }
+        assert!(matches!(
+            NativeOutput::<RowWithoutOption>::new().deserialize(&bytes),
+            Err(RowBinaryError::TypeMismatch(_))
+        ));
+
+        Ok(())
+    }
+}
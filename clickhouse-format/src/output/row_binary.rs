@@ -0,0 +1,122 @@
+use core::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+
+use crate::{
+    format_name::FormatName,
+    row_binary::{Deserializer, RowBinaryError},
+};
+
+use super::{Output, OutputError, OutputResult, deserialize_binary_checked};
+
+pub struct RowBinaryOutput<T> {
+    phantom: PhantomData<T>,
+}
+impl<T> Default for RowBinaryOutput<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> RowBinaryOutput<T> {
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> Output for RowBinaryOutput<T>
+where
+    T: DeserializeOwned,
+{
+    type Row = T;
+    type Info = ();
+
+    type Error = RowBinaryError;
+
+    fn format_name() -> FormatName {
+        FormatName::RowBinary
+    }
+
+    fn deserialize(&self, slice: &[u8]) -> OutputResult<Self::Row, Self::Info, Self::Error> {
+        let mut de = Deserializer::new(slice);
+
+        let mut data: Vec<T> = vec![];
+        while !de.is_empty() {
+            data.push(T::deserialize(&mut de)?);
+        }
+
+        Ok((data, ()))
+    }
+
+    fn deserialize_checked(
+        &self,
+        slice: &[u8],
+        exception_tag: Option<&str>,
+    ) -> OutputResult<Self::Row, Self::Info, OutputError<Self::Error>> {
+        deserialize_binary_checked(self, slice, exception_tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde::Deserialize;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Row {
+        id: u32,
+        name: Option<String>,
+    }
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        let bytes = [
+            1, 0, 0, 0, 0, 1, b'a', //
+            2, 0, 0, 0, 1,
+        ];
+
+        let (rows, _) = RowBinaryOutput::<Row>::new().deserialize(&bytes)?;
+        assert_eq!(
+            rows,
+            vec![
+                Row {
+                    id: 1,
+                    name: Some("a".into())
+                },
+                Row { id: 2, name: None },
+            ]
+        );
+
+        assert!(matches!(
+            RowBinaryOutput::<Row>::new().deserialize(&bytes[..10]),
+            Err(RowBinaryError::UnexpectedEof)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_exception_like_data() -> Result<(), Box<dyn std::error::Error>> {
+        // A String holding "\nCode: 1. DB::Exception: foo\n".
+        let mut bytes = vec![1, 0, 0, 0, 0, 29];
+        bytes.extend_from_slice(b"\nCode: 1. DB::Exception: foo\n");
+
+        let (rows, _) = RowBinaryOutput::<Row>::new().deserialize_checked(&bytes, None)?;
+        assert_eq!(
+            rows[0].name.as_deref(),
+            Some("\nCode: 1. DB::Exception: foo\n")
+        );
+
+        bytes.extend_from_slice(
+            b"__exception__\r\ntag\r\nCode: 395. DB::Exception: bar\n29 tag\r\n__exception__\r\n",
+        );
+        assert!(matches!(
+            RowBinaryOutput::<Row>::new().deserialize_checked(&bytes, Some("tag")),
+            Err(OutputError::Exception(message)) if message == "Code: 395. DB::Exception: bar"
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+use core::marker::PhantomData;
+
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
+
+use crate::{
+    format_name::FormatName,
+    row_binary::{
+        Deserializer, RowBinaryError,
+        typed::{RowDeserializer, read_header},
+    },
+};
+
+use super::{Output, OutputError, OutputResult, deserialize_binary_checked};
+
+/// Checks the rows against the column types, e.g. a `Nullable(String)` column needs an
+/// `Option<String>` field.
+///
+/// Structs and maps are read by column name, tuples by position.
+pub struct RowBinaryWithNamesAndTypesOutput<T> {
+    phantom: PhantomData<T>,
+}
+impl<T> Default for RowBinaryWithNamesAndTypesOutput<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> RowBinaryWithNamesAndTypesOutput<T> {
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> Output for RowBinaryWithNamesAndTypesOutput<T>
+where
+    T: DeserializeOwned,
+{
+    type Row = T;
+    type Info = IndexMap<String, String>;
+
+    type Error = RowBinaryError;
+
+    fn format_name() -> FormatName {
+        FormatName::RowBinaryWithNamesAndTypes
+    }
+
+    fn deserialize(&self, slice: &[u8]) -> OutputResult<Self::Row, Self::Info, Self::Error> {
+        let mut de = Deserializer::new(slice);
+
+        let columns = read_header(&mut de)?;
+
+        let mut data: Vec<T> = vec![];
+        while !de.is_empty() {
+            data.push(T::deserialize(RowDeserializer::new(&mut de, &columns))?);
+        }
+
+        let info = columns
+            .into_iter()
+            .map(|column| (column.name, column.type_name))
+            .collect();
+
+        Ok((data, info))
+    }
+
+    fn deserialize_checked(
+        &self,
+        slice: &[u8],
+        exception_tag: Option<&str>,
+    ) -> OutputResult<Self::Row, Self::Info, OutputError<Self::Error>> {
+        deserialize_binary_checked(self, slice, exception_tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use serde::Deserialize;
+
+    use crate::row_binary::to_writer;
+
+    fn header(columns: &[(&str, &str)]) -> Result<Vec<u8>, RowBinaryError> {
+        let mut buf = vec![];
+        to_writer(&mut buf, &(columns.len() as u8))?;
+        for (name, _) in columns {
+            to_writer(&mut buf, name)?;
+        }
+        for (_, type_name) in columns {
+            to_writer(&mut buf, type_name)?;
+        }
+        Ok(buf)
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    #[serde(rename_all = "lowercase")]
+    enum Status {
+        Ok,
+        Failed,
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Row {
+        id: u32,
+        name: Option<String>,
+        tags: Vec<String>,
+        status: Status,
+        uuid: String,
+        code: [u8; 3],
+        attrs: BTreeMap<String, u64>,
+    }
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        let mut bytes = header(&[
+            ("id", "UInt32"),
+            ("name", "Nullable(String)"),
+            ("tags", "Array(LowCardinality(String))"),
+            ("status", "Enum8('ok' = 1, 'failed' = 2)"),
+            ("uuid", "UUID"),
+            ("code", "FixedString(3)"),
+            ("attrs", "Map(String, UInt64)"),
+            ("extra", "Tuple(String, Nullable(Int8))"),
+        ])?;
+        to_writer(
+            &mut bytes,
+            &(
+                1_u32,
+                Some("a"),
+                vec!["x"],
+                2_i8,
+                (0x6ba7_b810_9dad_11d1_u64, 0x80b4_00c0_4fd4_30c8_u64),
+                *b"abc",
+                vec![("k", 1_u64)].into_iter().collect::<BTreeMap<_, _>>(),
+                ("e", Some(-1_i8)),
+            ),
+        )?;
+
+        let (rows, info) = RowBinaryWithNamesAndTypesOutput::<Row>::new().deserialize(&bytes)?;
+        assert_eq!(
+            rows,
+            vec![Row {
+                id: 1,
+                name: Some("a".into()),
+                tags: vec!["x".into()],
+                status: Status::Failed,
+                uuid: "6ba7b810-9dad-11d1-80b4-00c04fd430c8".into(),
+                code: *b"abc",
+                attrs: vec![("k".into(), 1)].into_iter().collect(),
+            }]
+        );
+        assert_eq!(info.get("name").unwrap(), "Nullable(String)");
+        assert_eq!(info.len(), 8);
+
+        let (rows, _) = RowBinaryWithNamesAndTypesOutput::<(u32, Option<String>)>::new()
+            .deserialize(&{
+                let mut bytes = header(&[("id", "UInt32"), ("name", "Nullable(String)")])?;
+                to_writer(&mut bytes, &(2_u32, None::<String>))?;
+                bytes
+            })?;
+        assert_eq!(rows, vec![(2, None)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mismatch() -> Result<(), Box<dyn std::error::Error>> {
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Row {
+            id: u32,
+            name: String,
+        }
+
+        let mut bytes = header(&[("id", "UInt32"), ("name", "Nullable(String)")])?;
+        to_writer(&mut bytes, &(1_u32, Some("a")))?;
+
+        match RowBinaryWithNamesAndTypesOutput::<Row>::new().deserialize(&bytes) {
+            Err(RowBinaryError::TypeMismatch(msg)) => assert_eq!(
+                msg,
+                "column name of type Nullable(String) can't be read as str, Nullable needs an Option"
+            ),
+            ret => panic!("{ret:?}"),
+        }
+
+        assert!(matches!(
+            RowBinaryWithNamesAndTypesOutput::<(u64, Option<String>)>::new().deserialize(&bytes),
+            Err(RowBinaryError::TypeMismatch(_))
+        ));
+        assert!(matches!(
+            RowBinaryWithNamesAndTypesOutput::<(u32,)>::new().deserialize(&bytes),
+            Err(RowBinaryError::ColumnsMismatch(_))
+        ));
+
+        let bytes = header(&[("id", "Foo")])?;
+        assert!(matches!(
+            RowBinaryWithNamesAndTypesOutput::<(u32,)>::new().deserialize(&bytes),
+            Err(RowBinaryError::TypeNameParseError(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -6,7 +6,10 @@ use serde::de::DeserializeOwned;
 
 use crate::format_name::FormatName;
 
-use super::{Output, OutputResult, tsv_raw::TsvRawOutput};
+use super::{
+    Output, OutputResult, StreamOutput,
+    tsv_raw::{TsvRawOutput, deserialize_line_with_names},
+};
 
 pub struct TsvOutput<T> {
     names: Option<Vec<String>>,
@@ -78,6 +81,24 @@ where
     }
 }
 
+impl<T> StreamOutput for TsvOutput<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize_line(&mut self, line: &[u8]) -> Result<Option<Self::Row>, Self::Error> {
+        // TODO, unescape
+        deserialize_line_with_names(line, self.names.as_deref())
+    }
+
+    fn info(&self) -> Option<Self::Info> {
+        Some(self.types.as_ref().and_then(|types| {
+            self.names
+                .to_owned()
+                .map(|x| x.into_iter().zip(types.to_owned()).collect())
+        }))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
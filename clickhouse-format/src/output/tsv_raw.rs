@@ -6,7 +6,7 @@ use serde::de::DeserializeOwned;
 
 use crate::format_name::FormatName;
 
-use super::{Output, OutputResult};
+use super::{Output, OutputResult, StreamOutput};
 
 pub struct TsvRawOutput<T> {
     names: Option<Vec<String>>,
@@ -99,6 +99,50 @@ where
     }
 }
 
+impl<T> StreamOutput for TsvRawOutput<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize_line(&mut self, line: &[u8]) -> Result<Option<Self::Row>, Self::Error> {
+        deserialize_line_with_names(line, self.names.as_deref())
+    }
+
+    fn info(&self) -> Option<Self::Info> {
+        Some(self.types.as_ref().and_then(|types| {
+            self.names
+                .to_owned()
+                .map(|x| x.into_iter().zip(types.to_owned()).collect())
+        }))
+    }
+}
+
+pub(crate) fn read_line_record(line: &[u8]) -> Result<Option<StringRecord>, csv::Error> {
+    ReaderBuilder::new()
+        .delimiter(b'\t')
+        .has_headers(false)
+        .from_reader(line)
+        .into_records()
+        .next()
+        .transpose()
+}
+
+pub(crate) fn deserialize_line_with_names<T>(
+    line: &[u8],
+    names: Option<&[String]>,
+) -> Result<Option<T>, csv::Error>
+where
+    T: DeserializeOwned,
+{
+    let Some(record) = read_line_record(line)? else {
+        return Ok(None);
+    };
+
+    let header = names.map(StringRecord::from);
+    let row: T = record.deserialize(header.as_ref())?;
+
+    Ok(Some(row))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
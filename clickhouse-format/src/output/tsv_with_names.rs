@@ -5,9 +5,14 @@ use serde::de::DeserializeOwned;
 
 use crate::format_name::FormatName;
 
-use super::{Output, OutputResult, tsv::TsvOutput};
+use super::{
+    Output, OutputResult, StreamOutput,
+    tsv::TsvOutput,
+    tsv_raw::{deserialize_line_with_names, read_line_record},
+};
 
 pub struct TsvWithNamesOutput<T> {
+    names: Option<Vec<String>>,
     types: Option<Vec<String>>,
     phantom: PhantomData<T>,
 }
@@ -19,12 +24,14 @@ impl<T> Default for TsvWithNamesOutput<T> {
 impl<T> TsvWithNamesOutput<T> {
     pub fn new() -> Self {
         Self {
+            names: None,
             types: None,
             phantom: PhantomData,
         }
     }
     pub fn with_types(types: Vec<String>) -> Self {
         Self {
+            names: None,
             types: Some(types),
             phantom: PhantomData,
         }
@@ -66,6 +73,25 @@ where
     }
 }
 
+impl<T> StreamOutput for TsvWithNamesOutput<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize_line(&mut self, line: &[u8]) -> Result<Option<Self::Row>, Self::Error> {
+        let Some(names) = &self.names else {
+            self.names = read_line_record(line)?
+                .map(|record| record.iter().map(ToOwned::to_owned).collect());
+            return Ok(None);
+        };
+
+        deserialize_line_with_names(line, Some(names))
+    }
+
+    fn info(&self) -> Option<Self::Info> {
+        self.names.to_owned()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -7,9 +7,15 @@ use serde::de::DeserializeOwned;
 
 use crate::format_name::FormatName;
 
-use super::{Output, OutputResult, tsv::TsvOutput};
+use super::{
+    Output, OutputResult, StreamOutput,
+    tsv::TsvOutput,
+    tsv_raw::{deserialize_line_with_names, read_line_record},
+};
 
 pub struct TsvWithNamesAndTypesOutput<T> {
+    names: Option<Vec<String>>,
+    types: Option<Vec<String>>,
     phantom: PhantomData<T>,
 }
 impl<T> Default for TsvWithNamesAndTypesOutput<T> {
@@ -20,6 +26,8 @@ impl<T> Default for TsvWithNamesAndTypesOutput<T> {
 impl<T> TsvWithNamesAndTypesOutput<T> {
     pub fn new() -> Self {
         Self {
+            names: None,
+            types: None,
             phantom: PhantomData,
         }
     }
@@ -61,13 +69,47 @@ where
     }
 }
 
+impl<T> StreamOutput for TsvWithNamesAndTypesOutput<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize_line(&mut self, line: &[u8]) -> Result<Option<Self::Row>, Self::Error> {
+        let names = match (&self.names, &self.types) {
+            (Some(names), Some(_)) => names,
+            (Some(_), None) => {
+                self.types = read_line_record(line)?
+                    .map(|record| record.iter().map(ToOwned::to_owned).collect());
+                return Ok(None);
+            }
+            (None, _) => {
+                self.names = read_line_record(line)?
+                    .map(|record| record.iter().map(ToOwned::to_owned).collect());
+                return Ok(None);
+            }
+        };
+
+        deserialize_line_with_names(line, Some(names))
+    }
+
+    fn info(&self) -> Option<Self::Info> {
+        match (&self.names, &self.types) {
+            (Some(names), Some(types)) => {
+                Some(names.iter().cloned().zip(types.iter().cloned()).collect())
+            }
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use std::{collections::HashMap, fs, path::PathBuf};
 
-    use crate::test_helpers::{TEST_STRINGS_ROW_1, TestStringsRow};
+    use crate::test_helpers::{
+        TEST_STRINGS_ROW_1, TEST_STRINGS_ROW_2, TestStringsRow, stream_deserialize,
+    };
 
     #[test]
     fn simple() -> Result<(), Box<dyn std::error::Error>> {
@@ -110,4 +152,23 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn with_stream() -> Result<(), Box<dyn std::error::Error>> {
+        let content =
+            fs::read_to_string(PathBuf::new().join("tests/files/TSVWithNamesAndTypes.tsv"))?;
+
+        let (rows, info) = stream_deserialize(
+            TsvWithNamesAndTypesOutput::<TestStringsRow>::new(),
+            content.as_bytes(),
+        )?;
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows.first().unwrap(), &*TEST_STRINGS_ROW_1);
+        assert_eq!(rows.last().unwrap(), &*TEST_STRINGS_ROW_2);
+        let info = info.unwrap();
+        assert_eq!(info.get("array1"), Some(&"Array(UInt8)".to_owned()));
+        assert_eq!(info.get("map1"), Some(&"Map(String, String)".to_owned()));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,20 @@
+use serde::Deserialize;
+use serde_aux::field_attributes::deserialize_option_number_from_string;
+
+/// The progress of a query, from a JSONEachRowWithProgress progress line or an
+/// `X-ClickHouse-Progress` header.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct JsonEachRowProgress {
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub read_rows: Option<usize>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub read_bytes: Option<usize>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub total_rows_to_read: Option<usize>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub written_rows: Option<usize>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub written_bytes: Option<usize>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub elapsed_ns: Option<usize>,
+}
@@ -0,0 +1,591 @@
+// Checks a row against the column types before it is encoded.
+//
+// RowBinary isn't self-describing, so a field whose Rust type doesn't match its column
+// would otherwise be written as is and misread by the server, or fail there on a
+// length that is off.
+
+use core::slice;
+
+use serde::ser::{self, Serialize};
+
+use super::{
+    RowBinaryError,
+    typed::{Column, Kind},
+};
+
+/// Checks that the fields of `row`, a struct or a tuple, match the columns in order.
+pub(crate) fn check_row<T>(row: &T, columns: &[Column]) -> Result<(), RowBinaryError>
+where
+    T: Serialize + ?Sized,
+{
+    row.serialize(RowChecker { columns })
+}
+
+struct RowChecker<'a> {
+    columns: &'a [Column],
+}
+
+impl<'a> RowChecker<'a> {
+    fn fields(self, len: usize) -> Result<RowFieldsChecker<'a>, RowBinaryError> {
+        if len != self.columns.len() {
+            return Err(self.mismatch(&format!("{len} fields")));
+        }
+        Ok(RowFieldsChecker {
+            columns: self.columns.iter(),
+        })
+    }
+
+    fn mismatch(&self, got: &str) -> RowBinaryError {
+        RowBinaryError::ColumnsMismatch(format!(
+            "{} columns can't be written from {got}",
+            self.columns.len()
+        ))
+    }
+
+    fn unsupported(self) -> Result<(), RowBinaryError> {
+        Err(self.mismatch("a value that is not a struct or a tuple"))
+    }
+}
+
+macro_rules! unsupported_row {
+    ($($method:ident $ty:ty),*) => {
+        $(
+            fn $method(self, _v: $ty) -> Result<(), RowBinaryError> {
+                self.unsupported()
+            }
+        )*
+    };
+}
+
+impl<'a> ser::Serializer for RowChecker<'a> {
+    type Ok = ();
+    type Error = RowBinaryError;
+
+    type SerializeSeq = ser::Impossible<(), RowBinaryError>;
+    type SerializeTuple = RowFieldsChecker<'a>;
+    type SerializeTupleStruct = RowFieldsChecker<'a>;
+    type SerializeTupleVariant = ser::Impossible<(), RowBinaryError>;
+    type SerializeMap = ser::Impossible<(), RowBinaryError>;
+    type SerializeStruct = RowFieldsChecker<'a>;
+    type SerializeStructVariant = ser::Impossible<(), RowBinaryError>;
+
+    unsupported_row! {
+        serialize_bool bool, serialize_i8 i8, serialize_i16 i16, serialize_i32 i32,
+        serialize_i64 i64, serialize_i128 i128, serialize_u8 u8, serialize_u16 u16,
+        serialize_u32 u32, serialize_u64 u64, serialize_u128 u128, serialize_f32 f32,
+        serialize_f64 f64, serialize_char char, serialize_str &str, serialize_bytes &[u8],
+        serialize_unit_struct &'static str
+    }
+
+    fn serialize_none(self) -> Result<(), RowBinaryError> {
+        self.unsupported()
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.unsupported()
+    }
+
+    fn serialize_unit(self) -> Result<(), RowBinaryError> {
+        self.unsupported()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), RowBinaryError> {
+        self.unsupported()
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.unsupported()
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, RowBinaryError> {
+        Err(self.mismatch("a sequence"))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, RowBinaryError> {
+        self.fields(len)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, RowBinaryError> {
+        self.fields(len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, RowBinaryError> {
+        Err(RowBinaryError::Unsupported("enum"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, RowBinaryError> {
+        Err(self.mismatch("a map"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, RowBinaryError> {
+        self.fields(len)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, RowBinaryError> {
+        Err(RowBinaryError::Unsupported("enum"))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+struct RowFieldsChecker<'a> {
+    columns: slice::Iter<'a, Column>,
+}
+
+impl RowFieldsChecker<'_> {
+    fn check_field<T>(&mut self, value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        // The length is checked up front, but it is only what the Serialize impl claims.
+        let column = self.columns.next().ok_or_else(|| {
+            RowBinaryError::ColumnsMismatch("a row has more fields than the columns".to_owned())
+        })?;
+        value.serialize(ValueChecker::new(&column.kind, column))
+    }
+
+    fn end(self) -> Result<(), RowBinaryError> {
+        match self.columns.as_slice().first() {
+            Some(column) => Err(RowBinaryError::ColumnsMismatch(format!(
+                "a row has no field for column {}",
+                column.name
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl ser::SerializeTuple for RowFieldsChecker<'_> {
+    type Ok = ();
+    type Error = RowBinaryError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.check_field(value)
+    }
+
+    fn end(self) -> Result<(), RowBinaryError> {
+        RowFieldsChecker::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for RowFieldsChecker<'_> {
+    type Ok = ();
+    type Error = RowBinaryError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.check_field(value)
+    }
+
+    fn end(self) -> Result<(), RowBinaryError> {
+        RowFieldsChecker::end(self)
+    }
+}
+
+impl ser::SerializeStruct for RowFieldsChecker<'_> {
+    type Ok = ();
+    type Error = RowBinaryError;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.check_field(value)
+    }
+
+    fn end(self) -> Result<(), RowBinaryError> {
+        RowFieldsChecker::end(self)
+    }
+}
+
+static KIND_UINT8: Kind = Kind::UInt8;
+
+// Checks a value against the kind of its column, or of an element of it.
+struct ValueChecker<'a> {
+    kind: &'a Kind,
+    // For error messages.
+    column: &'a Column,
+}
+
+impl<'a> ValueChecker<'a> {
+    fn new(kind: &'a Kind, column: &'a Column) -> Self {
+        let kind = match kind {
+            Kind::LowCardinality(kind) => kind,
+            kind => kind,
+        };
+        Self { kind, column }
+    }
+
+    fn mismatch(&self, got: &str) -> RowBinaryError {
+        let mut msg = format!(
+            "column {} of type {} can't be written from {got}",
+            self.column.name, self.column.type_name
+        );
+        if matches!(self.kind, Kind::Nullable(_)) {
+            msg.push_str(", Nullable needs an Option");
+        }
+        RowBinaryError::ColumnsMismatch(msg)
+    }
+
+    fn check(&self, is_ok: bool, got: &str) -> Result<(), RowBinaryError> {
+        if is_ok {
+            Ok(())
+        } else {
+            Err(self.mismatch(got))
+        }
+    }
+
+    fn elements(self, kinds: ElementKinds<'a>) -> ElementsChecker<'a> {
+        ElementsChecker {
+            kinds,
+            column: self.column,
+        }
+    }
+}
+
+macro_rules! check_kind {
+    ($($method:ident $ty:ty => $pattern:pat),*) => {
+        $(
+            fn $method(self, _v: $ty) -> Result<(), RowBinaryError> {
+                self.check(matches!(self.kind, $pattern), stringify!($ty))
+            }
+        )*
+    };
+}
+
+impl<'a> ser::Serializer for ValueChecker<'a> {
+    type Ok = ();
+    type Error = RowBinaryError;
+
+    type SerializeSeq = ElementsChecker<'a>;
+    type SerializeTuple = ElementsChecker<'a>;
+    type SerializeTupleStruct = ElementsChecker<'a>;
+    type SerializeTupleVariant = ser::Impossible<(), RowBinaryError>;
+    type SerializeMap = MapChecker<'a>;
+    type SerializeStruct = ElementsChecker<'a>;
+    type SerializeStructVariant = ser::Impossible<(), RowBinaryError>;
+
+    check_kind! {
+        serialize_bool bool => Kind::UInt8,
+        serialize_u8 u8 => Kind::UInt8,
+        serialize_u16 u16 => Kind::UInt16,
+        serialize_u32 u32 => Kind::UInt32,
+        serialize_u64 u64 => Kind::UInt64,
+        serialize_i8 i8 => Kind::Int8 | Kind::Enum8(_),
+        serialize_i16 i16 => Kind::Int16 | Kind::Enum16(_),
+        serialize_i32 i32 => Kind::Int32,
+        serialize_i64 i64 => Kind::Int64,
+        serialize_i128 i128 => Kind::Int128,
+        serialize_f32 f32 => Kind::Float32,
+        serialize_f64 f64 => Kind::Float64,
+        serialize_char char => Kind::String,
+        serialize_str &str => Kind::String,
+        serialize_bytes &[u8] => Kind::String
+    }
+
+    fn serialize_u128(self, _v: u128) -> Result<(), RowBinaryError> {
+        Err(self.mismatch("u128"))
+    }
+
+    fn serialize_none(self) -> Result<(), RowBinaryError> {
+        self.check(matches!(self.kind, Kind::Nullable(_)), "None")
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        match self.kind {
+            Kind::Nullable(kind) if !matches!(**kind, Kind::Nothing) => {
+                value.serialize(ValueChecker::new(kind, self.column))
+            }
+            _ => Err(self.mismatch("Some")),
+        }
+    }
+
+    fn serialize_unit(self) -> Result<(), RowBinaryError> {
+        self.check(matches!(self.kind, Kind::Nothing), "unit")
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), RowBinaryError> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), RowBinaryError> {
+        Err(RowBinaryError::Unsupported("enum"))
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(RowBinaryError::Unsupported("enum"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, RowBinaryError> {
+        match self.kind {
+            Kind::Array(kind) => Ok(self.elements(ElementKinds::Same(kind))),
+            _ => Err(self.mismatch("a sequence")),
+        }
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, RowBinaryError> {
+        match (self.kind, self.kind.fixed_len()) {
+            (Kind::Tuple(kinds), _) if kinds.len() == len => {
+                Ok(self.elements(ElementKinds::Each(kinds.iter())))
+            }
+            // e.g. `[u8; 16]` for a UUID.
+            (_, Some(n)) if n == len => Ok(self.elements(ElementKinds::Same(&KIND_UINT8))),
+            _ => Err(self.mismatch(&format!("a tuple of {len}"))),
+        }
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, RowBinaryError> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, RowBinaryError> {
+        Err(RowBinaryError::Unsupported("enum"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, RowBinaryError> {
+        match self.kind {
+            Kind::Map(key, value) => Ok(MapChecker {
+                key,
+                value,
+                column: self.column,
+            }),
+            _ => Err(self.mismatch("a map")),
+        }
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, RowBinaryError> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, RowBinaryError> {
+        Err(RowBinaryError::Unsupported("enum"))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+enum ElementKinds<'a> {
+    Same(&'a Kind),
+    Each(slice::Iter<'a, Kind>),
+}
+
+struct ElementsChecker<'a> {
+    kinds: ElementKinds<'a>,
+    column: &'a Column,
+}
+
+impl ElementsChecker<'_> {
+    fn check_element<T>(&mut self, value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let kind = match &mut self.kinds {
+            ElementKinds::Same(kind) => *kind,
+            ElementKinds::Each(kinds) => kinds.next().ok_or_else(|| {
+                RowBinaryError::ColumnsMismatch(format!(
+                    "column {} of type {} has fewer elements than the value",
+                    self.column.name, self.column.type_name
+                ))
+            })?,
+        };
+        value.serialize(ValueChecker::new(kind, self.column))
+    }
+}
+
+impl ser::SerializeSeq for ElementsChecker<'_> {
+    type Ok = ();
+    type Error = RowBinaryError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.check_element(value)
+    }
+
+    fn end(self) -> Result<(), RowBinaryError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for ElementsChecker<'_> {
+    type Ok = ();
+    type Error = RowBinaryError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.check_element(value)
+    }
+
+    fn end(self) -> Result<(), RowBinaryError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for ElementsChecker<'_> {
+    type Ok = ();
+    type Error = RowBinaryError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.check_element(value)
+    }
+
+    fn end(self) -> Result<(), RowBinaryError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for ElementsChecker<'_> {
+    type Ok = ();
+    type Error = RowBinaryError;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.check_element(value)
+    }
+
+    fn end(self) -> Result<(), RowBinaryError> {
+        Ok(())
+    }
+}
+
+struct MapChecker<'a> {
+    key: &'a Kind,
+    value: &'a Kind,
+    column: &'a Column,
+}
+
+impl ser::SerializeMap for MapChecker<'_> {
+    type Ok = ();
+    type Error = RowBinaryError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        key.serialize(ValueChecker::new(self.key, self.column))
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(ValueChecker::new(self.value, self.column))
+    }
+
+    fn end(self) -> Result<(), RowBinaryError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,402 @@
+use serde::de::{self, Deserialize, DeserializeSeed, Visitor};
+
+use super::{RowBinaryError, read_leb128};
+
+/// Decodes a single value, `slice` must not have any bytes left over.
+pub fn from_slice<'de, T>(slice: &'de [u8]) -> Result<T, RowBinaryError>
+where
+    T: Deserialize<'de>,
+{
+    let mut de = Deserializer::new(slice);
+    let value = T::deserialize(&mut de)?;
+    if !de.is_empty() {
+        return Err(RowBinaryError::TrailingBytes(de.input.len()));
+    }
+    Ok(value)
+}
+
+pub struct Deserializer<'de> {
+    input: &'de [u8],
+}
+
+impl<'de> Deserializer<'de> {
+    pub fn new(input: &'de [u8]) -> Self {
+        Self { input }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.input.is_empty()
+    }
+    #[cfg(feature = "with-native")]
+    pub(super) fn remaining(&self) -> usize {
+        self.input.len()
+    }
+
+    pub(super) fn read(&mut self, n: usize) -> Result<&'de [u8], RowBinaryError> {
+        if self.input.len() < n {
+            return Err(RowBinaryError::UnexpectedEof);
+        }
+        let (bytes, rest) = self.input.split_at(n);
+        self.input = rest;
+        Ok(bytes)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], RowBinaryError> {
+        let mut bytes = [0; N];
+        bytes.copy_from_slice(self.read(N)?);
+        Ok(bytes)
+    }
+
+    pub(super) fn read_len(&mut self) -> Result<usize, RowBinaryError> {
+        usize::try_from(read_leb128(&mut self.input)?).map_err(|_| RowBinaryError::LengthInvalid)
+    }
+
+    pub(super) fn read_bytes(&mut self) -> Result<&'de [u8], RowBinaryError> {
+        let len = self.read_len()?;
+        self.read(len)
+    }
+
+    pub(super) fn read_str(&mut self) -> Result<&'de str, RowBinaryError> {
+        Ok(core::str::from_utf8(self.read_bytes()?)?)
+    }
+}
+
+macro_rules! deserialize_le {
+    ($($method:ident $ty:ident $visit:ident),*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+            where
+                V: Visitor<'de>,
+            {
+                visitor.$visit($ty::from_le_bytes(self.read_array()?))
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = RowBinaryError;
+
+    deserialize_le! {
+        deserialize_i8 i8 visit_i8, deserialize_i16 i16 visit_i16, deserialize_i32 i32 visit_i32,
+        deserialize_i64 i64 visit_i64, deserialize_i128 i128 visit_i128,
+        deserialize_u8 u8 visit_u8, deserialize_u16 u16 visit_u16, deserialize_u32 u32 visit_u32,
+        deserialize_u64 u64 visit_u64, deserialize_u128 u128 visit_u128,
+        deserialize_f32 f32 visit_f32, deserialize_f64 f64 visit_f64
+    }
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        Err(RowBinaryError::Unsupported("deserialize_any"))
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        match self.read_array::<1>()? {
+            [0] => visitor.visit_bool(false),
+            [1] => visitor.visit_bool(true),
+            [b] => Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(b.into()),
+                &"0 or 1",
+            )),
+        }
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        let s = self.read_str()?;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => visitor.visit_char(c),
+            _ => Err(de::Error::invalid_value(de::Unexpected::Str(s), &"a char")),
+        }
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_borrowed_str(self.read_str()?)
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_borrowed_bytes(self.read_bytes()?)
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        match self.read_array::<1>()? {
+            [0] => visitor.visit_some(self),
+            [1] => visitor.visit_none(),
+            [b] => Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(b.into()),
+                &"a Nullable marker",
+            )),
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        let len = self.read_len()?;
+        visitor.visit_seq(Access { de: self, len })
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(Access { de: self, len })
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        let len = self.read_len()?;
+        visitor.visit_map(Access { de: self, len })
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        Err(RowBinaryError::Unsupported("enum"))
+    }
+
+    fn deserialize_identifier<V>(self, _visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        Err(RowBinaryError::Unsupported("deserialize_identifier"))
+    }
+
+    fn deserialize_ignored_any<V>(self, _visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        Err(RowBinaryError::Unsupported("deserialize_ignored_any"))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+struct Access<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    len: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for Access<'_, 'de> {
+    type Error = RowBinaryError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, RowBinaryError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Access<'_, 'de> {
+    type Error = RowBinaryError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, RowBinaryError>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::row_binary::to_writer;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Row {
+        id: u8,
+        name: String,
+        nickname: Option<String>,
+        tags: Vec<String>,
+        attrs: BTreeMap<String, i32>,
+        point: (f64, f64),
+        big: i128,
+        fixed: [u8; 4],
+    }
+
+    #[test]
+    fn test_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let row = Row {
+            id: 1,
+            name: "a".repeat(200),
+            nickname: None,
+            tags: vec!["x".into(), "y".into()],
+            attrs: vec![("k".into(), -1)].into_iter().collect(),
+            point: (1.5, -2.0),
+            big: i128::MIN,
+            fixed: *b"abcd",
+        };
+
+        let mut buf = vec![];
+        to_writer(&mut buf, &row)?;
+
+        let mut expected = vec![0x01, 0xc8, 0x01];
+        expected.extend_from_slice("a".repeat(200).as_bytes());
+        expected.push(0x01);
+        expected.extend_from_slice(&[0x02, 0x01, b'x', 0x01, b'y']);
+        expected.extend_from_slice(&[0x01, 0x01, b'k', 0xff, 0xff, 0xff, 0xff]);
+        expected.extend_from_slice(&1.5_f64.to_le_bytes());
+        expected.extend_from_slice(&(-2.0_f64).to_le_bytes());
+        expected.extend_from_slice(&i128::MIN.to_le_bytes());
+        expected.extend_from_slice(b"abcd");
+        assert_eq!(buf, expected);
+
+        assert_eq!(from_slice::<Row>(&buf)?, row);
+
+        let mut buf = vec![];
+        to_writer(&mut buf, &Some("b"))?;
+        assert_eq!(buf, vec![0x00, 0x01, b'b']);
+        assert_eq!(from_slice::<Option<String>>(&buf)?, Some("b".into()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(matches!(
+            from_slice::<u32>(&[0x01, 0x02]),
+            Err(RowBinaryError::UnexpectedEof)
+        ));
+        assert!(matches!(
+            from_slice::<u8>(&[0x01, 0x02]),
+            Err(RowBinaryError::TrailingBytes(1))
+        ));
+        assert!(matches!(
+            from_slice::<Option<u8>>(&[0x02, 0x01]),
+            Err(RowBinaryError::Custom(_))
+        ));
+        assert!(matches!(
+            from_slice::<String>(&[0x01, 0xff]),
+            Err(RowBinaryError::Utf8Error(_))
+        ));
+        assert!(matches!(
+            from_slice::<serde::de::IgnoredAny>(&[0x00]),
+            Err(RowBinaryError::Unsupported(_))
+        ));
+    }
+}
@@ -0,0 +1,118 @@
+// serde support for the RowBinary encoding.
+//
+// Values are little-endian, String and Array/Map are prefixed by a LEB128 length,
+// Nullable by a 0/1 marker. Tuple and struct fields are written back to back.
+// FixedString(N), Int256 and UInt256 map to `[u8; N]`.
+//
+// The encoding isn't self-describing, so `deserialize_any` and enums are not supported,
+// unless the column types are known, see `typed`.
+
+use core::fmt;
+
+#[cfg(feature = "with-native")]
+mod check;
+pub mod de;
+#[cfg(feature = "with-native")]
+pub(crate) mod native;
+pub mod ser;
+pub(crate) mod typed;
+
+pub use self::{
+    de::{Deserializer, from_slice},
+    ser::{Serializer, to_writer},
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum RowBinaryError {
+    #[error("Custom {0}")]
+    Custom(String),
+    #[error("Unsupported {0}")]
+    Unsupported(&'static str),
+    #[error("UnexpectedEof")]
+    UnexpectedEof,
+    #[error("LengthInvalid")]
+    LengthInvalid,
+    #[error("Utf8Error {0:?}")]
+    Utf8Error(#[from] core::str::Utf8Error),
+    #[error("TrailingBytes {0}")]
+    TrailingBytes(usize),
+    #[error("TypeNameParseError {0:?}")]
+    TypeNameParseError(#[from] clickhouse_data_type::ParseError),
+    #[error("ColumnsMismatch {0}")]
+    ColumnsMismatch(String),
+    #[error("TypeMismatch {0}")]
+    TypeMismatch(String),
+}
+
+impl serde::ser::Error for RowBinaryError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+impl serde::de::Error for RowBinaryError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+pub(crate) fn write_leb128(buf: &mut Vec<u8>, mut val: u64) {
+    loop {
+        let byte = (val & 0x7f) as u8;
+        val >>= 7;
+        if val == 0 {
+            buf.push(byte);
+            break;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+pub(crate) fn read_leb128(slice: &mut &[u8]) -> Result<u64, RowBinaryError> {
+    let mut val = 0_u64;
+    for shift in (0..64).step_by(7) {
+        let (byte, rest) = slice.split_first().ok_or(RowBinaryError::UnexpectedEof)?;
+        *slice = rest;
+
+        val |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(val);
+        }
+    }
+    Err(RowBinaryError::LengthInvalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leb128() -> Result<(), Box<dyn std::error::Error>> {
+        for (val, bytes) in [
+            (0_u64, vec![0x00]),
+            (127, vec![0x7f]),
+            (128, vec![0x80, 0x01]),
+            (300, vec![0xac, 0x02]),
+            (u64::MAX, vec![0xff; 9].into_iter().chain([0x01]).collect()),
+        ] {
+            let mut buf = vec![];
+            write_leb128(&mut buf, val);
+            assert_eq!(buf, bytes);
+
+            let mut slice = &buf[..];
+            assert_eq!(read_leb128(&mut slice)?, val);
+            assert!(slice.is_empty());
+        }
+
+        assert!(matches!(
+            read_leb128(&mut &[0x80][..]),
+            Err(RowBinaryError::UnexpectedEof)
+        ));
+        assert!(matches!(
+            read_leb128(&mut &[0xff; 10][..]),
+            Err(RowBinaryError::LengthInvalid)
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,743 @@
+// The Native format, a block is the column count, the row count, then for each column
+// the name, the type and the values of all rows.
+//
+// Columns are decoded to RowBinary values, so that rows go through the same serde code,
+// including the checks of the column types. The values of the fixed-width types are the same
+// in both, so they are read in place.
+//
+// Column layouts:
+//   Nullable(T), a null map of one byte per row, then T with placeholders for NULL
+//   Array(T), the end offset of each row as UInt64, then T for all the elements
+//   Map(K, V), like Array(Tuple(K, V))
+//   Tuple(T1, T2), T1 for all rows, then T2 for all rows
+//   LowCardinality(T), a version before any data, then the flags, the dictionary and
+//   the indexes. For LowCardinality(Nullable(T)), the dictionary is T and 0 is NULL.
+
+use std::collections::HashMap;
+
+use clickhouse_data_type::type_name::TypeName;
+use serde::Serialize;
+
+use super::{
+    Deserializer, RowBinaryError,
+    check::check_row,
+    read_leb128, to_writer,
+    typed::{Column, Kind},
+    write_leb128,
+};
+
+// SharedDictionariesWithAdditionalKeys
+const LOW_CARDINALITY_VERSION: u64 = 1;
+
+const LOW_CARDINALITY_INDEX_TYPE_MASK: u64 = 0xff;
+const LOW_CARDINALITY_NEED_GLOBAL_DICTIONARY: u64 = 1 << 8;
+const LOW_CARDINALITY_HAS_ADDITIONAL_KEYS: u64 = 1 << 9;
+const LOW_CARDINALITY_NEED_UPDATE_DICTIONARY: u64 = 1 << 10;
+
+pub(crate) struct Block<'a> {
+    pub(crate) columns: Vec<Column>,
+    pub(crate) num_rows: usize,
+    // The values of each column, empty without rows.
+    values: Vec<Values<'a>>,
+}
+
+impl Block<'_> {
+    /// The RowBinary values of row `i`, one per column.
+    pub(crate) fn row(&self, i: usize) -> Vec<&[u8]> {
+        self.values.iter().map(|values| values.get(i)).collect()
+    }
+}
+
+/// Reads a block, the values are RowBinary encoded.
+pub(crate) fn read_block<'a>(de: &mut Deserializer<'a>) -> Result<Block<'a>, RowBinaryError> {
+    let num_columns = de.read_len()?;
+    let num_rows = de.read_len()?;
+    // Every column has a name and a type, and every value at least one byte.
+    if num_columns > de.remaining()
+        || num_rows > de.remaining()
+        || (num_rows > 0 && num_columns == 0)
+    {
+        return Err(RowBinaryError::LengthInvalid);
+    }
+
+    let mut columns = Vec::with_capacity(num_columns);
+    let mut values = Vec::with_capacity(num_columns);
+    for _ in 0..num_columns {
+        let name = de.read_str()?.to_owned();
+        let type_name = de.read_str()?.to_owned();
+        let column = Column::new(name, type_name)?;
+
+        // Zero rows have no data at all.
+        if num_rows > 0 {
+            read_prefix(de, &column.kind)?;
+            values.push(read_values(de, &column.kind, num_rows)?);
+        }
+
+        columns.push(column);
+    }
+
+    Ok(Block {
+        columns,
+        num_rows,
+        values,
+    })
+}
+
+/// Writes the rows as a block, after checking their fields against the columns.
+pub(crate) fn write_block<T>(
+    buf: &mut Vec<u8>,
+    columns: &[(String, TypeName)],
+    rows: &[T],
+) -> Result<(), RowBinaryError>
+where
+    T: Serialize,
+{
+    let columns = columns
+        .iter()
+        .map(|(name, type_name)| {
+            Ok(Column {
+                name: name.to_owned(),
+                type_name: type_name.to_string(),
+                kind: Kind::new(type_name)?,
+            })
+        })
+        .collect::<Result<Vec<_>, RowBinaryError>>()?;
+
+    let rows = rows
+        .iter()
+        .map(|row| {
+            check_row(row, &columns)?;
+            let mut buf = vec![];
+            to_writer(&mut buf, row)?;
+            Ok(buf)
+        })
+        .collect::<Result<Vec<_>, RowBinaryError>>()?;
+
+    let mut values: Vec<Vec<&[u8]>> = vec![Vec::with_capacity(rows.len()); columns.len()];
+    for row in &rows {
+        let mut slice = &row[..];
+        for (column, values) in columns.iter().zip(values.iter_mut()) {
+            values.push(split_value(&column.kind, &mut slice)?);
+        }
+        if !slice.is_empty() {
+            return Err(RowBinaryError::ColumnsMismatch(format!(
+                "a row has more values than the {} columns",
+                columns.len()
+            )));
+        }
+    }
+
+    write_leb128(buf, columns.len() as u64);
+    write_leb128(buf, rows.len() as u64);
+    for (column, values) in columns.iter().zip(&values) {
+        write_str(buf, &column.name);
+        write_str(buf, &column.type_name);
+
+        if !rows.is_empty() {
+            write_prefix(buf, &column.kind);
+            write_values(buf, &column.kind, values)?;
+        }
+    }
+
+    Ok(())
+}
+
+// The types that are the same in RowBinary and Native, one value after another.
+fn size(kind: &Kind) -> Option<usize> {
+    match kind {
+        Kind::UInt8 | Kind::Int8 | Kind::Enum8(_) => Some(1),
+        Kind::UInt16 | Kind::Int16 | Kind::Enum16(_) => Some(2),
+        Kind::UInt32 | Kind::Int32 | Kind::Float32 => Some(4),
+        Kind::UInt64 | Kind::Int64 | Kind::Float64 => Some(8),
+        Kind::Int128 | Kind::Uuid => Some(16),
+        Kind::FixedString(n) | Kind::Bytes(n) => Some(*n),
+        _ => None,
+    }
+}
+
+fn read_u64(de: &mut Deserializer<'_>) -> Result<u64, RowBinaryError> {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(de.read(8)?);
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_usize(de: &mut Deserializer<'_>) -> Result<usize, RowBinaryError> {
+    usize::try_from(read_u64(de)?).map_err(|_| RowBinaryError::LengthInvalid)
+}
+
+fn read_prefix(de: &mut Deserializer<'_>, kind: &Kind) -> Result<(), RowBinaryError> {
+    match kind {
+        Kind::LowCardinality(_) => match read_u64(de)? {
+            LOW_CARDINALITY_VERSION => Ok(()),
+            version => Err(RowBinaryError::Custom(format!(
+                "LowCardinality version {version} is not supported"
+            ))),
+        },
+        Kind::Nullable(kind) | Kind::Array(kind) => read_prefix(de, kind),
+        Kind::Map(key, value) => {
+            read_prefix(de, key)?;
+            read_prefix(de, value)
+        }
+        Kind::Tuple(kinds) => kinds.iter().try_for_each(|kind| read_prefix(de, kind)),
+        _ => Ok(()),
+    }
+}
+
+// The RowBinary bytes of the values of a column, one after another.
+enum Values<'a> {
+    // The types that are the same in RowBinary and Native are not copied.
+    Fixed { bytes: &'a [u8], size: usize },
+    Owned { bytes: Vec<u8>, ends: Vec<usize> },
+}
+
+impl<'a> Values<'a> {
+    fn with_capacity(n: usize) -> Self {
+        Self::Owned {
+            bytes: vec![],
+            ends: Vec::with_capacity(n),
+        }
+    }
+
+    // The values from `start` to `end`, as a single slice.
+    fn range(&self, start: usize, end: usize) -> &[u8] {
+        match self {
+            Self::Fixed { bytes, size } => &bytes[start * size..end * size],
+            Self::Owned { bytes, ends } => {
+                let start = if start == 0 { 0 } else { ends[start - 1] };
+                let end = if end == 0 { 0 } else { ends[end - 1] };
+                &bytes[start..end]
+            }
+        }
+    }
+
+    fn get(&self, i: usize) -> &[u8] {
+        self.range(i, i + 1)
+    }
+
+    // Appends a value, written by `f`.
+    fn push(&mut self, f: impl FnOnce(&mut Vec<u8>)) {
+        if let Self::Owned { bytes, ends } = self {
+            f(bytes);
+            ends.push(bytes.len());
+        }
+    }
+}
+
+fn read_values<'a>(
+    de: &mut Deserializer<'a>,
+    kind: &Kind,
+    n: usize,
+) -> Result<Values<'a>, RowBinaryError> {
+    // The counts come from the input, so they are not trusted for allocating.
+    if n > de.remaining() {
+        return Err(RowBinaryError::LengthInvalid);
+    }
+
+    if let Some(size) = size(kind) {
+        let len = size.checked_mul(n).ok_or(RowBinaryError::LengthInvalid)?;
+        return Ok(Values::Fixed {
+            bytes: de.read(len)?,
+            size,
+        });
+    }
+
+    let mut values = Values::with_capacity(n);
+    match kind {
+        Kind::String => {
+            for _ in 0..n {
+                let bytes = de.read_bytes()?;
+                values.push(|buf| {
+                    write_leb128(buf, bytes.len() as u64);
+                    buf.extend_from_slice(bytes);
+                });
+            }
+        }
+        // One placeholder byte per row.
+        Kind::Nothing => {
+            de.read(n)?;
+            (0..n).for_each(|_| values.push(|_| {}));
+        }
+        Kind::Nullable(kind) => {
+            let null_map = de.read(n)?;
+            let inner = read_values(de, kind, n)?;
+            for (i, is_null) in null_map.iter().enumerate() {
+                values.push(|buf| match is_null {
+                    0 => {
+                        buf.push(0);
+                        buf.extend_from_slice(inner.get(i));
+                    }
+                    _ => buf.push(1),
+                });
+            }
+        }
+        Kind::Array(kind) => {
+            let offsets = read_offsets(de, n)?;
+            let elements = read_values(de, kind, offsets.last().copied().unwrap_or_default())?;
+            let mut start = 0;
+            for end in offsets {
+                values.push(|buf| {
+                    write_leb128(buf, (end - start) as u64);
+                    buf.extend_from_slice(elements.range(start, end));
+                });
+                start = end;
+            }
+        }
+        Kind::Map(key, value) => {
+            let offsets = read_offsets(de, n)?;
+            let len = offsets.last().copied().unwrap_or_default();
+            let keys = read_values(de, key, len)?;
+            let map_values = read_values(de, value, len)?;
+            let mut start = 0;
+            for end in offsets {
+                values.push(|buf| {
+                    write_leb128(buf, (end - start) as u64);
+                    for i in start..end {
+                        buf.extend_from_slice(keys.get(i));
+                        buf.extend_from_slice(map_values.get(i));
+                    }
+                });
+                start = end;
+            }
+        }
+        Kind::Tuple(kinds) => {
+            let columns = kinds
+                .iter()
+                .map(|kind| read_values(de, kind, n))
+                .collect::<Result<Vec<_>, _>>()?;
+            for i in 0..n {
+                values.push(|buf| {
+                    for column in &columns {
+                        buf.extend_from_slice(column.get(i));
+                    }
+                });
+            }
+        }
+        Kind::LowCardinality(kind) => read_low_cardinality_values(de, kind, n, &mut values)?,
+        _ => return Err(RowBinaryError::Unsupported("Native column")),
+    }
+    Ok(values)
+}
+
+// The offsets are where each row ends.
+fn read_offsets(de: &mut Deserializer<'_>, n: usize) -> Result<Vec<usize>, RowBinaryError> {
+    let offsets = (0..n)
+        .map(|_| read_usize(de))
+        .collect::<Result<Vec<_>, _>>()?;
+    if offsets.windows(2).any(|x| x[0] > x[1]) {
+        return Err(RowBinaryError::LengthInvalid);
+    }
+    Ok(offsets)
+}
+
+fn read_low_cardinality_values(
+    de: &mut Deserializer<'_>,
+    kind: &Kind,
+    n: usize,
+    values: &mut Values<'_>,
+) -> Result<(), RowBinaryError> {
+    let (key_kind, is_nullable) = match kind {
+        Kind::Nullable(kind) => (&**kind, true),
+        kind => (kind, false),
+    };
+
+    let mut num_values = 0;
+    let mut keys = None;
+    while num_values < n {
+        let flags = read_u64(de)?;
+        if flags & (LOW_CARDINALITY_NEED_GLOBAL_DICTIONARY | LOW_CARDINALITY_NEED_UPDATE_DICTIONARY)
+            != 0
+        {
+            return Err(RowBinaryError::Unsupported(
+                "LowCardinality with a global dictionary",
+            ));
+        }
+        if flags & LOW_CARDINALITY_HAS_ADDITIONAL_KEYS != 0 {
+            let num_keys = read_usize(de)?;
+            keys = Some((read_values(de, key_kind, num_keys)?, num_keys));
+        }
+
+        let num_indexes = read_usize(de)?;
+        if num_indexes == 0 || num_indexes > n - num_values {
+            return Err(RowBinaryError::LengthInvalid);
+        }
+        let width = match flags & LOW_CARDINALITY_INDEX_TYPE_MASK {
+            0 => 1,
+            1 => 2,
+            2 => 4,
+            3 => 8,
+            _ => return Err(RowBinaryError::Unsupported("LowCardinality index type")),
+        };
+
+        for index in de.read(width * num_indexes)?.chunks(width) {
+            let mut bytes = [0; 8];
+            bytes[..width].copy_from_slice(index);
+            let index = u64::from_le_bytes(bytes) as usize;
+
+            let key = match &keys {
+                Some((keys, num_keys)) if index < *num_keys => keys.get(index),
+                _ => return Err(RowBinaryError::LengthInvalid),
+            };
+            values.push(|buf| match (is_nullable, index) {
+                (true, 0) => buf.push(1),
+                (true, _) => {
+                    buf.push(0);
+                    buf.extend_from_slice(key);
+                }
+                (false, _) => buf.extend_from_slice(key),
+            });
+        }
+        num_values += num_indexes;
+    }
+
+    Ok(())
+}
+
+fn take<'a>(slice: &mut &'a [u8], n: usize) -> Result<&'a [u8], RowBinaryError> {
+    if slice.len() < n {
+        return Err(RowBinaryError::UnexpectedEof);
+    }
+    let (bytes, rest) = slice.split_at(n);
+    *slice = rest;
+    Ok(bytes)
+}
+
+fn take_len(slice: &mut &[u8]) -> Result<usize, RowBinaryError> {
+    usize::try_from(read_leb128(slice)?).map_err(|_| RowBinaryError::LengthInvalid)
+}
+
+// The RowBinary bytes of the next value.
+fn split_value<'a>(kind: &Kind, slice: &mut &'a [u8]) -> Result<&'a [u8], RowBinaryError> {
+    let start = *slice;
+    skip_value(kind, slice)?;
+    Ok(&start[..start.len() - slice.len()])
+}
+
+fn skip_value(kind: &Kind, slice: &mut &[u8]) -> Result<(), RowBinaryError> {
+    match kind {
+        Kind::String => {
+            let len = take_len(slice)?;
+            take(slice, len)?;
+        }
+        Kind::Nothing => {}
+        Kind::Nullable(kind) => {
+            if take(slice, 1)?[0] == 0 {
+                skip_value(kind, slice)?;
+            }
+        }
+        Kind::Array(kind) => {
+            for _ in 0..take_len(slice)? {
+                skip_value(kind, slice)?;
+            }
+        }
+        Kind::Map(key, value) => {
+            for _ in 0..take_len(slice)? {
+                skip_value(key, slice)?;
+                skip_value(value, slice)?;
+            }
+        }
+        Kind::Tuple(kinds) => {
+            for kind in kinds {
+                skip_value(kind, slice)?;
+            }
+        }
+        Kind::LowCardinality(kind) => skip_value(kind, slice)?,
+        _ => {
+            let size = size(kind).ok_or(RowBinaryError::Unsupported("Native column"))?;
+            take(slice, size)?;
+        }
+    }
+    Ok(())
+}
+
+// The RowBinary bytes of the placeholder for NULL.
+fn default_value(kind: &Kind) -> Vec<u8> {
+    match kind {
+        Kind::String | Kind::Array(_) | Kind::Map(_, _) => vec![0],
+        Kind::Nullable(_) => vec![1],
+        Kind::Tuple(kinds) => kinds.iter().flat_map(default_value).collect(),
+        Kind::LowCardinality(kind) => default_value(kind),
+        _ => vec![0; size(kind).unwrap_or_default()],
+    }
+}
+
+fn write_u64(buf: &mut Vec<u8>, val: u64) {
+    buf.extend_from_slice(&val.to_le_bytes());
+}
+
+fn write_str(buf: &mut Vec<u8>, val: &str) {
+    write_leb128(buf, val.len() as u64);
+    buf.extend_from_slice(val.as_bytes());
+}
+
+fn write_prefix(buf: &mut Vec<u8>, kind: &Kind) {
+    match kind {
+        Kind::LowCardinality(_) => write_u64(buf, LOW_CARDINALITY_VERSION),
+        Kind::Nullable(kind) | Kind::Array(kind) => write_prefix(buf, kind),
+        Kind::Map(key, value) => {
+            write_prefix(buf, key);
+            write_prefix(buf, value);
+        }
+        Kind::Tuple(kinds) => kinds.iter().for_each(|kind| write_prefix(buf, kind)),
+        _ => {}
+    }
+}
+
+fn write_values(buf: &mut Vec<u8>, kind: &Kind, values: &[&[u8]]) -> Result<(), RowBinaryError> {
+    match kind {
+        Kind::Nothing => buf.extend(values.iter().map(|_| b'0')),
+        Kind::Nullable(kind) => {
+            let default = default_value(kind);
+            let mut inner = Vec::with_capacity(values.len());
+            for value in values {
+                let (is_null, value) = value.split_first().ok_or(RowBinaryError::UnexpectedEof)?;
+                buf.push(*is_null);
+                inner.push(if *is_null == 0 { value } else { &default[..] });
+            }
+            write_values(buf, kind, &inner)?;
+        }
+        Kind::Array(kind) => {
+            let mut elements = vec![];
+            let mut offset = 0_u64;
+            for value in values {
+                let mut slice = *value;
+                let len = take_len(&mut slice)?;
+                for _ in 0..len {
+                    elements.push(split_value(kind, &mut slice)?);
+                }
+                offset += len as u64;
+                write_u64(buf, offset);
+            }
+            write_values(buf, kind, &elements)?;
+        }
+        Kind::Map(key, value_kind) => {
+            let mut keys = vec![];
+            let mut map_values = vec![];
+            let mut offset = 0_u64;
+            for value in values {
+                let mut slice = *value;
+                let len = take_len(&mut slice)?;
+                for _ in 0..len {
+                    keys.push(split_value(key, &mut slice)?);
+                    map_values.push(split_value(value_kind, &mut slice)?);
+                }
+                offset += len as u64;
+                write_u64(buf, offset);
+            }
+            write_values(buf, key, &keys)?;
+            write_values(buf, value_kind, &map_values)?;
+        }
+        Kind::Tuple(kinds) => {
+            let mut columns = vec![Vec::with_capacity(values.len()); kinds.len()];
+            for value in values {
+                let mut slice = *value;
+                for (kind, column) in kinds.iter().zip(columns.iter_mut()) {
+                    column.push(split_value(kind, &mut slice)?);
+                }
+            }
+            for (kind, column) in kinds.iter().zip(&columns) {
+                write_values(buf, kind, column)?;
+            }
+        }
+        Kind::LowCardinality(kind) => write_low_cardinality_values(buf, kind, values)?,
+        // The other types are the same in RowBinary.
+        _ => values.iter().for_each(|value| buf.extend_from_slice(value)),
+    }
+    Ok(())
+}
+
+fn write_low_cardinality_values(
+    buf: &mut Vec<u8>,
+    kind: &Kind,
+    values: &[&[u8]],
+) -> Result<(), RowBinaryError> {
+    let (key_kind, is_nullable) = match kind {
+        Kind::Nullable(kind) => (&**kind, true),
+        kind => (kind, false),
+    };
+
+    let null_key = default_value(key_kind);
+    let mut keys: Vec<&[u8]> = vec![];
+    if is_nullable {
+        keys.push(&null_key);
+    }
+    let mut key_indexes: HashMap<&[u8], u64> = HashMap::new();
+    let mut indexes = Vec::with_capacity(values.len());
+    for value in values {
+        let key = match (is_nullable, value.split_first()) {
+            (true, Some((0, key))) => key,
+            (true, Some(_)) => {
+                indexes.push(0);
+                continue;
+            }
+            (true, None) => return Err(RowBinaryError::UnexpectedEof),
+            (false, _) => *value,
+        };
+        let index = *key_indexes.entry(key).or_insert_with(|| {
+            keys.push(key);
+            keys.len() as u64 - 1
+        });
+        indexes.push(index);
+    }
+
+    let (index_type, width) = match keys.len() {
+        0..=0xff => (0, 1),
+        0x100..=0xffff => (1, 2),
+        0x1_0000..=0xffff_ffff => (2, 4),
+        _ => (3, 8),
+    };
+    write_u64(buf, LOW_CARDINALITY_HAS_ADDITIONAL_KEYS | index_type);
+    write_u64(buf, keys.len() as u64);
+    write_values(buf, key_kind, &keys)?;
+    write_u64(buf, indexes.len() as u64);
+    for index in indexes {
+        buf.extend_from_slice(&index.to_le_bytes()[..width]);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_read_block() -> Result<(), Box<dyn std::error::Error>> {
+        // SELECT ['a', 'b'] AS arr, toLowCardinality(toNullable('x')) AS lc, CAST(NULL, 'Nullable(UInt8)') AS n
+        let mut bytes = vec![0x03, 0x01];
+        bytes.extend_from_slice(b"\x03arr\x0dArray(String)");
+        bytes.extend_from_slice(&2_u64.to_le_bytes());
+        bytes.extend_from_slice(b"\x01a\x01b");
+        bytes.extend_from_slice(b"\x02lc\x20LowCardinality(Nullable(String))");
+        bytes.extend_from_slice(&1_u64.to_le_bytes());
+        bytes.extend_from_slice(&(1_u64 << 9).to_le_bytes());
+        bytes.extend_from_slice(&2_u64.to_le_bytes());
+        bytes.extend_from_slice(b"\x00\x01x");
+        bytes.extend_from_slice(&1_u64.to_le_bytes());
+        bytes.push(0x01);
+        bytes.extend_from_slice(b"\x01n\x0fNullable(UInt8)\x01\x00");
+
+        let mut de = Deserializer::new(&bytes);
+        let block = read_block(&mut de)?;
+        assert!(de.is_empty());
+        assert_eq!(
+            block
+                .columns
+                .iter()
+                .map(|x| (x.name.as_str(), x.type_name.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("arr", "Array(String)"),
+                ("lc", "LowCardinality(Nullable(String))"),
+                ("n", "Nullable(UInt8)")
+            ]
+        );
+
+        let mut expected = vec![];
+        to_writer(&mut expected, &(vec!["a", "b"], Some("x"), None::<u8>))?;
+        assert_eq!(block.num_rows, 1);
+        assert_eq!(block.row(0).concat(), expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_block_with_invalid_len() {
+        // LEB128 of 2^61
+        let len = [0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x20];
+
+        let mut rows = vec![0x01];
+        rows.extend_from_slice(&len);
+        rows.extend_from_slice(b"\x01a\x05UInt8\x00");
+
+        let mut columns = len.to_vec();
+        columns.extend_from_slice(b"\x01\x01a\x05UInt8\x00");
+
+        let mut tuples = b"\x01\x01\x01a\x1bArray(Tuple(UInt8, String))".to_vec();
+        tuples.extend_from_slice(&(1_u64 << 61).to_le_bytes());
+        tuples.extend_from_slice(b"\x00\x00");
+
+        for bytes in [rows, columns, tuples] {
+            assert!(matches!(
+                read_block(&mut Deserializer::new(&bytes)),
+                Err(RowBinaryError::LengthInvalid)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_write_block() -> Result<(), Box<dyn std::error::Error>> {
+        let columns = vec![
+            ("id".to_owned(), "UInt32".parse()?),
+            ("tags".to_owned(), "Array(LowCardinality(String))".parse()?),
+            (
+                "name".to_owned(),
+                "LowCardinality(Nullable(String))".parse()?,
+            ),
+            (
+                "attrs".to_owned(),
+                "Map(String, Array(Nullable(Int8)))".parse()?,
+            ),
+            (
+                "point".to_owned(),
+                "Tuple(Float64, Nullable(String))".parse()?,
+            ),
+        ];
+        let rows = [
+            (
+                1_u32,
+                vec!["a", "b"],
+                Some(""),
+                BTreeMap::from([("k", vec![Some(1_i8)])]),
+                (1.5_f64, None::<&str>),
+            ),
+            (2, vec![], None, BTreeMap::new(), (-1.0, Some("p"))),
+            (
+                3,
+                vec!["b"],
+                Some("x"),
+                BTreeMap::from([("k", vec![None]), ("l", vec![Some(-1), None])]),
+                (0.0, Some("")),
+            ),
+        ];
+
+        let mut bytes = vec![];
+        write_block(&mut bytes, &columns, &rows)?;
+
+        let mut de = Deserializer::new(&bytes);
+        let block = read_block(&mut de)?;
+        assert!(de.is_empty());
+        assert_eq!(block.columns.len(), columns.len());
+        assert_eq!(block.num_rows, rows.len());
+        for (i, row) in rows.iter().enumerate() {
+            let mut expected = vec![];
+            to_writer(&mut expected, row)?;
+            assert_eq!(block.row(i).concat(), expected);
+        }
+        // UInt32 is read in place.
+        assert!(matches!(block.values[0], Values::Fixed { .. }));
+
+        // The null key, "" and "x".
+        let name_type = b"LowCardinality(Nullable(String))";
+        let name_column = &bytes[bytes
+            .windows(name_type.len())
+            .position(|x| x == name_type)
+            .unwrap()
+            + name_type.len()..];
+        assert_eq!(&name_column[..8], &1_u64.to_le_bytes());
+        assert_eq!(&name_column[8..16], &(1_u64 << 9).to_le_bytes());
+        assert_eq!(&name_column[16..24], &3_u64.to_le_bytes());
+        assert_eq!(&name_column[24..28], b"\x00\x00\x01x");
+        assert_eq!(&name_column[28..36], &3_u64.to_le_bytes());
+        assert_eq!(&name_column[36..39], &[1, 0, 2]);
+
+        let mut bytes = vec![];
+        assert!(matches!(
+            write_block(&mut bytes, &columns[..1], &rows),
+            Err(RowBinaryError::ColumnsMismatch(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,271 @@
+use serde::ser::{self, Serialize};
+
+use super::{RowBinaryError, write_leb128};
+
+/// Appends the RowBinary encoding of `value` to `buf`.
+pub fn to_writer<T>(buf: &mut Vec<u8>, value: &T) -> Result<(), RowBinaryError>
+where
+    T: Serialize + ?Sized,
+{
+    value.serialize(&mut Serializer::new(buf))
+}
+
+pub struct Serializer<'a> {
+    buf: &'a mut Vec<u8>,
+}
+
+impl<'a> Serializer<'a> {
+    pub fn new(buf: &'a mut Vec<u8>) -> Self {
+        Self { buf }
+    }
+
+    fn write_len(&mut self, len: Option<usize>) -> Result<(), RowBinaryError> {
+        let len = len.ok_or(RowBinaryError::Unsupported("sequence without length"))?;
+        write_leb128(self.buf, len as u64);
+        Ok(())
+    }
+}
+
+macro_rules! serialize_le {
+    ($($method:ident $ty:ty),*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<(), RowBinaryError> {
+                self.buf.extend_from_slice(&v.to_le_bytes());
+                Ok(())
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for &mut Serializer<'_> {
+    type Ok = ();
+    type Error = RowBinaryError;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = ser::Impossible<(), RowBinaryError>;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = ser::Impossible<(), RowBinaryError>;
+
+    serialize_le! {
+        serialize_i8 i8, serialize_i16 i16, serialize_i32 i32, serialize_i64 i64, serialize_i128 i128,
+        serialize_u8 u8, serialize_u16 u16, serialize_u32 u32, serialize_u64 u64, serialize_u128 u128,
+        serialize_f32 f32, serialize_f64 f64
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<(), RowBinaryError> {
+        self.buf.push(v as u8);
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), RowBinaryError> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), RowBinaryError> {
+        self.serialize_bytes(v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), RowBinaryError> {
+        write_leb128(self.buf, v.len() as u64);
+        self.buf.extend_from_slice(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), RowBinaryError> {
+        self.buf.push(1);
+        Ok(())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.buf.push(0);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), RowBinaryError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), RowBinaryError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), RowBinaryError> {
+        Err(RowBinaryError::Unsupported("enum"))
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(RowBinaryError::Unsupported("enum"))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, RowBinaryError> {
+        self.write_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, RowBinaryError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, RowBinaryError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, RowBinaryError> {
+        Err(RowBinaryError::Unsupported("enum"))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, RowBinaryError> {
+        self.write_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, RowBinaryError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, RowBinaryError> {
+        Err(RowBinaryError::Unsupported("enum"))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl ser::SerializeSeq for &mut Serializer<'_> {
+    type Ok = ();
+    type Error = RowBinaryError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), RowBinaryError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for &mut Serializer<'_> {
+    type Ok = ();
+    type Error = RowBinaryError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), RowBinaryError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for &mut Serializer<'_> {
+    type Ok = ();
+    type Error = RowBinaryError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), RowBinaryError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for &mut Serializer<'_> {
+    type Ok = ();
+    type Error = RowBinaryError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), RowBinaryError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut Serializer<'_> {
+    type Ok = ();
+    type Error = RowBinaryError;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<(), RowBinaryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), RowBinaryError> {
+        Ok(())
+    }
+}
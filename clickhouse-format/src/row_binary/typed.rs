@@ -0,0 +1,731 @@
+// Decoding driven by the column types of RowBinaryWithNamesAndTypes.
+//
+// Every serde hint is checked against the column type before anything is read,
+// so a Rust type that doesn't match the server schema fails on the first row
+// instead of silently misreading the bytes.
+
+use core::{slice, str};
+use std::collections::HashMap;
+
+use clickhouse_data_type::{nullable::NullableTypeName, type_name::TypeName};
+use serde::de::{self, DeserializeSeed, IntoDeserializer as _, Visitor, value::StrDeserializer};
+
+use super::{Deserializer, RowBinaryError};
+
+pub(crate) struct Column {
+    pub(crate) name: String,
+    pub(crate) type_name: String,
+    pub(super) kind: Kind,
+}
+
+impl Column {
+    pub(super) fn new(name: String, type_name: String) -> Result<Self, RowBinaryError> {
+        let kind = Kind::new(&type_name.parse()?)?;
+        Ok(Self {
+            name,
+            type_name,
+            kind,
+        })
+    }
+}
+
+/// Reads the column count, the names and the types.
+pub(crate) fn read_header(de: &mut Deserializer<'_>) -> Result<Vec<Column>, RowBinaryError> {
+    let len = de.read_len()?;
+    let names = (0..len)
+        .map(|_| de.read_str().map(ToOwned::to_owned))
+        .collect::<Result<Vec<_>, _>>()?;
+    let types = (0..len)
+        .map(|_| de.read_str().map(ToOwned::to_owned))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    names
+        .into_iter()
+        .zip(types)
+        .map(|(name, type_name)| Column::new(name, type_name))
+        .collect()
+}
+
+// The binary layout of a type, e.g. Date is a UInt16, Decimal(18, 2) an Int64.
+pub(super) enum Kind {
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    Int128,
+    Float32,
+    Float64,
+    String,
+    FixedString(usize),
+    // UInt256, Int256, Decimal256 and IPv6.
+    Bytes(usize),
+    Uuid,
+    Enum8(HashMap<i8, String>),
+    Enum16(HashMap<i16, String>),
+    Nothing,
+    Nullable(Box<Kind>),
+    Array(Box<Kind>),
+    Tuple(Vec<Kind>),
+    Map(Box<Kind>, Box<Kind>),
+    // Same as the inner type in RowBinary.
+    LowCardinality(Box<Kind>),
+}
+
+static KIND_UINT8: Kind = Kind::UInt8;
+
+impl Kind {
+    pub(super) fn new(type_name: &TypeName) -> Result<Self, RowBinaryError> {
+        let kind = match type_name {
+            TypeName::UInt8 => Self::UInt8,
+            TypeName::UInt16 | TypeName::Date => Self::UInt16,
+            TypeName::UInt32 | TypeName::DateTime(_) | TypeName::Ipv4 => Self::UInt32,
+            TypeName::UInt64 => Self::UInt64,
+            TypeName::Int8 => Self::Int8,
+            TypeName::Int16 => Self::Int16,
+            TypeName::Int32 => Self::Int32,
+            TypeName::Int64 | TypeName::DateTime64(_, _) => Self::Int64,
+            TypeName::Int128 => Self::Int128,
+            TypeName::UInt256 | TypeName::Int256 => Self::Bytes(32),
+            TypeName::Float32 => Self::Float32,
+            TypeName::Float64 => Self::Float64,
+            TypeName::Decimal(precision, _) => match precision.0 {
+                0..=9 => Self::Int32,
+                10..=18 => Self::Int64,
+                19..=38 => Self::Int128,
+                _ => Self::Bytes(32),
+            },
+            TypeName::String => Self::String,
+            TypeName::FixedString(n) => Self::FixedString(n.0),
+            TypeName::Uuid => Self::Uuid,
+            TypeName::Ipv6 => Self::Bytes(16),
+            TypeName::Enum8(map) => Self::Enum8(map.iter().map(|(k, v)| (*v, k.clone())).collect()),
+            TypeName::Enum16(map) => {
+                Self::Enum16(map.iter().map(|(k, v)| (*v, k.clone())).collect())
+            }
+            TypeName::LowCardinality(data_type) => {
+                Self::LowCardinality(Self::new(&TypeName::from(data_type.to_owned()))?.into())
+            }
+            TypeName::Nullable(NullableTypeName::Nothing) => Self::Nullable(Self::Nothing.into()),
+            TypeName::Nullable(type_name) => {
+                Self::Nullable(Self::new(&TypeName::try_from(type_name.to_owned())?)?.into())
+            }
+            TypeName::Point => Self::Tuple(vec![Self::Float64, Self::Float64]),
+            TypeName::Ring => Self::Array(Self::new(&TypeName::Point)?.into()),
+            TypeName::Polygon => Self::Array(Self::new(&TypeName::Ring)?.into()),
+            TypeName::MultiPolygon => Self::Array(Self::new(&TypeName::Polygon)?.into()),
+            TypeName::Array(type_name) => Self::Array(Self::new(type_name)?.into()),
+            TypeName::Tuple(type_names) => Self::Tuple(
+                type_names
+                    .iter()
+                    .map(Self::new)
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            TypeName::Map(key, value) => Self::Map(
+                Self::new(&TypeName::from(key.to_owned()))?.into(),
+                Self::new(&TypeName::from(value.to_owned()))?.into(),
+            ),
+        };
+        Ok(kind)
+    }
+
+    // FixedString, UUID and the big integers can also be read as `[u8; N]` or `Vec<u8>`.
+    pub(super) fn fixed_len(&self) -> Option<usize> {
+        match self {
+            Self::FixedString(n) | Self::Bytes(n) => Some(*n),
+            Self::Uuid => Some(16),
+            _ => None,
+        }
+    }
+}
+
+/// Deserializes a row, a struct or a map by column name, or a tuple by position.
+pub(crate) struct RowDeserializer<'a, 'de> {
+    values: RowValues<'a, 'de>,
+    columns: &'a [Column],
+}
+
+impl<'a, 'de> RowDeserializer<'a, 'de> {
+    /// The values are read one after another from `de`.
+    pub(crate) fn new(de: &'a mut Deserializer<'de>, columns: &'a [Column]) -> Self {
+        Self {
+            values: RowValues::Row(de),
+            columns,
+        }
+    }
+
+    /// Each value is read from its own slice, one per column.
+    #[cfg(feature = "with-native")]
+    pub(crate) fn with_values(values: &'a [&'de [u8]], columns: &'a [Column]) -> Self {
+        Self {
+            values: RowValues::Columns(values.iter()),
+            columns,
+        }
+    }
+}
+
+enum RowValues<'a, 'de> {
+    Row(&'a mut Deserializer<'de>),
+    #[cfg_attr(not(feature = "with-native"), allow(dead_code))]
+    Columns(slice::Iter<'a, &'de [u8]>),
+}
+
+impl<'de> RowValues<'_, 'de> {
+    // The columns are visited in order, so the next value is the one of `column`.
+    fn deserialize_next<T>(&mut self, seed: T, column: &Column) -> Result<T::Value, RowBinaryError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self {
+            Self::Row(de) => seed.deserialize(TypedDeserializer::new(de, &column.kind, column)),
+            Self::Columns(values) => {
+                let value = values.next().ok_or(RowBinaryError::UnexpectedEof)?;
+                let mut de = Deserializer::new(value);
+                seed.deserialize(TypedDeserializer::new(&mut de, &column.kind, column))
+            }
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for RowDeserializer<'_, 'de> {
+    type Error = RowBinaryError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(RowAccess {
+            values: self.values,
+            columns: self.columns.iter(),
+            column: None,
+        })
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        if len != self.columns.len() {
+            return Err(RowBinaryError::ColumnsMismatch(format!(
+                "a tuple of {len} can't hold {} columns",
+                self.columns.len()
+            )));
+        }
+        visitor.visit_seq(RowAccess {
+            values: self.values,
+            columns: self.columns.iter(),
+            column: None,
+        })
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq map struct
+        enum identifier ignored_any
+    }
+}
+
+struct RowAccess<'a, 'de> {
+    values: RowValues<'a, 'de>,
+    columns: slice::Iter<'a, Column>,
+    column: Option<&'a Column>,
+}
+
+impl<'de> de::MapAccess<'de> for RowAccess<'_, 'de> {
+    type Error = RowBinaryError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, RowBinaryError>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        self.column = self.columns.next();
+        match self.column {
+            Some(column) => seed
+                .deserialize(StrDeserializer::new(&column.name))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let column = self
+            .column
+            .take()
+            .ok_or_else(|| RowBinaryError::Custom("value without a key".to_owned()))?;
+        self.values.deserialize_next(seed, column)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.columns.len())
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for RowAccess<'_, 'de> {
+    type Error = RowBinaryError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, RowBinaryError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.columns.next() {
+            Some(column) => self.values.deserialize_next(seed, column).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.columns.len())
+    }
+}
+
+struct TypedDeserializer<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    kind: &'a Kind,
+    // For error messages.
+    column: &'a Column,
+}
+
+impl<'a, 'de> TypedDeserializer<'a, 'de> {
+    fn new(de: &'a mut Deserializer<'de>, kind: &'a Kind, column: &'a Column) -> Self {
+        let kind = match kind {
+            Kind::LowCardinality(kind) => kind,
+            kind => kind,
+        };
+        Self { de, kind, column }
+    }
+
+    fn mismatch(&self, expected: &str) -> RowBinaryError {
+        let mut msg = format!(
+            "column {} of type {} can't be read as {expected}",
+            self.column.name, self.column.type_name
+        );
+        if matches!(self.kind, Kind::Nullable(_)) {
+            msg.push_str(", Nullable needs an Option");
+        }
+        RowBinaryError::TypeMismatch(msg)
+    }
+
+    fn check(&self, is_ok: bool, expected: &str) -> Result<(), RowBinaryError> {
+        if is_ok {
+            Ok(())
+        } else {
+            Err(self.mismatch(expected))
+        }
+    }
+
+    fn access(self, kinds: Kinds<'a>, len: usize) -> TypedAccess<'a, 'de> {
+        TypedAccess {
+            de: self.de,
+            kinds,
+            len,
+            column: self.column,
+        }
+    }
+
+    // Trailing NULs are padding.
+    fn read_fixed_string(&mut self, n: usize) -> Result<&'de [u8], RowBinaryError> {
+        let bytes = self.de.read(n)?;
+        let len = bytes.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+        Ok(&bytes[..len])
+    }
+
+    // Two UInt64, the high half first.
+    fn read_uuid(&mut self) -> Result<String, RowBinaryError> {
+        let bytes = self.de.read(16)?;
+        let mut high = [0; 8];
+        let mut low = [0; 8];
+        high.copy_from_slice(&bytes[..8]);
+        low.copy_from_slice(&bytes[8..]);
+        let v = (u128::from(u64::from_le_bytes(high)) << 64) | u128::from(u64::from_le_bytes(low));
+
+        Ok(format!(
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            v >> 96,
+            (v >> 80) & 0xffff,
+            (v >> 64) & 0xffff,
+            (v >> 48) & 0xffff,
+            v & 0xffff_ffff_ffff
+        ))
+    }
+
+    fn read_enum_name(&mut self) -> Result<String, RowBinaryError> {
+        let name = match self.kind {
+            Kind::Enum8(names) => {
+                let v = i8::from_le_bytes([self.de.read(1)?[0]]);
+                names.get(&v).ok_or(i16::from(v))
+            }
+            Kind::Enum16(names) => {
+                let mut bytes = [0; 2];
+                bytes.copy_from_slice(self.de.read(2)?);
+                let v = i16::from_le_bytes(bytes);
+                names.get(&v).ok_or(v)
+            }
+            _ => return Err(self.mismatch("an enum")),
+        };
+        name.cloned().map_err(|v| {
+            RowBinaryError::Custom(format!("column {} has no enum value {v}", self.column.name))
+        })
+    }
+}
+
+macro_rules! deserialize_checked {
+    ($($method:ident $expected:literal $kind:pat),*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+            where
+                V: Visitor<'de>,
+            {
+                self.check(matches!(self.kind, $kind), $expected)?;
+                de::Deserializer::$method(self.de, visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for TypedDeserializer<'_, 'de> {
+    type Error = RowBinaryError;
+
+    deserialize_checked! {
+        deserialize_bool "bool" Kind::UInt8,
+        deserialize_u8 "u8" Kind::UInt8,
+        deserialize_u16 "u16" Kind::UInt16,
+        deserialize_u32 "u32" Kind::UInt32,
+        deserialize_u64 "u64" Kind::UInt64,
+        deserialize_i8 "i8" Kind::Int8 | Kind::Enum8(_),
+        deserialize_i16 "i16" Kind::Int16 | Kind::Enum16(_),
+        deserialize_i32 "i32" Kind::Int32,
+        deserialize_i64 "i64" Kind::Int64,
+        deserialize_i128 "i128" Kind::Int128,
+        deserialize_f32 "f32" Kind::Float32,
+        deserialize_f64 "f64" Kind::Float64,
+        deserialize_char "char" Kind::String
+    }
+
+    fn deserialize_u128<V>(self, _visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        Err(self.mismatch("u128"))
+    }
+
+    fn deserialize_any<V>(mut self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        match self.kind {
+            Kind::UInt8 => self.deserialize_u8(visitor),
+            Kind::UInt16 => self.deserialize_u16(visitor),
+            Kind::UInt32 => self.deserialize_u32(visitor),
+            Kind::UInt64 => self.deserialize_u64(visitor),
+            Kind::Int8 => self.deserialize_i8(visitor),
+            Kind::Int16 => self.deserialize_i16(visitor),
+            Kind::Int32 => self.deserialize_i32(visitor),
+            Kind::Int64 => self.deserialize_i64(visitor),
+            Kind::Int128 => self.deserialize_i128(visitor),
+            Kind::Float32 => self.deserialize_f32(visitor),
+            Kind::Float64 => self.deserialize_f64(visitor),
+            Kind::String => {
+                let bytes = self.de.read_bytes()?;
+                match str::from_utf8(bytes) {
+                    Ok(s) => visitor.visit_borrowed_str(s),
+                    Err(_) => visitor.visit_borrowed_bytes(bytes),
+                }
+            }
+            Kind::FixedString(n) => {
+                let bytes = self.read_fixed_string(*n)?;
+                match str::from_utf8(bytes) {
+                    Ok(s) => visitor.visit_borrowed_str(s),
+                    Err(_) => visitor.visit_borrowed_bytes(bytes),
+                }
+            }
+            Kind::Bytes(n) => visitor.visit_borrowed_bytes(self.de.read(*n)?),
+            Kind::Uuid => visitor.visit_string(self.read_uuid()?),
+            Kind::Enum8(_) | Kind::Enum16(_) => visitor.visit_string(self.read_enum_name()?),
+            Kind::Nothing | Kind::Nullable(_) => self.deserialize_option(visitor),
+            Kind::Array(_) => self.deserialize_seq(visitor),
+            Kind::Tuple(kinds) => self.deserialize_tuple(kinds.len(), visitor),
+            Kind::Map(_, _) => self.deserialize_map(visitor),
+            Kind::LowCardinality(kind) => {
+                TypedDeserializer::new(self.de, kind, self.column).deserialize_any(visitor)
+            }
+        }
+    }
+
+    fn deserialize_str<V>(mut self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        match self.kind {
+            Kind::String => de::Deserializer::deserialize_str(self.de, visitor),
+            Kind::FixedString(n) => {
+                visitor.visit_borrowed_str(str::from_utf8(self.read_fixed_string(*n)?)?)
+            }
+            Kind::Uuid => visitor.visit_string(self.read_uuid()?),
+            Kind::Enum8(_) | Kind::Enum16(_) => visitor.visit_string(self.read_enum_name()?),
+            _ => Err(self.mismatch("str")),
+        }
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        match (self.kind, self.kind.fixed_len()) {
+            (Kind::String, _) => de::Deserializer::deserialize_bytes(self.de, visitor),
+            (_, Some(n)) => visitor.visit_borrowed_bytes(self.de.read(n)?),
+            _ => Err(self.mismatch("bytes")),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        let Kind::Nullable(kind) = self.kind else {
+            return Err(self.mismatch("an Option"));
+        };
+        match self.de.read(1)?[0] {
+            0 if matches!(**kind, Kind::Nothing) => Err(RowBinaryError::Custom(format!(
+                "column {} of type Nullable(Nothing) has a value",
+                self.column.name
+            ))),
+            0 => visitor.visit_some(TypedDeserializer::new(self.de, kind, self.column)),
+            1 => visitor.visit_none(),
+            b => Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(b.into()),
+                &"a Nullable marker",
+            )),
+        }
+    }
+
+    fn deserialize_unit<V>(self, _visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        Err(self.mismatch("unit"))
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        _visitor: V,
+    ) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        Err(self.mismatch("unit"))
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        match (self.kind, self.kind.fixed_len()) {
+            (Kind::Array(kind), _) => {
+                let len = self.de.read_len()?;
+                visitor.visit_seq(self.access(Kinds::Same(kind), len))
+            }
+            (_, Some(n)) => visitor.visit_seq(self.access(Kinds::Same(&KIND_UINT8), n)),
+            _ => Err(self.mismatch("a sequence")),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        match (self.kind, self.kind.fixed_len()) {
+            (Kind::Tuple(kinds), _) if kinds.len() == len => {
+                visitor.visit_seq(self.access(Kinds::Each(kinds.iter()), len))
+            }
+            (_, Some(n)) if n == len => visitor.visit_seq(self.access(Kinds::Same(&KIND_UINT8), n)),
+            _ => Err(self.mismatch(&format!("a tuple of {len}"))),
+        }
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        let Kind::Map(key, value) = self.kind else {
+            return Err(self.mismatch("a map"));
+        };
+        let len = self.de.read_len()?;
+        visitor.visit_map(TypedMapAccess {
+            de: self.de,
+            key,
+            value,
+            len,
+            column: self.column,
+        })
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V>(
+        mut self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_enum(self.read_enum_name()?.into_deserializer())
+    }
+
+    fn deserialize_identifier<V>(self, _visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        Err(RowBinaryError::Unsupported("deserialize_identifier"))
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+enum Kinds<'a> {
+    Same(&'a Kind),
+    Each(slice::Iter<'a, Kind>),
+}
+
+struct TypedAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    kinds: Kinds<'a>,
+    len: usize,
+    column: &'a Column,
+}
+
+impl<'de> de::SeqAccess<'de> for TypedAccess<'_, 'de> {
+    type Error = RowBinaryError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, RowBinaryError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+
+        let kind = match &mut self.kinds {
+            Kinds::Same(kind) => *kind,
+            Kinds::Each(kinds) => kinds.next().ok_or(RowBinaryError::LengthInvalid)?,
+        };
+        seed.deserialize(TypedDeserializer::new(self.de, kind, self.column))
+            .map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+struct TypedMapAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    key: &'a Kind,
+    value: &'a Kind,
+    len: usize,
+    column: &'a Column,
+}
+
+impl<'de> de::MapAccess<'de> for TypedMapAccess<'_, 'de> {
+    type Error = RowBinaryError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, RowBinaryError>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(TypedDeserializer::new(self.de, self.key, self.column))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, RowBinaryError>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(TypedDeserializer::new(self.de, self.value, self.column))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
@@ -3,6 +3,8 @@ use std::collections::HashMap;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
+use crate::output::{OutputResult, StreamOutput};
+
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub(crate) struct TestRow {
     pub(crate) array1: Vec<usize>,
@@ -60,3 +62,26 @@ pub(crate) static TEST_STRINGS_ROW_2: Lazy<TestStringsRow> = Lazy::new(|| TestSt
     tuple2: "(2,'b')".into(),
     map1: "{}".into(),
 });
+
+#[allow(dead_code)]
+pub(crate) fn stream_deserialize<O: StreamOutput>(
+    mut output: O,
+    slice: &[u8],
+) -> OutputResult<O::Row, Option<O::Info>, O::Error> {
+    let mut rows = vec![];
+    let mut buf = vec![];
+    for line in slice.split(|b| *b == b'\n') {
+        if !buf.is_empty() {
+            buf.push(b'\n');
+        }
+        buf.extend_from_slice(line);
+        if buf.is_empty() || !output.is_complete_line(&buf) {
+            continue;
+        }
+        if let Some(row) = output.deserialize_line(&buf)? {
+            rows.push(row);
+        }
+        buf.clear();
+    }
+    Ok((rows, output.info()))
+}
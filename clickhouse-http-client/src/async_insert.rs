@@ -0,0 +1,216 @@
+use core::time::Duration;
+
+use crate::{
+    client_config::{
+        ASYNC_INSERT_BUSY_TIMEOUT_MS_KEY_URL_PARAMETER, ASYNC_INSERT_KEY_URL_PARAMETER,
+        WAIT_FOR_ASYNC_INSERT_KEY_URL_PARAMETER,
+    },
+    error::ClientInsertWithFormatError,
+    response_info::QuerySummary,
+};
+
+// https://clickhouse.com/docs/en/optimize/asynchronous-inserts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsyncInsertOptions {
+    is_async_insert: bool,
+    is_wait_for_async_insert: bool,
+    busy_timeout: Option<Duration>,
+}
+impl Default for AsyncInsertOptions {
+    fn default() -> Self {
+        Self {
+            is_async_insert: true,
+            is_wait_for_async_insert: true,
+            busy_timeout: None,
+        }
+    }
+}
+impl AsyncInsertOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_async_insert(&mut self, val: bool) -> &mut Self {
+        self.is_async_insert = val;
+        self
+    }
+    /// When false, the server acknowledges the insert as soon as the data is queued.
+    pub fn set_wait_for_async_insert(&mut self, val: bool) -> &mut Self {
+        self.is_wait_for_async_insert = val;
+        self
+    }
+    pub fn set_busy_timeout(&mut self, val: Duration) -> &mut Self {
+        self.busy_timeout = Some(val);
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), ClientInsertWithFormatError> {
+        if !self.is_async_insert && (!self.is_wait_for_async_insert || self.busy_timeout.is_some())
+        {
+            return Err(ClientInsertWithFormatError::AsyncInsertOptionsInvalid(
+                "wait_for_async_insert and async_insert_busy_timeout_ms require async_insert"
+                    .to_owned(),
+            ));
+        }
+        if self
+            .busy_timeout
+            .map(|x| x.as_millis() == 0)
+            .unwrap_or(false)
+        {
+            return Err(ClientInsertWithFormatError::AsyncInsertOptionsInvalid(
+                "async_insert_busy_timeout_ms must be at least 1ms".to_owned(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// The requested mode, see `acknowledged_mode` for the one of a response.
+    pub fn mode(&self) -> Option<AsyncInsertMode> {
+        match (self.is_async_insert, self.is_wait_for_async_insert) {
+            (false, _) => None,
+            (true, false) => Some(AsyncInsertMode::Queued),
+            (true, true) => Some(AsyncInsertMode::Flushed),
+        }
+    }
+
+    /// How the server acknowledged an insert sent with these options.
+    ///
+    /// Rows written by the insert itself mean the server ran it synchronously. Otherwise it was
+    /// queued, and also flushed if `wait_for_async_insert` held the response until the flush.
+    pub fn acknowledged_mode(&self, summary: Option<&QuerySummary>) -> Option<AsyncInsertMode> {
+        if summary.and_then(|x| x.written_rows).is_some_and(|x| x > 0) {
+            return Some(AsyncInsertMode::Flushed);
+        }
+        self.mode()
+    }
+
+    pub(crate) fn to_settings(
+        &self,
+    ) -> Result<Vec<(&'static str, String)>, ClientInsertWithFormatError> {
+        self.validate()?;
+
+        let mut settings = vec![(
+            ASYNC_INSERT_KEY_URL_PARAMETER,
+            u8::from(self.is_async_insert).to_string(),
+        )];
+        if self.is_async_insert {
+            settings.push((
+                WAIT_FOR_ASYNC_INSERT_KEY_URL_PARAMETER,
+                u8::from(self.is_wait_for_async_insert).to_string(),
+            ));
+        }
+        if let Some(busy_timeout) = self.busy_timeout {
+            settings.push((
+                ASYNC_INSERT_BUSY_TIMEOUT_MS_KEY_URL_PARAMETER,
+                busy_timeout.as_millis().to_string(),
+            ));
+        }
+
+        Ok(settings)
+    }
+}
+
+/// When the server acknowledged an async insert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsyncInsertMode {
+    /// Once accepted into the server side buffer, before it is written to the table.
+    Queued,
+    /// Once written to the table.
+    Flushed,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_settings() {
+        let mut options = AsyncInsertOptions::new();
+        assert_eq!(
+            options.to_settings().unwrap(),
+            vec![
+                ("async_insert", "1".to_owned()),
+                ("wait_for_async_insert", "1".to_owned())
+            ]
+        );
+        assert_eq!(options.mode(), Some(AsyncInsertMode::Flushed));
+
+        options
+            .set_wait_for_async_insert(false)
+            .set_busy_timeout(Duration::from_millis(500));
+        assert_eq!(
+            options.to_settings().unwrap(),
+            vec![
+                ("async_insert", "1".to_owned()),
+                ("wait_for_async_insert", "0".to_owned()),
+                ("async_insert_busy_timeout_ms", "500".to_owned())
+            ]
+        );
+        assert_eq!(options.mode(), Some(AsyncInsertMode::Queued));
+
+        let mut options = AsyncInsertOptions::new();
+        options.set_async_insert(false);
+        assert_eq!(
+            options.to_settings().unwrap(),
+            vec![("async_insert", "0".to_owned())]
+        );
+        assert_eq!(options.mode(), None);
+    }
+
+    #[test]
+    fn test_acknowledged_mode() {
+        let mut options = AsyncInsertOptions::new();
+        options.set_wait_for_async_insert(false);
+        let mut summary = QuerySummary {
+            written_rows: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(
+            options.acknowledged_mode(Some(&summary)),
+            Some(AsyncInsertMode::Queued)
+        );
+        assert_eq!(
+            options.acknowledged_mode(None),
+            Some(AsyncInsertMode::Queued)
+        );
+
+        // Run synchronously by the server.
+        summary.written_rows = Some(2);
+        assert_eq!(
+            options.acknowledged_mode(Some(&summary)),
+            Some(AsyncInsertMode::Flushed)
+        );
+
+        options
+            .set_async_insert(true)
+            .set_wait_for_async_insert(true);
+        summary.written_rows = Some(0);
+        assert_eq!(
+            options.acknowledged_mode(Some(&summary)),
+            Some(AsyncInsertMode::Flushed)
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut options = AsyncInsertOptions::new();
+        options.set_busy_timeout(Duration::from_micros(10));
+        assert!(matches!(
+            options.validate(),
+            Err(ClientInsertWithFormatError::AsyncInsertOptionsInvalid(_))
+        ));
+
+        let mut options = AsyncInsertOptions::new();
+        options
+            .set_async_insert(false)
+            .set_wait_for_async_insert(false);
+        assert!(options.validate().is_err());
+
+        let mut options = AsyncInsertOptions::new();
+        options
+            .set_async_insert(false)
+            .set_busy_timeout(Duration::from_secs(1));
+        assert!(options.to_settings().is_err());
+    }
+}
@@ -0,0 +1,267 @@
+use core::{
+    future::Future,
+    ops::{Deref, DerefMut},
+};
+#[cfg(any(feature = "with-transport-hyper", feature = "with-transport-reqwest"))]
+use std::sync::Arc;
+
+use clickhouse_format::{
+    input::Input,
+    output::{Output, StreamOutput},
+};
+use futures_util::stream::StreamExt as _;
+
+use crate::{
+    client::{Client as AsyncClient, Settings},
+    client_config::ClientConfig,
+    error::Error,
+    response_info::QueryResponseInfo,
+    select_stream::SelectStream,
+};
+
+/// Runs the async client on the calling thread.
+///
+/// With the hyper or reqwest transport enabled, it owns a current thread tokio runtime for them,
+/// so it must not be used from inside another tokio runtime.
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: AsyncClient,
+    executor: Executor,
+}
+impl Deref for Client {
+    type Target = ClientConfig;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+impl DerefMut for Client {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+impl TryFrom<AsyncClient> for Client {
+    type Error = Error;
+
+    fn try_from(inner: AsyncClient) -> Result<Self, Self::Error> {
+        Ok(Self {
+            inner,
+            executor: Executor::new()?,
+        })
+    }
+}
+impl Client {
+    pub fn new() -> Result<Self, Error> {
+        AsyncClient::new()?.try_into()
+    }
+
+    pub fn as_async(&self) -> &AsyncClient {
+        &self.inner
+    }
+    pub fn into_async(self) -> AsyncClient {
+        self.inner
+    }
+
+    pub fn ping(&self) -> Result<bool, Error> {
+        self.executor.block_on(self.inner.ping())
+    }
+
+    pub fn execute<'a>(
+        &self,
+        sql: impl AsRef<str>,
+        settings: impl Into<Option<Settings<'a>>>,
+    ) -> Result<QueryResponseInfo, Error> {
+        self.executor.block_on(self.inner.execute(sql, settings))
+    }
+
+    pub fn insert_with_format<'a, I: Input>(
+        &self,
+        sql_prefix: impl AsRef<str>,
+        input: I,
+        settings: impl Into<Option<Settings<'a>>>,
+    ) -> Result<QueryResponseInfo, Error> {
+        self.executor
+            .block_on(self.inner.insert_with_format(sql_prefix, input, settings))
+    }
+
+    pub fn select_with_format<'a, O: Output>(
+        &self,
+        sql: impl AsRef<str>,
+        output: O,
+        settings: impl Into<Option<Settings<'a>>>,
+    ) -> Result<(Vec<O::Row>, O::Info), Error> {
+        self.executor
+            .block_on(self.inner.select_with_format(sql, output, settings))
+    }
+
+    /// Reads the rows as the iterator is advanced.
+    pub fn select_iter_with_format<'a, O: StreamOutput>(
+        &self,
+        sql: impl AsRef<str>,
+        output: O,
+        settings: impl Into<Option<Settings<'a>>>,
+    ) -> Result<SelectIter<O>, Error> {
+        let stream = self
+            .executor
+            .block_on(self.inner.select_stream_with_format(sql, output, settings))?;
+
+        Ok(SelectIter {
+            inner: stream,
+            executor: self.executor.to_owned(),
+        })
+    }
+}
+
+pub struct SelectIter<O>
+where
+    O: StreamOutput,
+{
+    inner: SelectStream<O>,
+    executor: Executor,
+}
+
+impl<O> SelectIter<O>
+where
+    O: StreamOutput,
+{
+    pub fn info(&self) -> Option<O::Info> {
+        self.inner.info()
+    }
+
+    pub fn response_info(&self) -> QueryResponseInfo {
+        self.inner.response_info()
+    }
+}
+
+impl<O> Iterator for SelectIter<O>
+where
+    O: StreamOutput,
+{
+    type Item = Result<O::Row, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.executor.block_on(self.inner.next())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Executor {
+    // hyper and reqwest only run inside a tokio runtime.
+    #[cfg(any(feature = "with-transport-hyper", feature = "with-transport-reqwest"))]
+    runtime: Arc<tokio::runtime::Runtime>,
+}
+
+impl Executor {
+    fn new() -> Result<Self, Error> {
+        Ok(Self {
+            #[cfg(any(feature = "with-transport-hyper", feature = "with-transport-reqwest"))]
+            runtime: Arc::new(
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()?,
+            ),
+        })
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        #[cfg(any(feature = "with-transport-hyper", feature = "with-transport-reqwest"))]
+        return self.runtime.block_on(future);
+        #[cfg(not(any(feature = "with-transport-hyper", feature = "with-transport-reqwest")))]
+        return futures_executor::block_on(future);
+    }
+}
+
+#[cfg(all(test, feature = "with-format-json"))]
+mod tests {
+    use super::*;
+
+    use clickhouse_format::output::JsonCompactEachRowWithNamesAndTypesOutput;
+    use futures_util::future::BoxFuture;
+    use http::{Request, Response};
+    use serde::Deserialize;
+
+    use crate::{
+        client::ClientBuilder,
+        transport::{Body, Transport, TransportError},
+    };
+
+    #[derive(Debug)]
+    struct MockTransport;
+
+    impl Transport for MockTransport {
+        fn send(
+            &self,
+            _req: Request<Body>,
+        ) -> BoxFuture<'_, Result<Response<Body>, TransportError>> {
+            Box::pin(async move {
+                Ok(Response::new(Body::from(
+                    "[\"id\"]\n[\"UInt32\"]\n[1]\n[2]\n",
+                )))
+            })
+        }
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Row {
+        id: u32,
+    }
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        let mut client_builder = ClientBuilder::new();
+        client_builder.set_transport(MockTransport);
+        let client = Client::try_from(client_builder.build()?)?;
+
+        let (rows, _) = client.select_with_format(
+            "SELECT id",
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            None,
+        )?;
+        assert_eq!(rows.len(), 2);
+
+        let mut iter = client.select_iter_with_format(
+            "SELECT id",
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            None,
+        )?;
+        assert!(iter.info().is_none());
+        assert_eq!(iter.next().transpose()?.map(|x| x.id), Some(1));
+        assert!(iter.info().is_some());
+        assert_eq!(iter.next().transpose()?.map(|x| x.id), Some(2));
+        assert!(iter.next().is_none());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "with-transport-hyper")]
+    #[test]
+    fn with_hyper() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::{
+            test_helpers::{response, spawn_server},
+            transport::HyperTransport,
+        };
+
+        let server = spawn_server(|req| match req.body.is_empty() {
+            true => response(&[], b"Ok.\n"),
+            false => response(&[], b"[\"id\"]\n[\"UInt32\"]\n[1]\n[2]\n"),
+        });
+
+        let mut client_builder = ClientBuilder::new();
+        client_builder.set_url(&server.url)?;
+        client_builder.set_transport(HyperTransport::new());
+        let client = Client::try_from(client_builder.build()?)?;
+
+        assert!(client.ping()?);
+        let ids = client
+            .select_iter_with_format(
+                "SELECT id",
+                JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+                None,
+            )?
+            .map(|x| x.map(|row| row.id))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(ids, vec![1, 2]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::thread;
+
+use crate::{client::Client, error::Error};
+
+/// Issues `KILL QUERY` for `query_id` when dropped after the inner future was first polled
+/// and before it completes, see `Client::cancel_on_drop`.
+///
+/// The cancel is spawned onto the tokio runtime `CancelOnDrop` was created in, or else sent
+/// from a new thread, which only works for transports that need no runtime.
+pub struct CancelOnDrop<F> {
+    client: Client,
+    query_id: String,
+    future: Pin<Box<F>>,
+    is_started: bool,
+    is_done: bool,
+    #[cfg(any(feature = "with-transport-hyper", feature = "with-transport-reqwest"))]
+    runtime: Option<tokio::runtime::Handle>,
+}
+
+impl<F> CancelOnDrop<F> {
+    pub(crate) fn new(
+        client: Client,
+        query_id: String,
+        future_fn: impl FnOnce() -> F,
+    ) -> Result<Self, Error> {
+        #[cfg(any(feature = "with-transport-hyper", feature = "with-transport-reqwest"))]
+        let runtime = tokio::runtime::Handle::try_current().ok();
+        #[cfg(any(feature = "with-transport-hyper", feature = "with-transport-reqwest"))]
+        let has_runtime = runtime.is_some();
+        #[cfg(not(any(feature = "with-transport-hyper", feature = "with-transport-reqwest")))]
+        let has_runtime = false;
+
+        if !has_runtime && client.is_transport_tokio_runtime_required() {
+            return Err(Error::TokioRuntimeMissing);
+        }
+
+        Ok(Self {
+            client,
+            query_id,
+            future: Box::pin(future_fn()),
+            is_started: false,
+            is_done: false,
+            #[cfg(any(feature = "with-transport-hyper", feature = "with-transport-reqwest"))]
+            runtime,
+        })
+    }
+
+    pub fn query_id(&self) -> &str {
+        &self.query_id
+    }
+}
+
+impl<F> Future for CancelOnDrop<F>
+where
+    F: Future,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.is_started = true;
+
+        let output = this.future.as_mut().poll(cx);
+        if output.is_ready() {
+            this.is_done = true;
+        }
+        output
+    }
+}
+
+impl<F> Drop for CancelOnDrop<F> {
+    fn drop(&mut self) {
+        // Nothing was sent before the first poll.
+        if !self.is_started || self.is_done {
+            return;
+        }
+
+        let client = self.client.to_owned();
+        let query_id = core::mem::take(&mut self.query_id);
+
+        #[cfg(any(feature = "with-transport-hyper", feature = "with-transport-reqwest"))]
+        if let Some(runtime) = &self.runtime {
+            runtime.spawn(async move {
+                let _ = client.cancel(&query_id).await;
+            });
+            return;
+        }
+
+        // Drop can't await, and there is no runtime to spawn onto.
+        thread::spawn(move || {
+            let _ = futures_executor::block_on(client.cancel(&query_id));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures_util::{FutureExt as _, future};
+
+    use crate::test_helpers::MockTransport;
+
+    fn wait_requests(transport: &MockTransport, n: usize) -> Vec<String> {
+        for _ in 0..50 {
+            if transport.requests.lock().unwrap().len() >= n {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        transport
+            .requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|x| String::from_utf8_lossy(&x.body).into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_drop() {
+        let transport = MockTransport::ok();
+        let client = transport.client();
+
+        let mut future = client
+            .cancel_on_drop(|client| async move {
+                let _ = client
+                    .execute("SELECT 1", Some(vec![("query_id", "foo")]))
+                    .await;
+                future::pending::<()>().await
+            })
+            .unwrap();
+        let query_id = future.query_id().to_owned();
+        assert!((&mut future).now_or_never().is_none());
+        drop(future);
+
+        assert_eq!(
+            wait_requests(&transport, 2),
+            vec![
+                "SELECT 1".to_owned(),
+                format!("KILL QUERY WHERE query_id = '{query_id}'")
+            ]
+        );
+        assert!(
+            transport.requests.lock().unwrap()[0]
+                .query_pairs()
+                .contains(&("query_id".to_owned(), query_id.to_owned()))
+        );
+        // The cancel has a query id of its own.
+        assert!(
+            !transport.requests.lock().unwrap()[1]
+                .query_pairs()
+                .contains(&("query_id".to_owned(), query_id))
+        );
+
+        // Neither when never polled, nor when done.
+        let transport = MockTransport::ok();
+        let client = transport.client();
+        drop(client.cancel_on_drop(|client| async move { client.execute("SELECT 1", None).await }));
+        let future = client
+            .cancel_on_drop(|client| async move { client.execute("SELECT 2", None).await })
+            .unwrap();
+        assert!(future.now_or_never().unwrap().is_ok());
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(wait_requests(&transport, 2), vec!["SELECT 2".to_owned()]);
+    }
+
+    #[cfg(feature = "with-transport-hyper")]
+    #[test]
+    fn test_new_with_hyper_without_runtime() {
+        use crate::{client::ClientBuilder, error::Error, transport::HyperTransport};
+
+        let mut client_builder = ClientBuilder::new();
+        client_builder.set_transport(HyperTransport::new());
+        let client = client_builder.build().unwrap();
+
+        assert!(matches!(
+            client.cancel_on_drop(|client| async move { client.execute("SELECT 1", None).await }),
+            Err(Error::TokioRuntimeMissing)
+        ));
+    }
+
+    #[cfg(feature = "with-transport-hyper")]
+    #[tokio::test]
+    async fn test_drop_with_hyper() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::{
+            client::ClientBuilder,
+            test_helpers::{response, spawn_server},
+            transport::HyperTransport,
+        };
+
+        let server = spawn_server(|req| {
+            if !req.body.starts_with(b"KILL QUERY") {
+                std::thread::sleep(Duration::from_secs(5));
+            }
+            response(&[], b"")
+        });
+
+        let mut client_builder = ClientBuilder::new();
+        client_builder.set_url(&server.url)?;
+        client_builder.set_transport(HyperTransport::new());
+        let client = client_builder.build()?;
+
+        let future = client.cancel_on_drop(|client| async move {
+            client
+                .execute("SELECT sleep(3)", Some(vec![("query_id", "bar")]))
+                .await
+        })?;
+        let kill_sql = format!("KILL QUERY WHERE query_id = '{}'", future.query_id());
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), future)
+                .await
+                .is_err()
+        );
+
+        let is_cancelled = || {
+            server
+                .requests
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|x| x.body == kill_sql.as_bytes())
+        };
+        for _ in 0..50 {
+            if is_cancelled() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(is_cancelled());
+
+        Ok(())
+    }
+}
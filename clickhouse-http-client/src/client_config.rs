@@ -1,9 +1,21 @@
-use isahc::http::{
-    Request,
-    header::{HeaderMap, InvalidHeaderValue},
+use core::time::Duration;
+use std::sync::Arc;
+
+use http::{
+    Request, Uri,
+    header::{ACCEPT_ENCODING, HeaderMap, HeaderValue, InvalidHeaderValue},
 };
 use url::{ParseError, Url};
 
+use crate::{
+    compression::Compression,
+    dsn,
+    endpoints::{Endpoints, LoadBalancing},
+    error::DsnError,
+    query_settings::QuerySettings,
+    retry_policy::RetryPolicy,
+};
+
 pub const DATABASE_KEY_URL_PARAMETER: &str = "database";
 pub const DATABASE_KEY_HEADER: &str = "X-ClickHouse-Database";
 
@@ -13,13 +25,33 @@ pub const USERNAME_KEY_HEADER: &str = "X-ClickHouse-User";
 pub const PASSWORD_KEY_URL_PARAMETER: &str = "password";
 pub const PASSWORD_KEY_HEADER: &str = "X-ClickHouse-Key";
 
+pub const SSL_CERTIFICATE_AUTH_KEY_HEADER: &str = "X-ClickHouse-SSL-Certificate-Auth";
+
 pub const FORMAT_KEY_URL_PARAMETER: &str = "default_format";
 pub const FORMAT_KEY_HEADER: &str = "X-ClickHouse-Format";
 
 pub const QUERY_KEY_URL_PARAMETER: &str = "query";
+pub const QUERY_ID_KEY_URL_PARAMETER: &str = "query_id";
+
+pub const INSERT_DEDUPLICATION_TOKEN_KEY_URL_PARAMETER: &str = "insert_deduplication_token";
+
+pub const ASYNC_INSERT_KEY_URL_PARAMETER: &str = "async_insert";
+pub const WAIT_FOR_ASYNC_INSERT_KEY_URL_PARAMETER: &str = "wait_for_async_insert";
+pub const ASYNC_INSERT_BUSY_TIMEOUT_MS_KEY_URL_PARAMETER: &str = "async_insert_busy_timeout_ms";
+
+pub const SESSION_ID_KEY_URL_PARAMETER: &str = "session_id";
+pub const SESSION_TIMEOUT_KEY_URL_PARAMETER: &str = "session_timeout";
+pub const SESSION_CHECK_KEY_URL_PARAMETER: &str = "session_check";
+
+pub const HTTP_COMPRESSION_KEY_URL_PARAMETER: &str = "enable_http_compression";
+
+pub const SEND_PROGRESS_IN_HTTP_HEADERS_KEY_URL_PARAMETER: &str = "send_progress_in_http_headers";
 
 pub const SUMMARY_KEY_HEADER: &str = "X-ClickHouse-Summary";
+pub const PROGRESS_KEY_HEADER: &str = "X-ClickHouse-Progress";
 pub const QUERY_ID_KEY_HEADER: &str = "X-ClickHouse-Query-Id";
+pub const EXCEPTION_CODE_KEY_HEADER: &str = "X-ClickHouse-Exception-Code";
+pub const EXCEPTION_TAG_KEY_HEADER: &str = "X-ClickHouse-Exception-Tag";
 
 const HTTP_SERVER_DEFAULT_RESPONSE_DEFAULT: &str = "Ok.\n";
 
@@ -28,6 +60,12 @@ pub struct ClientConfig {
     url: Url,
     header_map: HeaderMap,
     http_server_default_response: Option<String>,
+    request_compression: Option<Compression>,
+    retry_policy: Option<RetryPolicy>,
+    endpoints: Arc<Endpoints>,
+    pinned_endpoint: Option<usize>,
+    default_settings: QuerySettings,
+    query_id: Option<String>,
 }
 impl Default for ClientConfig {
     fn default() -> Self {
@@ -35,10 +73,38 @@ impl Default for ClientConfig {
             url: "http://localhost:8123/".parse().unwrap(),
             header_map: HeaderMap::new(),
             http_server_default_response: None,
+            request_compression: None,
+            retry_policy: None,
+            endpoints: Default::default(),
+            pinned_endpoint: None,
+            default_settings: Default::default(),
+            query_id: None,
         }
     }
 }
 impl ClientConfig {
+    /// See `ClientBuilder::from_dsn` for the TLS parameters.
+    pub fn from_dsn(dsn: impl AsRef<str>) -> Result<Self, DsnError> {
+        match dsn::parse(dsn.as_ref())? {
+            (client_config, None) => Ok(client_config),
+            (_, Some(_)) => Err(DsnError::ParamInvalid(
+                "ca_cert, client_cert, client_key and skip_verify require ClientBuilder::from_dsn"
+                    .to_owned(),
+            )),
+        }
+    }
+    /// Reads `CLICKHOUSE_URL`, `CLICKHOUSE_USER`, `CLICKHOUSE_PASSWORD` and `CLICKHOUSE_DATABASE`.
+    pub fn from_env() -> Result<Self, DsnError> {
+        match dsn::parse_env()? {
+            (client_config, None) => Ok(client_config),
+            (_, Some(_)) => Err(DsnError::ParamInvalid(
+                "ca_cert, client_cert, client_key and skip_verify require ClientBuilder::from_env"
+                    .to_owned(),
+            )),
+        }
+    }
+
+    /// Replaces the urls of `set_urls`.
     pub fn set_url(&mut self, val: impl AsRef<str>) -> Result<&mut Self, ParseError> {
         let mut query_pairs = self.url.query_pairs();
         let database = query_pairs
@@ -50,6 +116,11 @@ impl ClientConfig {
         let password = query_pairs
             .find(|(k, _)| k == PASSWORD_KEY_URL_PARAMETER)
             .map(|(_, v)| v.to_string());
+        let http_compression = self
+            .url
+            .query_pairs()
+            .find(|(k, _)| k == HTTP_COMPRESSION_KEY_URL_PARAMETER)
+            .map(|(_, v)| v.to_string());
 
         let mut url: Url = val.as_ref().parse()?;
         if let Some(ref database) = database {
@@ -64,10 +135,55 @@ impl ClientConfig {
             url.query_pairs_mut()
                 .append_pair(PASSWORD_KEY_URL_PARAMETER, password);
         }
+        if let Some(ref http_compression) = http_compression {
+            url.query_pairs_mut()
+                .append_pair(HTTP_COMPRESSION_KEY_URL_PARAMETER, http_compression);
+        }
 
         self.url = url;
+        self.endpoints = Arc::new(Endpoints::new(
+            vec![],
+            self.endpoints.load_balancing(),
+            self.endpoints.cooldown(),
+        ));
+        self.pinned_endpoint = None;
+        Ok(self)
+    }
+    /// The first url also becomes the `set_url` one, a later `set_url` replaces them.
+    pub fn set_urls<T>(
+        &mut self,
+        vals: impl IntoIterator<Item = T>,
+    ) -> Result<&mut Self, ParseError>
+    where
+        T: AsRef<str>,
+    {
+        let urls = vals
+            .into_iter()
+            .map(|val| val.as_ref().parse())
+            .collect::<Result<Vec<Url>, _>>()?;
+        if let Some(url) = urls.first() {
+            self.set_url(url)?;
+        }
+
+        self.endpoints = Arc::new(Endpoints::new(
+            urls,
+            self.endpoints.load_balancing(),
+            self.endpoints.cooldown(),
+        ));
+        self.pinned_endpoint = None;
         Ok(self)
     }
+    /// Like the health of the endpoints, it is shared with the clones of the config.
+    pub fn set_load_balancing(&mut self, val: LoadBalancing) -> &mut Self {
+        self.endpoints.set_load_balancing(val);
+        self
+    }
+    /// How long an endpoint is skipped once marked down, shared like `set_load_balancing`.
+    pub fn set_endpoint_cooldown(&mut self, val: Duration) -> &mut Self {
+        self.endpoints.set_cooldown(val);
+        self
+    }
+
     pub fn set_database_to_url_parameter(&mut self, val: impl AsRef<str>) -> &mut Self {
         self.url
             .query_pairs_mut()
@@ -113,14 +229,103 @@ impl ClientConfig {
         Ok(self)
     }
 
+    /// Authenticates `username` by the client certificate of `TlsConfig` instead of a password.
+    // https://clickhouse.com/docs/en/guides/sre/ssl-user-auth
+    pub fn set_ssl_certificate_auth(
+        &mut self,
+        username: impl AsRef<str>,
+    ) -> Result<&mut Self, InvalidHeaderValue> {
+        self.header_map
+            .insert(USERNAME_KEY_HEADER, username.as_ref().parse()?);
+        self.header_map.insert(
+            SSL_CERTIFICATE_AUTH_KEY_HEADER,
+            HeaderValue::from_static("on"),
+        );
+        self.header_map.remove(PASSWORD_KEY_HEADER);
+        Ok(self)
+    }
+
     pub fn set_http_server_default_response(&mut self, val: impl Into<String>) -> &mut Self {
         self.http_server_default_response = Some(val.into());
         self
     }
+
+    pub fn set_request_compression(&mut self, val: Compression) -> &mut Self {
+        self.request_compression = Some(val);
+        self
+    }
+    pub fn set_response_compression(&mut self, val: Compression) -> &mut Self {
+        self.url
+            .query_pairs_mut()
+            .append_pair(HTTP_COMPRESSION_KEY_URL_PARAMETER, "1");
+        self.header_map.insert(
+            ACCEPT_ENCODING,
+            HeaderValue::from_static(val.content_encoding()),
+        );
+        self
+    }
+
+    pub fn set_retry_policy(&mut self, val: RetryPolicy) -> &mut Self {
+        self.retry_policy = Some(val);
+        self
+    }
+    /// Sent with every request, unless the request settings have the same key.
+    pub fn set_default_settings(&mut self, val: QuerySettings) -> &mut Self {
+        self.default_settings = val;
+        self
+    }
 }
 impl ClientConfig {
-    pub(crate) fn get_url(&self) -> &Url {
-        &self.url
+    pub(crate) fn get_url(&self) -> Url {
+        match self.pinned_endpoint.or_else(|| self.endpoints.select()) {
+            Some(index) => self.get_endpoint_url(index),
+            None => self.url.to_owned(),
+        }
+    }
+    pub(crate) fn get_endpoint_urls(&self) -> Vec<Url> {
+        (0..self.endpoints.urls().len())
+            .map(|index| self.get_endpoint_url(index))
+            .collect()
+    }
+    fn get_endpoint_url(&self, index: usize) -> Url {
+        let urls = self.endpoints.urls();
+        let mut url = urls[index].to_owned();
+
+        // `self.url` is the first url followed by the parameters set for every endpoint.
+        let pairs = self
+            .url
+            .query_pairs()
+            .skip(urls[0].query_pairs().count())
+            .collect::<Vec<_>>();
+        if !pairs.is_empty() {
+            url.query_pairs_mut().extend_pairs(pairs);
+        }
+        url
+    }
+    pub(crate) fn set_endpoint_health(&self, index: usize, is_healthy: bool) {
+        self.endpoints.set_health(index, is_healthy);
+    }
+    pub(crate) fn mark_endpoint_down(&self, uri: &Uri) {
+        self.endpoints.mark_down(uri);
+    }
+    /// Sends every request to one endpoint, as sessions and queries only live on the server running them.
+    pub(crate) fn pin_endpoint(&mut self) {
+        if self.pinned_endpoint.is_none() {
+            self.pinned_endpoint = self.endpoints.select();
+        }
+    }
+    /// Pins the endpoint that ran `query_id`, returns false if it is not known.
+    pub(crate) fn pin_endpoint_of_query(&mut self, query_id: &str) -> bool {
+        match self.endpoints.find_query(query_id) {
+            Some(index) => {
+                self.pinned_endpoint = Some(index);
+                true
+            }
+            None => false,
+        }
+    }
+    pub(crate) fn record_endpoint_query(&self, uri: &Uri, query_id: &str) {
+        self.endpoints.record_query(uri, query_id);
     }
     pub(crate) fn get_request(&self) -> Request<()> {
         let mut req = Request::new(());
@@ -134,6 +339,25 @@ impl ClientConfig {
             .as_deref()
             .unwrap_or(HTTP_SERVER_DEFAULT_RESPONSE_DEFAULT)
     }
+    pub(crate) fn get_request_compression(&self) -> Option<Compression> {
+        self.request_compression
+    }
+    pub(crate) fn get_retry_policy(&self) -> Option<&RetryPolicy> {
+        self.retry_policy.as_ref()
+    }
+    pub(crate) fn get_default_settings(&self) -> &QuerySettings {
+        &self.default_settings
+    }
+    /// Sent as the query id of every request, over the one in the request settings.
+    pub(crate) fn set_query_id(&mut self, val: impl Into<String>) {
+        self.query_id = Some(val.into());
+    }
+    pub(crate) fn get_query_id(&self) -> Option<&str> {
+        self.query_id.as_deref()
+    }
+    pub(crate) fn clear_query_id(&mut self) {
+        self.query_id = None;
+    }
 }
 
 #[cfg(test)]
@@ -149,6 +373,20 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn with_from_dsn() -> Result<(), Box<dyn std::error::Error>> {
+        let config = ClientConfig::from_dsn("clickhouse://user@127.0.0.1/db")?;
+        assert_eq!(config.url.as_str(), "http://127.0.0.1:8123/");
+        assert_eq!(config.header_map.len(), 2);
+
+        assert!(matches!(
+            ClientConfig::from_dsn("https://127.0.0.1/?skip_verify=true"),
+            Err(DsnError::ParamInvalid(_))
+        ));
+
+        Ok(())
+    }
+
     #[test]
     fn with_set_url() -> Result<(), Box<dyn std::error::Error>> {
         let mut config = ClientConfig::default();
@@ -227,4 +465,102 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn with_set_ssl_certificate_auth() -> Result<(), Box<dyn std::error::Error>> {
+        let mut config = ClientConfig::default();
+        config
+            .set_username_to_header("user")?
+            .set_password_to_header("password")?
+            .set_ssl_certificate_auth("default")?;
+        assert_eq!(config.header_map.len(), 2);
+        assert_eq!(
+            config.header_map.get("X-ClickHouse-User").unwrap(),
+            "default"
+        );
+        assert_eq!(
+            config
+                .header_map
+                .get("X-ClickHouse-SSL-Certificate-Auth")
+                .unwrap(),
+            "on"
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "with-compression-zstd")]
+    #[test]
+    fn with_set_compression() -> Result<(), Box<dyn std::error::Error>> {
+        let mut config = ClientConfig::default();
+        config
+            .set_request_compression(Compression::Zstd)
+            .set_response_compression(Compression::Zstd)
+            .set_url("http://127.0.0.1:8123/foo/?bar=1")?;
+        assert_eq!(
+            config.url.as_str(),
+            "http://127.0.0.1:8123/foo/?bar=1&enable_http_compression=1"
+        );
+        assert_eq!(config.header_map.len(), 1);
+        assert_eq!(config.header_map.get("Accept-Encoding").unwrap(), "zstd");
+        assert_eq!(config.get_request_compression(), Some(Compression::Zstd));
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_set_urls() -> Result<(), Box<dyn std::error::Error>> {
+        let mut config = ClientConfig::default();
+        config
+            .set_database_to_url_parameter("db")
+            .set_urls(["http://a:8123/", "http://b:8123/"])?
+            .set_load_balancing(LoadBalancing::FirstHealthy);
+        assert_eq!(config.url.as_str(), "http://a:8123/?database=db");
+        assert_eq!(config.get_url().as_str(), "http://a:8123/?database=db");
+        assert_eq!(
+            config
+                .get_endpoint_urls()
+                .iter()
+                .map(|x| x.as_str())
+                .collect::<Vec<_>>(),
+            vec!["http://a:8123/?database=db", "http://b:8123/?database=db"]
+        );
+
+        config.mark_endpoint_down(&"http://a:8123/?database=db".parse()?);
+        assert_eq!(config.get_url().as_str(), "http://b:8123/?database=db");
+
+        config.set_endpoint_health(0, true);
+        assert_eq!(config.get_url().as_str(), "http://a:8123/?database=db");
+
+        // The settings change in place, keeping the health.
+        config.mark_endpoint_down(&"http://a:8123/?database=db".parse()?);
+        config
+            .set_endpoint_cooldown(Duration::from_secs(60))
+            .set_load_balancing(LoadBalancing::FirstHealthy);
+        assert_eq!(config.get_url().as_str(), "http://b:8123/?database=db");
+        config.set_endpoint_health(0, true);
+
+        // Each endpoint keeps its own query.
+        let mut config = ClientConfig::default();
+        config
+            .set_urls(["http://a:8123/?foo=1", "http://b:8123/?bar=2"])?
+            .set_database_to_url_parameter("db");
+        assert_eq!(
+            config
+                .get_endpoint_urls()
+                .iter()
+                .map(|x| x.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "http://a:8123/?foo=1&database=db",
+                "http://b:8123/?bar=2&database=db"
+            ]
+        );
+
+        config.set_url("http://c:8123/")?;
+        assert!(config.get_endpoint_urls().is_empty());
+        assert_eq!(config.get_url().as_str(), "http://c:8123/?database=db");
+
+        Ok(())
+    }
 }
@@ -0,0 +1,200 @@
+use std::io::Error as IoError;
+
+use futures_util::io::{AsyncBufRead, AsyncRead, AsyncReadExt as _, BufReader};
+use http::{
+    Response,
+    header::{CONTENT_ENCODING, CONTENT_LENGTH},
+};
+
+use crate::transport::Body;
+
+// https://clickhouse.com/docs/en/interfaces/http#compression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    #[cfg(feature = "with-compression-gzip")]
+    Gzip,
+    #[cfg(feature = "with-compression-zstd")]
+    Zstd,
+    #[cfg(feature = "with-compression-lz4")]
+    Lz4,
+}
+
+impl Compression {
+    pub fn content_encoding(&self) -> &'static str {
+        match *self {
+            #[cfg(feature = "with-compression-gzip")]
+            Self::Gzip => "gzip",
+            #[cfg(feature = "with-compression-zstd")]
+            Self::Zstd => "zstd",
+            #[cfg(feature = "with-compression-lz4")]
+            Self::Lz4 => "lz4",
+        }
+    }
+
+    pub fn from_content_encoding(val: &str) -> Option<Self> {
+        match val.trim() {
+            #[cfg(feature = "with-compression-gzip")]
+            "gzip" => Some(Self::Gzip),
+            #[cfg(feature = "with-compression-zstd")]
+            "zstd" => Some(Self::Zstd),
+            #[cfg(feature = "with-compression-lz4")]
+            "lz4" => Some(Self::Lz4),
+            _ => None,
+        }
+    }
+
+    #[cfg_attr(
+        not(any(
+            feature = "with-compression-gzip",
+            feature = "with-compression-zstd",
+            feature = "with-compression-lz4"
+        )),
+        allow(unused_variables)
+    )]
+    pub(crate) fn encode_reader<'a, R>(
+        &self,
+        reader: R,
+    ) -> Box<dyn AsyncRead + Send + Sync + Unpin + 'a>
+    where
+        R: AsyncBufRead + Send + Sync + Unpin + 'a,
+    {
+        match *self {
+            #[cfg(feature = "with-compression-gzip")]
+            Self::Gzip => Box::new(async_compression::futures::bufread::GzipEncoder::new(
+                reader,
+            )),
+            #[cfg(feature = "with-compression-zstd")]
+            Self::Zstd => Box::new(async_compression::futures::bufread::ZstdEncoder::new(
+                reader,
+            )),
+            #[cfg(feature = "with-compression-lz4")]
+            Self::Lz4 => Box::new(async_compression::futures::bufread::Lz4Encoder::new(reader)),
+        }
+    }
+
+    #[cfg_attr(
+        not(any(
+            feature = "with-compression-gzip",
+            feature = "with-compression-zstd",
+            feature = "with-compression-lz4"
+        )),
+        allow(unused_variables)
+    )]
+    pub(crate) fn decode_reader<'a, R>(
+        &self,
+        reader: R,
+    ) -> Box<dyn AsyncRead + Send + Sync + Unpin + 'a>
+    where
+        R: AsyncBufRead + Send + Sync + Unpin + 'a,
+    {
+        match *self {
+            #[cfg(feature = "with-compression-gzip")]
+            Self::Gzip => Box::new(async_compression::futures::bufread::GzipDecoder::new(
+                reader,
+            )),
+            #[cfg(feature = "with-compression-zstd")]
+            Self::Zstd => Box::new(async_compression::futures::bufread::ZstdDecoder::new(
+                reader,
+            )),
+            #[cfg(feature = "with-compression-lz4")]
+            Self::Lz4 => Box::new(async_compression::futures::bufread::Lz4Decoder::new(reader)),
+        }
+    }
+
+    pub(crate) async fn encode_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, IoError> {
+        let mut buf = Vec::with_capacity(bytes.len());
+        self.encode_reader(bytes).read_to_end(&mut buf).await?;
+        Ok(buf)
+    }
+
+    pub(crate) fn encode_body(&self, body: Body) -> Body {
+        Body::from_reader(self.encode_reader(BufReader::new(body)))
+    }
+}
+
+pub(crate) fn decode_response(resp: Response<Body>) -> Response<Body> {
+    let compression = resp
+        .headers()
+        .get(CONTENT_ENCODING)
+        .and_then(|x| x.to_str().ok())
+        .and_then(Compression::from_content_encoding);
+
+    match compression {
+        Some(compression) => {
+            let (mut parts, body) = resp.into_parts();
+            parts.headers.remove(CONTENT_ENCODING);
+            parts.headers.remove(CONTENT_LENGTH);
+
+            let body = Body::from_reader(compression.decode_reader(BufReader::new(body)));
+
+            Response::from_parts(parts, body)
+        }
+        None => resp,
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "with-compression-gzip",
+    feature = "with-compression-zstd",
+    feature = "with-compression-lz4"
+))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_encode_and_decode() -> Result<(), Box<dyn std::error::Error>> {
+        let bytes = b"[1, \"a\"]\n[2, \"b\"]\n".repeat(100);
+
+        for compression in [Compression::Gzip, Compression::Zstd, Compression::Lz4] {
+            assert_eq!(
+                Compression::from_content_encoding(compression.content_encoding()),
+                Some(compression)
+            );
+
+            let encoded = compression.encode_bytes(&bytes).await?;
+            assert!(encoded.len() < bytes.len());
+
+            let resp = Response::builder()
+                .header(CONTENT_ENCODING, compression.content_encoding())
+                .header(CONTENT_LENGTH, encoded.len())
+                .body(Body::from(encoded))?;
+            let mut resp = decode_response(resp);
+            assert!(resp.headers().get(CONTENT_ENCODING).is_none());
+
+            let mut buf = vec![];
+            resp.body_mut().read_to_end(&mut buf).await?;
+            assert_eq!(buf, bytes);
+        }
+
+        assert_eq!(Compression::from_content_encoding("br"), None);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "with-transport-isahc")]
+    #[tokio::test]
+    async fn test_decode_response_with_isahc() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::{
+            client::ClientBuilder,
+            test_helpers::{response, spawn_server},
+        };
+
+        let encoded = Compression::Gzip.encode_bytes(b"Ok.\n").await?;
+        let server = spawn_server(move |_| response(&[("Content-Encoding", "gzip")], &encoded));
+
+        let mut client_builder = ClientBuilder::new();
+        client_builder
+            .set_url(&server.url)?
+            .set_response_compression(Compression::Gzip);
+        let client = client_builder.build()?;
+
+        assert!(client.ping().await?);
+        assert_eq!(
+            server.requests.lock().unwrap()[0].header("Accept-Encoding"),
+            Some("gzip")
+        );
+
+        Ok(())
+    }
+}
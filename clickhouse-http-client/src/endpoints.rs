@@ -0,0 +1,306 @@
+use core::{
+    hash::{BuildHasher as _, Hasher as _},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+use std::{
+    collections::{VecDeque, hash_map::RandomState},
+    sync::Mutex,
+    time::Instant,
+};
+
+use http::Uri;
+use url::Url;
+
+const COOLDOWN_DEFAULT: Duration = Duration::from_secs(30);
+// Enough to find the endpoint of a query being cancelled, old entries are dropped.
+const QUERIES_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoadBalancing {
+    #[default]
+    RoundRobin,
+    Random,
+    /// The first endpoint that is not marked down, returning to it once its cooldown ends.
+    FirstHealthy,
+    /// Stays on one endpoint until it is marked down, then moves on to the next.
+    InOrder,
+}
+
+// Shared by the clones of a config, so the settings change in place and keep the health.
+#[derive(Debug)]
+pub(crate) struct Endpoints {
+    urls: Vec<Url>,
+    load_balancing: Mutex<LoadBalancing>,
+    cooldown: Mutex<Duration>,
+    down_until: Mutex<Vec<Option<Instant>>>,
+    cursor: AtomicUsize,
+    queries: Mutex<VecDeque<(String, usize)>>,
+}
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self::new(vec![], Default::default(), COOLDOWN_DEFAULT)
+    }
+}
+impl Endpoints {
+    pub(crate) fn new(urls: Vec<Url>, load_balancing: LoadBalancing, cooldown: Duration) -> Self {
+        Self {
+            down_until: Mutex::new(vec![None; urls.len()]),
+            urls,
+            load_balancing: Mutex::new(load_balancing),
+            cooldown: Mutex::new(cooldown),
+            cursor: AtomicUsize::new(0),
+            queries: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub(crate) fn urls(&self) -> &[Url] {
+        &self.urls
+    }
+    pub(crate) fn load_balancing(&self) -> LoadBalancing {
+        *self
+            .load_balancing
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+    pub(crate) fn set_load_balancing(&self, val: LoadBalancing) {
+        *self
+            .load_balancing
+            .lock()
+            .unwrap_or_else(|err| err.into_inner()) = val;
+    }
+    pub(crate) fn cooldown(&self) -> Duration {
+        *self.cooldown.lock().unwrap_or_else(|err| err.into_inner())
+    }
+    /// Applies to the endpoints marked down from now on.
+    pub(crate) fn set_cooldown(&self, val: Duration) {
+        *self.cooldown.lock().unwrap_or_else(|err| err.into_inner()) = val;
+    }
+
+    pub(crate) fn select(&self) -> Option<usize> {
+        let n = self.urls.len();
+        if n == 0 {
+            return None;
+        }
+
+        let now = Instant::now();
+        let down_until = self
+            .down_until
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        let is_up = |i: &usize| down_until[*i].map(|x| x <= now).unwrap_or(true);
+        let find_up_from = |start: usize| (0..n).map(|i| (start + i) % n).find(is_up);
+
+        let index = match self.load_balancing() {
+            LoadBalancing::RoundRobin => {
+                let start = self.cursor.fetch_add(1, Ordering::Relaxed) % n;
+                find_up_from(start).unwrap_or(start)
+            }
+            LoadBalancing::Random => {
+                let start = RandomState::new().build_hasher().finish() as usize % n;
+                find_up_from(start).unwrap_or(start)
+            }
+            LoadBalancing::FirstHealthy => find_up_from(0).unwrap_or(0),
+            LoadBalancing::InOrder => {
+                let start = self.cursor.load(Ordering::Relaxed) % n;
+                let index = find_up_from(start).unwrap_or(start);
+                self.cursor.store(index, Ordering::Relaxed);
+                index
+            }
+        };
+
+        Some(index)
+    }
+
+    pub(crate) fn set_health(&self, index: usize, is_healthy: bool) {
+        let cooldown = self.cooldown();
+        let mut down_until = self
+            .down_until
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        if let Some(x) = down_until.get_mut(index) {
+            *x = if is_healthy {
+                None
+            } else {
+                Some(Instant::now() + cooldown)
+            };
+        }
+    }
+
+    pub(crate) fn mark_down(&self, uri: &Uri) {
+        if let Some(index) = self.position(uri) {
+            self.set_health(index, false);
+        }
+    }
+
+    /// Remembers the endpoint of `uri` as the one running `query_id`.
+    pub(crate) fn record_query(&self, uri: &Uri, query_id: &str) {
+        let Some(index) = self.position(uri) else {
+            return;
+        };
+
+        let mut queries = self.queries.lock().unwrap_or_else(|err| err.into_inner());
+        if queries.len() >= QUERIES_CAPACITY {
+            queries.pop_front();
+        }
+        queries.push_back((query_id.to_owned(), index));
+    }
+    pub(crate) fn find_query(&self, query_id: &str) -> Option<usize> {
+        let queries = self.queries.lock().unwrap_or_else(|err| err.into_inner());
+        queries
+            .iter()
+            .rev()
+            .find(|(x, _)| x == query_id)
+            .map(|(_, index)| *index)
+    }
+
+    fn position(&self, uri: &Uri) -> Option<usize> {
+        self.urls.iter().position(|url| {
+            uri.scheme_str() == Some(url.scheme())
+                && uri.host() == url.host_str()
+                && uri.port_u16().or_else(|| default_port(uri)) == url.port_or_known_default()
+                && uri.path() == url.path()
+        })
+    }
+}
+
+fn default_port(uri: &Uri) -> Option<u16> {
+    match uri.scheme_str() {
+        Some("http") => Some(80),
+        Some("https") => Some(443),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoints(load_balancing: LoadBalancing) -> Endpoints {
+        Endpoints::new(
+            vec![
+                "http://a:8123/".parse().unwrap(),
+                "http://b:8123/".parse().unwrap(),
+                "http://c/".parse().unwrap(),
+            ],
+            load_balancing,
+            Duration::from_secs(60),
+        )
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let endpoints = endpoints(LoadBalancing::RoundRobin);
+        assert_eq!(
+            (0..4)
+                .map(|_| endpoints.select().unwrap())
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2, 0]
+        );
+
+        endpoints.mark_down(&"http://b:8123/?query_id=1".parse().unwrap());
+        assert_eq!(
+            (0..4)
+                .map(|_| endpoints.select().unwrap())
+                .collect::<Vec<_>>(),
+            vec![2, 2, 0, 2]
+        );
+    }
+
+    #[test]
+    fn test_random() {
+        let endpoints = endpoints(LoadBalancing::Random);
+        endpoints.set_health(0, false);
+        endpoints.set_health(1, false);
+        for _ in 0..10 {
+            assert_eq!(endpoints.select(), Some(2));
+        }
+    }
+
+    #[test]
+    fn test_first_healthy() {
+        let endpoints = endpoints(LoadBalancing::FirstHealthy);
+        assert_eq!(endpoints.select(), Some(0));
+
+        endpoints.mark_down(&"http://a:8123/".parse().unwrap());
+        assert_eq!(endpoints.select(), Some(1));
+
+        endpoints.set_health(0, true);
+        assert_eq!(endpoints.select(), Some(0));
+
+        (0..3).for_each(|i| endpoints.set_health(i, false));
+        assert_eq!(endpoints.select(), Some(0));
+    }
+
+    #[test]
+    fn test_in_order() {
+        let endpoints = endpoints(LoadBalancing::InOrder);
+        assert_eq!(endpoints.select(), Some(0));
+
+        endpoints.mark_down(&"http://a:8123/".parse().unwrap());
+        assert_eq!(endpoints.select(), Some(1));
+
+        endpoints.set_health(0, true);
+        assert_eq!(endpoints.select(), Some(1));
+
+        endpoints.mark_down(&"http://b:8123/".parse().unwrap());
+        endpoints.mark_down(&"http://c/".parse().unwrap());
+        assert_eq!(endpoints.select(), Some(0));
+    }
+
+    #[test]
+    fn test_set_load_balancing_and_cooldown() {
+        let endpoints = endpoints(LoadBalancing::RoundRobin);
+        endpoints.mark_down(&"http://a:8123/".parse().unwrap());
+
+        // The health is kept.
+        endpoints.set_load_balancing(LoadBalancing::FirstHealthy);
+        assert_eq!(endpoints.select(), Some(1));
+
+        // Without a cooldown b is back at once, a is still down from before.
+        endpoints.set_cooldown(Duration::ZERO);
+        endpoints.mark_down(&"http://b:8123/".parse().unwrap());
+        assert_eq!(endpoints.select(), Some(1));
+        assert_eq!(endpoints.cooldown(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_record_query() {
+        let endpoints = endpoints(LoadBalancing::RoundRobin);
+        endpoints.record_query(&"http://b:8123/?query_id=1".parse().unwrap(), "1");
+        endpoints.record_query(&"http://c:80/?query_id=2".parse().unwrap(), "2");
+        endpoints.record_query(&"http://d:8123/?query_id=3".parse().unwrap(), "3");
+        assert_eq!(endpoints.find_query("1"), Some(1));
+        assert_eq!(endpoints.find_query("2"), Some(2));
+        assert_eq!(endpoints.find_query("3"), None);
+
+        for i in 0..QUERIES_CAPACITY {
+            endpoints.record_query(&"http://a:8123/".parse().unwrap(), &format!("a{i}"));
+        }
+        assert_eq!(endpoints.find_query("a0"), Some(0));
+        assert_eq!(endpoints.find_query("1"), None);
+        assert_eq!(endpoints.find_query("2"), None);
+
+        // The scheme and the path tell apart endpoints on one host and port.
+        let endpoints = Endpoints::new(
+            vec![
+                "http://a:8123/foo/".parse().unwrap(),
+                "http://a:8123/bar/".parse().unwrap(),
+                "https://a:8123/foo/".parse().unwrap(),
+            ],
+            LoadBalancing::RoundRobin,
+            Duration::from_secs(60),
+        );
+        endpoints.record_query(&"http://a:8123/bar/?query_id=1".parse().unwrap(), "1");
+        endpoints.record_query(&"https://a:8123/foo/?query_id=2".parse().unwrap(), "2");
+        endpoints.record_query(&"http://a:8123/?query_id=3".parse().unwrap(), "3");
+        assert_eq!(endpoints.find_query("1"), Some(1));
+        assert_eq!(endpoints.find_query("2"), Some(2));
+        assert_eq!(endpoints.find_query("3"), None);
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(Endpoints::default().select(), None);
+    }
+}
@@ -1,24 +1,149 @@
 use std::io::Error as IoError;
 
-use isahc::http;
+use clickhouse_format::format_name::FormatName;
+use http::{self, header::HeaderMap};
+
+use crate::{
+    client_config::{EXCEPTION_CODE_KEY_HEADER, EXCEPTION_TAG_KEY_HEADER, QUERY_ID_KEY_HEADER},
+    retry_policy::RetryPolicy,
+    transport::TransportError,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
-    #[error("IsahcError {0:?}")]
-    IsahcError(#[from] isahc::Error),
+    #[error("TransportError {0:?}")]
+    TransportError(#[from] TransportError),
+    #[error("TransportMissing")]
+    TransportMissing,
+    #[error("TokioRuntimeMissing")]
+    TokioRuntimeMissing,
+    #[error("TlsConfigInvalid {0:?}")]
+    TlsConfigInvalid(String),
     #[error("HttpInvalidUri {0:?}")]
     HttpInvalidUri(#[from] http::uri::InvalidUri),
+    #[error("HttpInvalidHeaderValue {0:?}")]
+    HttpInvalidHeaderValue(#[from] http::header::InvalidHeaderValue),
     #[error("UrlParseError {0:?}")]
     UrlParseError(#[from] url::ParseError),
     #[error("IoError {0:?}")]
     IoError(#[from] IoError),
     //
+    #[error("ServerException {0:?}")]
+    ServerException(#[from] ServerException),
+    //
     #[error("ClientExecuteError {0:?}")]
     ClientExecuteError(#[from] ClientExecuteError),
     #[error("ClientInsertWithFormatError {0:?}")]
     ClientInsertWithFormatError(#[from] ClientInsertWithFormatError),
     #[error("ClientSelectWithFormatError {0:?}")]
     ClientSelectWithFormatError(#[from] ClientSelectWithFormatError),
+    #[error("QueryError {0:?}")]
+    QueryError(#[from] QueryError),
+    #[error("DsnError {0:?}")]
+    DsnError(#[from] DsnError),
+}
+
+impl Error {
+    /// Whether the default `RetryPolicy` retries it.
+    pub fn is_retryable(&self) -> bool {
+        RetryPolicy::default().is_retryable(self)
+    }
+}
+
+//
+//
+//
+// https://github.com/ClickHouse/ClickHouse/blob/master/src/Common/ErrorCodes.cpp
+pub const TIMEOUT_EXCEEDED: i32 = 159;
+pub const TOO_MANY_SIMULTANEOUS_QUERIES: i32 = 202;
+pub const SOCKET_TIMEOUT: i32 = 209;
+pub const NETWORK_ERROR: i32 = 210;
+pub const TABLE_IS_READ_ONLY: i32 = 242;
+pub const TOO_MANY_PARTS: i32 = 252;
+pub const ALL_CONNECTION_TRIES_FAILED: i32 = 279;
+pub const KEEPER_EXCEPTION: i32 = 999;
+
+// Not TIMEOUT_EXCEEDED, the query would most likely time out again.
+pub(crate) const RETRYABLE_CODES: &[i32] = &[
+    TOO_MANY_SIMULTANEOUS_QUERIES,
+    SOCKET_TIMEOUT,
+    NETWORK_ERROR,
+    TABLE_IS_READ_ONLY,
+    TOO_MANY_PARTS,
+    ALL_CONNECTION_TRIES_FAILED,
+    KEEPER_EXCEPTION,
+];
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("{message}")]
+pub struct ServerException {
+    pub code: i32,
+    pub name: Option<String>,
+    pub message: String,
+    pub query_id: Option<String>,
+}
+impl ServerException {
+    pub fn is_retryable(&self) -> bool {
+        RETRYABLE_CODES.contains(&self.code)
+    }
+
+    pub(crate) fn from_parts(headers: &HeaderMap, body: &str) -> Option<Self> {
+        let message = body.trim();
+
+        let code = headers
+            .get(EXCEPTION_CODE_KEY_HEADER)
+            .and_then(|x| x.to_str().ok())
+            .and_then(|x| x.trim().parse().ok())
+            .or_else(|| parse_code(message))?;
+
+        let query_id = headers
+            .get(QUERY_ID_KEY_HEADER)
+            .and_then(|x| x.to_str().ok())
+            .map(ToOwned::to_owned);
+
+        Some(Self {
+            code,
+            name: parse_name(message),
+            message: message.to_owned(),
+            query_id,
+        })
+    }
+}
+
+pub(crate) fn get_exception_tag(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(EXCEPTION_TAG_KEY_HEADER)
+        .and_then(|x| x.to_str().ok())
+}
+
+// An exception after the response has started, the status is 200 and the code is only in the message.
+pub(crate) fn exception_in_body(headers: &HeaderMap, message: String) -> Error {
+    match ServerException::from_parts(headers, &message) {
+        Some(exception) => exception.into(),
+        None => ClientSelectWithFormatError::FormatDeError(message).into(),
+    }
+}
+
+// e.g. "Code: 60. DB::Exception: ..." or "Code: 60, e.displayText() = DB::Exception: ..."
+fn parse_code(message: &str) -> Option<i32> {
+    let s = message.strip_prefix("Code: ")?;
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s[..end].parse().ok()
+}
+
+// e.g. "... doesn't exist. (UNKNOWN_TABLE) (version 21.11.1.1)"
+fn parse_name(message: &str) -> Option<String> {
+    message
+        .split('(')
+        .skip(1)
+        .filter_map(|x| x.split_once(')').map(|(name, _)| name))
+        .find(|name| {
+            !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        })
+        .map(ToOwned::to_owned)
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -33,6 +158,10 @@ pub enum ClientInsertWithFormatError {
     FormatSerError(String),
     #[error("StatusCodeMismatch {0:?}")]
     StatusCodeMismatch(http::StatusCode),
+    #[error("AsyncInsertOptionsInvalid {0:?}")]
+    AsyncInsertOptionsInvalid(String),
+    #[error("FormatNotConcatenable {0:?}")]
+    FormatNotConcatenable(FormatName),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -43,4 +172,95 @@ pub enum ClientSelectWithFormatError {
     FormatMismatch(String),
     #[error("FormatDeError {0:?}")]
     FormatDeError(String),
+    #[error("FormatSerError {0:?}")]
+    FormatSerError(String),
+    #[error("ExternalTableInvalid {0:?}")]
+    ExternalTableInvalid(String),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum QueryError {
+    #[error("ParamTypeNameInvalid {0:?}")]
+    ParamTypeNameInvalid(String),
+    #[error("ParamNotFound {0:?}")]
+    ParamNotFound(String),
+    #[error("ParamUnbound {0:?}")]
+    ParamUnbound(String),
+    #[error("ParamValueInvalid {0:?}")]
+    ParamValueInvalid(String),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum DsnError {
+    #[error("UrlParseError {0:?}")]
+    UrlParseError(#[from] url::ParseError),
+    #[error("HttpInvalidHeaderValue {0:?}")]
+    HttpInvalidHeaderValue(#[from] http::header::InvalidHeaderValue),
+    #[error("SchemeInvalid {0:?}")]
+    SchemeInvalid(String),
+    #[error("ParamInvalid {0:?}")]
+    ParamInvalid(String),
+    #[error("ParamUnknown {0:?}")]
+    ParamUnknown(String),
+}
+
+impl serde::ser::Error for QueryError {
+    fn custom<T: core::fmt::Display>(msg: T) -> Self {
+        Self::ParamValueInvalid(msg.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_exception() {
+        let mut headers = HeaderMap::new();
+        headers.insert(EXCEPTION_CODE_KEY_HEADER, "60".parse().unwrap());
+        headers.insert(QUERY_ID_KEY_HEADER, "foo".parse().unwrap());
+        let exception = ServerException::from_parts(
+            &headers,
+            "Code: 60. DB::Exception: Table default.t_x doesn't exist. (UNKNOWN_TABLE) (version 22.3.2.2 (official build))\n",
+        )
+        .unwrap();
+        assert_eq!(exception.code, 60);
+        assert_eq!(exception.name, Some("UNKNOWN_TABLE".to_owned()));
+        assert_eq!(
+            exception.message,
+            "Code: 60. DB::Exception: Table default.t_x doesn't exist. (UNKNOWN_TABLE) (version 22.3.2.2 (official build))"
+        );
+        assert_eq!(exception.query_id, Some("foo".to_owned()));
+        assert!(!exception.is_retryable());
+
+        let exception = ServerException::from_parts(
+            &HeaderMap::new(),
+            "Code: 202, e.displayText() = DB::Exception: Too many simultaneous queries. Maximum: 100 (version 21.3.4.25 (official build))",
+        )
+        .unwrap();
+        assert_eq!(exception.code, TOO_MANY_SIMULTANEOUS_QUERIES);
+        assert_eq!(exception.name, None);
+        assert_eq!(exception.query_id, None);
+        assert!(exception.is_retryable());
+        assert!(Error::from(exception).is_retryable());
+        assert!(
+            Error::from(ClientExecuteError::StatusCodeMismatch(
+                http::StatusCode::BAD_GATEWAY
+            ))
+            .is_retryable()
+        );
+
+        assert!(matches!(
+            exception_in_body(
+                &HeaderMap::new(),
+                "Code: 241. DB::Exception: Memory limit (total) exceeded".to_owned()
+            ),
+            Error::ServerException(ServerException { code: 241, .. })
+        ));
+
+        assert_eq!(
+            ServerException::from_parts(&HeaderMap::new(), "<html>502 Bad Gateway</html>"),
+            None
+        );
+    }
 }
@@ -0,0 +1,163 @@
+use clickhouse_data_type::type_name::TypeName;
+use clickhouse_format::{format_name::FormatName, input::Input};
+use url::Url;
+
+use crate::error::ClientSelectWithFormatError;
+
+// https://clickhouse.com/docs/en/engines/table-engines/special/external-data
+#[derive(Debug, Clone)]
+pub struct ExternalTable {
+    name: String,
+    structure: Vec<(String, TypeName)>,
+    format_name: FormatName,
+    format_bytes: Vec<u8>,
+}
+
+impl ExternalTable {
+    pub fn new<I: Input>(
+        name: impl Into<String>,
+        structure: Vec<(String, TypeName)>,
+        input: I,
+    ) -> Result<Self, ClientSelectWithFormatError> {
+        let name = name.into();
+
+        if name.is_empty()
+            || name.starts_with(|c: char| c.is_ascii_digit())
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(ClientSelectWithFormatError::ExternalTableInvalid(format!(
+                "name {name:?} is not an identifier"
+            )));
+        }
+        if structure.is_empty() {
+            return Err(ClientSelectWithFormatError::ExternalTableInvalid(format!(
+                "structure of {name:?} is empty"
+            )));
+        }
+
+        let format_bytes = input
+            .serialize()
+            .map_err(|err| ClientSelectWithFormatError::FormatSerError(err.to_string()))?;
+
+        Ok(Self {
+            name,
+            structure,
+            format_name: I::format_name(),
+            format_bytes,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn structure(&self) -> &[(String, TypeName)] {
+        &self.structure
+    }
+
+    // e.g. "id UInt32, name String"
+    fn get_structure(&self) -> String {
+        self.structure
+            .iter()
+            .map(|(name, type_name)| format!("{name} {type_name}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub(crate) fn append_url_parameters(&self, url: &mut Url) {
+        url.query_pairs_mut()
+            .append_pair(&format!("{}_structure", self.name), &self.get_structure())
+            .append_pair(
+                &format!("{}_format", self.name),
+                &self.format_name.to_string(),
+            );
+    }
+}
+
+pub(crate) fn multipart_content_type(boundary: &str) -> String {
+    format!("multipart/form-data; boundary={boundary}")
+}
+
+pub(crate) fn multipart_body(boundary: &str, external_tables: &[ExternalTable]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(
+        external_tables
+            .iter()
+            .map(|x| x.format_bytes.len() + 256)
+            .sum(),
+    );
+
+    for external_table in external_tables {
+        body.extend_from_slice(
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"; filename=\"{name}\"\r\nContent-Type: application/octet-stream\r\n\r\n",
+                name = external_table.name
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(&external_table.format_bytes);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+
+    body
+}
+
+#[cfg(all(test, feature = "with-format-json"))]
+mod tests {
+    use super::*;
+
+    use clickhouse_format::input::JsonCompactEachRowInput;
+    use serde_json::Value;
+
+    fn external_table() -> ExternalTable {
+        ExternalTable::new(
+            "ids",
+            vec![
+                ("id".to_owned(), TypeName::UInt32),
+                ("name".to_owned(), "Nullable(String)".parse().unwrap()),
+            ],
+            JsonCompactEachRowInput::<Value>::new(vec![vec![1.into(), "a".into()]]),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(
+            external_table().get_structure(),
+            "id UInt32, name Nullable(String)"
+        );
+
+        for name in ["", "1ids", "ids;", "i ds"] {
+            assert!(matches!(
+                ExternalTable::new(
+                    name,
+                    vec![("id".to_owned(), TypeName::UInt32)],
+                    JsonCompactEachRowInput::<Value>::new(vec![])
+                ),
+                Err(ClientSelectWithFormatError::ExternalTableInvalid(_))
+            ));
+        }
+        assert!(
+            ExternalTable::new("ids", vec![], JsonCompactEachRowInput::<Value>::new(vec![]))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_append_url_parameters() {
+        let mut url: Url = "http://localhost:8123/".parse().unwrap();
+        external_table().append_url_parameters(&mut url);
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:8123/?ids_structure=id+UInt32%2C+name+Nullable%28String%29&ids_format=JSONCompactEachRow"
+        );
+    }
+
+    #[test]
+    fn test_multipart_body() {
+        assert_eq!(
+            String::from_utf8(multipart_body("foo", &[external_table()])).unwrap(),
+            "--foo\r\nContent-Disposition: form-data; name=\"ids\"; filename=\"ids\"\r\nContent-Type: application/octet-stream\r\n\r\n[1, \"a\"]\n\r\n--foo--\r\n"
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use core::{marker::PhantomData, mem, time::Duration};
+use std::time::Instant;
+
+use bytes::Bytes;
+use clickhouse_format::input::Input;
+
+use crate::{
+    client::{Client, Settings},
+    client_config::INSERT_DEDUPLICATION_TOKEN_KEY_URL_PARAMETER,
+    error::{ClientInsertWithFormatError, Error},
+    query_id::generate_query_id,
+    response_info::QueryResponseInfo,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct InserterStats {
+    pub rows: usize,
+    pub bytes: usize,
+    pub response_info: Option<QueryResponseInfo>,
+}
+
+/// Buffers rows and inserts them in batches.
+///
+/// Each row is serialized on its own, so `I` must be a format without a header,
+/// e.g. `JsonCompactEachRowInput` or `JsonEachRowInput`.
+/// `max_period` is only checked on `write`, there is no background timer, so an idle `Inserter`
+/// never flushes, call `flush` for that.
+/// When a flush fails, its rows stay buffered and the next flush sends them again, with the same
+/// `insert_deduplication_token`, before the rows written since.
+pub struct Inserter<T, I> {
+    client: Client,
+    sql_prefix: String,
+    max_rows: Option<usize>,
+    max_bytes: Option<usize>,
+    max_period: Option<Duration>,
+    buf: Vec<u8>,
+    rows: usize,
+    failed_batch: Option<Batch>,
+    last_flushed_at: Instant,
+    phantom: PhantomData<fn(T) -> I>,
+}
+
+impl<T, I> Inserter<T, I>
+where
+    I: Input + From<Vec<T>>,
+{
+    pub fn new(client: Client, table: impl AsRef<str>) -> Self {
+        Self {
+            client,
+            sql_prefix: format!("INSERT INTO {}", table.as_ref()),
+            max_rows: None,
+            max_bytes: None,
+            max_period: None,
+            buf: Vec::with_capacity(4096),
+            rows: 0,
+            failed_batch: None,
+            last_flushed_at: Instant::now(),
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn set_max_rows(&mut self, val: usize) -> &mut Self {
+        self.max_rows = Some(val);
+        self
+    }
+    pub fn set_max_bytes(&mut self, val: usize) -> &mut Self {
+        self.max_bytes = Some(val);
+        self
+    }
+    pub fn set_max_period(&mut self, val: Duration) -> &mut Self {
+        self.max_period = Some(val);
+        self
+    }
+
+    pub fn pending_rows(&self) -> usize {
+        self.rows
+    }
+    pub fn pending_bytes(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns the stats when the write triggered a flush.
+    pub async fn write(&mut self, row: T) -> Result<Option<InserterStats>, Error> {
+        let bytes = I::from(vec![row])
+            .serialize()
+            .map_err(|err| ClientInsertWithFormatError::FormatSerError(err.to_string()))?;
+        self.buf.extend_from_slice(&bytes);
+        self.rows += 1;
+
+        if self.is_full() {
+            return self.flush().await.map(Some);
+        }
+        Ok(None)
+    }
+
+    pub async fn flush(&mut self) -> Result<InserterStats, Error> {
+        self.last_flushed_at = Instant::now();
+
+        let mut stats = InserterStats::default();
+        while self.rows > 0 {
+            let batch = self.failed_batch.take().unwrap_or_else(|| Batch {
+                insert_deduplication_token: generate_query_id(),
+                rows: self.rows,
+                bytes: self.buf.len(),
+            });
+
+            let buf = Bytes::from(mem::take(&mut self.buf));
+            let ret = self
+                .client
+                .insert_with_format_bytes(
+                    &self.sql_prefix,
+                    I::format_name(),
+                    buf.slice(..batch.bytes),
+                    self.get_settings(&batch),
+                )
+                .await;
+            // No copy, the request holds no reference to it any more.
+            self.buf = buf.into();
+
+            match ret {
+                Ok(response_info) => {
+                    self.buf.drain(..batch.bytes);
+                    self.rows -= batch.rows;
+                    stats.rows += batch.rows;
+                    stats.bytes += batch.bytes;
+                    stats.response_info = Some(response_info);
+                }
+                Err(err) => {
+                    self.failed_batch = Some(batch);
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Flushes the rest, the rows stay buffered if that fails.
+    pub async fn end(&mut self) -> Result<InserterStats, Error> {
+        self.flush().await
+    }
+
+    fn get_settings<'a>(&self, batch: &'a Batch) -> Option<Settings<'a>> {
+        let is_insert_deduplication = self
+            .client
+            .get_retry_policy()
+            .map(|x| x.is_insert_deduplication())
+            .unwrap_or(true);
+
+        is_insert_deduplication.then(|| {
+            vec![(
+                INSERT_DEDUPLICATION_TOKEN_KEY_URL_PARAMETER,
+                batch.insert_deduplication_token.as_str(),
+            )]
+        })
+    }
+
+    fn is_full(&self) -> bool {
+        self.max_rows.map(|x| self.rows >= x).unwrap_or(false)
+            || self.max_bytes.map(|x| self.buf.len() >= x).unwrap_or(false)
+            || self
+                .max_period
+                .map(|x| self.last_flushed_at.elapsed() >= x)
+                .unwrap_or(false)
+    }
+}
+
+// Rows at the start of the buffer, inserted together with one token.
+struct Batch {
+    insert_deduplication_token: String,
+    rows: usize,
+    bytes: usize,
+}
+
+#[cfg(all(test, feature = "with-format-json"))]
+mod tests {
+    use super::*;
+
+    use clickhouse_format::input::JsonCompactEachRowInput;
+    use serde_json::Value;
+
+    #[test]
+    fn test_is_full() {
+        let client = crate::test_helpers::MockTransport::ok().client();
+
+        let mut inserter: Inserter<Vec<Value>, JsonCompactEachRowInput<Value>> =
+            client.inserter("t");
+        assert!(!inserter.is_full());
+
+        inserter.set_max_rows(2).set_max_bytes(100);
+        inserter.rows = 1;
+        assert!(!inserter.is_full());
+        inserter.rows = 2;
+        assert!(inserter.is_full());
+
+        inserter.rows = 1;
+        inserter.buf = vec![b'x'; 100];
+        assert!(inserter.is_full());
+
+        inserter.buf.clear();
+        inserter.set_max_period(Duration::from_millis(0));
+        assert!(inserter.is_full());
+    }
+
+    #[tokio::test]
+    async fn test_flush() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::test_helpers::MockTransport;
+
+        let transport = MockTransport::ok();
+        let client = transport.client();
+
+        let mut inserter: Inserter<Vec<Value>, JsonCompactEachRowInput<Value>> =
+            client.inserter("t");
+        inserter.set_max_rows(2);
+        assert!(inserter.write(vec![1.into()]).await?.is_none());
+        let stats = inserter.write(vec![2.into()]).await?.unwrap();
+        assert_eq!((stats.rows, stats.bytes), (2, 8));
+        assert_eq!((inserter.pending_rows(), inserter.pending_bytes()), (0, 0));
+
+        inserter.write(vec![3.into()]).await?;
+        assert_eq!(inserter.end().await?.rows, 1);
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].body, b"[1]\n[2]\n");
+        assert_eq!(requests[1].body, b"[3]\n");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_flush_failed() -> Result<(), Box<dyn std::error::Error>> {
+        use core::sync::atomic::{AtomicBool, Ordering};
+
+        use http::{Response, StatusCode};
+
+        use crate::{test_helpers::MockTransport, transport::Body};
+
+        let is_failed = AtomicBool::new(false);
+        let transport = MockTransport::new(move |_| {
+            let status = if is_failed.swap(true, Ordering::SeqCst) {
+                StatusCode::OK
+            } else {
+                StatusCode::SERVICE_UNAVAILABLE
+            };
+            Ok(Response::builder()
+                .status(status)
+                .body(Body::empty())
+                .unwrap())
+        });
+        let client = transport.client();
+
+        let mut inserter: Inserter<Vec<Value>, JsonCompactEachRowInput<Value>> =
+            client.inserter("t");
+        inserter.write(vec![1.into()]).await?;
+        assert!(inserter.flush().await.is_err());
+        assert_eq!((inserter.pending_rows(), inserter.pending_bytes()), (1, 4));
+
+        // The failed batch is sent again on its own, then the rows written since.
+        inserter.write(vec![2.into()]).await?;
+        let stats = inserter.end().await?;
+        assert_eq!((stats.rows, stats.bytes), (2, 8));
+        assert_eq!((inserter.pending_rows(), inserter.pending_bytes()), (0, 0));
+
+        let requests = transport.requests.lock().unwrap();
+        let tokens = requests
+            .iter()
+            .map(|x| {
+                x.query_pairs()
+                    .into_iter()
+                    .find(|(k, _)| k == "insert_deduplication_token")
+                    .unwrap()
+                    .1
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].body, b"[1]\n");
+        assert_eq!(requests[1].body, b"[1]\n");
+        assert_eq!(requests[2].body, b"[2]\n");
+        assert_eq!(tokens[0], tokens[1]);
+        assert_ne!(tokens[1], tokens[2]);
+
+        Ok(())
+    }
+}
@@ -1,9 +1,52 @@
+pub use clickhouse_data_type;
 pub use clickhouse_format;
+pub use http;
+#[cfg(feature = "with-transport-isahc")]
 pub use isahc;
 
+pub mod async_insert;
+#[cfg(feature = "with-blocking")]
+pub mod blocking;
+pub mod cancel_on_drop;
 pub mod client;
 pub mod client_config;
+pub mod compression;
+pub mod dsn;
+pub mod endpoints;
 pub mod error;
+pub mod external_data;
+pub mod inserter;
+#[cfg(feature = "with-format-json")]
+pub mod progress_output;
+pub mod query;
+pub mod query_id;
+pub mod query_param;
+pub mod query_settings;
+pub mod response_info;
+pub mod retry_policy;
+pub mod select_stream;
+pub mod session;
+pub mod tls;
+pub mod transport;
 
+#[cfg(test)]
+pub(crate) mod test_helpers;
+
+pub use self::async_insert::{AsyncInsertMode, AsyncInsertOptions};
+pub use self::cancel_on_drop::CancelOnDrop;
 pub use self::client::{Client, ClientBuilder};
+pub use self::compression::Compression;
+pub use self::endpoints::LoadBalancing;
 pub use self::error::Error;
+pub use self::external_data::ExternalTable;
+pub use self::inserter::{Inserter, InserterStats};
+#[cfg(feature = "with-format-json")]
+pub use self::progress_output::ProgressOutput;
+pub use self::query::Query;
+pub use self::query_settings::{QuerySettings, Readonly};
+pub use self::response_info::{Progress, QueryResponseInfo, QuerySummary};
+pub use self::retry_policy::RetryPolicy;
+pub use self::select_stream::SelectStream;
+pub use self::session::Session;
+pub use self::tls::TlsConfig;
+pub use self::transport::{Body, Transport, TransportError};
@@ -0,0 +1,71 @@
+use clickhouse_format::{
+    format_name::FormatName,
+    output::{
+        JsonEachRowWithProgressOutput, Output, OutputResult, StreamOutput,
+        json_each_row_with_progress::{JsonEachRowProgress, JsonEachRowWithProgressOutputError},
+    },
+};
+use serde::de::DeserializeOwned;
+
+use crate::response_info::Progress;
+
+/// `JsonEachRowWithProgressOutput` that calls `on_progress` for each progress line as it is read.
+///
+/// Used by `Client::select_stream_with_progress`.
+pub struct ProgressOutput<T, F> {
+    inner: JsonEachRowWithProgressOutput<T>,
+    on_progress: F,
+}
+impl<T, F> ProgressOutput<T, F>
+where
+    F: FnMut(Progress),
+{
+    pub fn new(on_progress: F) -> Self {
+        Self {
+            inner: JsonEachRowWithProgressOutput::new(),
+            on_progress,
+        }
+    }
+}
+
+impl<T, F> Output for ProgressOutput<T, F>
+where
+    T: DeserializeOwned,
+{
+    type Row = T;
+    type Info = JsonEachRowProgress;
+
+    type Error = JsonEachRowWithProgressOutputError;
+
+    fn format_name() -> FormatName {
+        FormatName::JsonEachRowWithProgress
+    }
+
+    fn deserialize(&self, slice: &[u8]) -> OutputResult<Self::Row, Self::Info, Self::Error> {
+        self.inner.deserialize(slice)
+    }
+}
+
+impl<T, F> StreamOutput for ProgressOutput<T, F>
+where
+    T: DeserializeOwned,
+    F: FnMut(Progress),
+{
+    fn deserialize_line(&mut self, line: &[u8]) -> Result<Option<Self::Row>, Self::Error> {
+        let row = self.inner.deserialize_line(line)?;
+
+        // The server writes `{"progress":{...}}`, the inner output keeps the latest one.
+        if row.is_none()
+            && line.starts_with(br#"{"progress""#)
+            && let Some(progress) = self.inner.info()
+        {
+            (self.on_progress)(progress);
+        }
+
+        Ok(row)
+    }
+
+    fn info(&self) -> Option<Self::Info> {
+        self.inner.info()
+    }
+}
@@ -0,0 +1,348 @@
+use clickhouse_data_type::type_name::TypeName;
+use clickhouse_format::{
+    input::Input,
+    output::{Output, StreamOutput},
+};
+use serde::Serialize;
+
+use crate::{
+    client::{Client, Settings},
+    error::{Error, QueryError},
+    query_param,
+    response_info::QueryResponseInfo,
+    select_stream::SelectStream,
+};
+
+// https://clickhouse.com/docs/en/interfaces/http#cli-queries-with-parameters
+pub struct Query<'a> {
+    client: &'a Client,
+    sql: String,
+    params: Vec<QueryParam>,
+}
+
+#[derive(Debug, Clone)]
+struct QueryParam {
+    name: String,
+    key: String,
+    param_type: ParamType,
+    value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ParamType {
+    Typed(TypeName),
+    // Not known to `TypeName`, e.g. Identifier, Bool or Date32, the value is sent as escaped text.
+    Untyped(String),
+}
+
+impl<'a> Query<'a> {
+    pub(crate) fn new(client: &'a Client, sql: impl Into<String>) -> Result<Self, QueryError> {
+        let sql = sql.into();
+
+        let mut params: Vec<QueryParam> = vec![];
+        for (name, param_type) in parse_placeholders(&sql) {
+            match params.iter().find(|x| x.name == name) {
+                Some(param) if param.param_type != param_type => {
+                    return Err(QueryError::ParamTypeNameInvalid(format!(
+                        "{name} declared as both {:?} and {param_type:?}",
+                        param.param_type
+                    )));
+                }
+                Some(_) => {}
+                None => params.push(QueryParam {
+                    key: format!("param_{name}"),
+                    name,
+                    param_type,
+                    value: None,
+                }),
+            }
+        }
+
+        Ok(Self {
+            client,
+            sql,
+            params,
+        })
+    }
+
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    pub fn bind<T>(mut self, name: &str, value: T) -> Result<Self, QueryError>
+    where
+        T: Serialize,
+    {
+        let param = self
+            .params
+            .iter_mut()
+            .find(|x| x.name == name)
+            .ok_or_else(|| QueryError::ParamNotFound(name.to_owned()))?;
+
+        param.value = Some(match &param.param_type {
+            ParamType::Typed(type_name) => query_param::to_string(&value, type_name)?,
+            ParamType::Untyped(_) => query_param::to_untyped_string(&value)?,
+        });
+
+        Ok(self)
+    }
+
+    fn get_settings<'b>(
+        &'b self,
+        settings: Option<Settings<'b>>,
+    ) -> Result<Settings<'b>, QueryError> {
+        let mut settings = settings.unwrap_or_default();
+        for param in &self.params {
+            let value = param
+                .value
+                .as_deref()
+                .ok_or_else(|| QueryError::ParamUnbound(param.name.to_owned()))?;
+            settings.push((param.key.as_str(), value));
+        }
+        Ok(settings)
+    }
+
+    //
+    //
+    //
+    pub async fn execute(
+        &self,
+        settings: impl Into<Option<Settings<'_>>>,
+    ) -> Result<QueryResponseInfo, Error> {
+        let settings = self.get_settings(settings.into())?;
+
+        self.client.execute(&self.sql, settings).await
+    }
+
+    pub async fn insert_with_format<I: Input>(
+        &self,
+        input: I,
+        settings: impl Into<Option<Settings<'_>>>,
+    ) -> Result<QueryResponseInfo, Error> {
+        let settings = self.get_settings(settings.into())?;
+
+        self.client
+            .insert_with_format(&self.sql, input, settings)
+            .await
+    }
+
+    pub async fn select_with_format<O: Output>(
+        &self,
+        output: O,
+        settings: impl Into<Option<Settings<'_>>>,
+    ) -> Result<(Vec<O::Row>, O::Info), Error> {
+        let settings = self.get_settings(settings.into())?;
+
+        self.client
+            .select_with_format(&self.sql, output, settings)
+            .await
+    }
+
+    pub async fn select_stream_with_format<O: StreamOutput>(
+        &self,
+        output: O,
+        settings: impl Into<Option<Settings<'_>>>,
+    ) -> Result<SelectStream<O>, Error> {
+        let settings = self.get_settings(settings.into())?;
+
+        self.client
+            .select_stream_with_format(&self.sql, output, settings)
+            .await
+    }
+}
+
+// e.g. "SELECT {id:UInt64}, {names:Array(String)}"
+fn parse_placeholders(sql: &str) -> Vec<(String, ParamType)> {
+    let bytes = sql.as_bytes();
+    let mut placeholders = vec![];
+
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'\'' | b'"' | b'`') => {
+                i = skip_quoted(bytes, i, quote);
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                i = sql[i..].find('\n').map(|n| i + n).unwrap_or(bytes.len());
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = sql[i + 2..]
+                    .find("*/")
+                    .map(|n| i + 2 + n + 2)
+                    .unwrap_or(bytes.len());
+            }
+            b'{' => match parse_placeholder(sql, i) {
+                Some((name, param_type, end)) => {
+                    placeholders.push((name, param_type));
+                    i = end;
+                }
+                None => i += 1,
+            },
+            _ => i += 1,
+        }
+    }
+
+    placeholders
+}
+
+fn skip_quoted(bytes: &[u8], start: usize, quote: u8) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b if b == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn parse_placeholder(sql: &str, start: usize) -> Option<(String, ParamType, usize)> {
+    let bytes = sql.as_bytes();
+
+    let mut i = start + 1;
+    while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+        i += 1;
+    }
+
+    let name_start = i;
+    while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+        i += 1;
+    }
+    let name = &sql[name_start..i];
+    if name.is_empty() || name.as_bytes()[0].is_ascii_digit() {
+        return None;
+    }
+
+    while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    if bytes.get(i) != Some(&b':') {
+        return None;
+    }
+    i += 1;
+
+    let type_start = i;
+    let mut depth = 0_usize;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\'' => {
+                i = skip_quoted(bytes, i, b'\'');
+                continue;
+            }
+            b'(' => depth += 1,
+            b')' => depth = depth.saturating_sub(1),
+            b'}' if depth == 0 => break,
+            _ => {}
+        }
+        i += 1;
+    }
+    if i >= bytes.len() {
+        return None;
+    }
+
+    let type_name_str = sql[type_start..i].trim();
+    let param_type = match type_name_str.parse::<TypeName>() {
+        Ok(type_name) => ParamType::Typed(type_name),
+        Err(_) => ParamType::Untyped(type_name_str.to_owned()),
+    };
+
+    Some((name.to_owned(), param_type, i + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_placeholders() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(
+            parse_placeholders(
+                "SELECT { id : UInt64 }, {names:Array(String)}, {e:Enum8('a' = 1)} FROM t WHERE s = '{x:UInt8}' AND `{y:UInt8}` = 1 -- {z:UInt8}\n AND 1 = {w: Nullable(Int8)} /* {v:UInt8} */"
+            ),
+            vec![
+                ("id".to_owned(), ParamType::Typed(TypeName::UInt64)),
+                (
+                    "names".to_owned(),
+                    ParamType::Typed(TypeName::Array(TypeName::String.into()))
+                ),
+                ("e".to_owned(), ParamType::Typed("Enum8('a' = 1)".parse()?)),
+                ("w".to_owned(), ParamType::Typed("Nullable(Int8)".parse()?)),
+            ]
+        );
+
+        assert_eq!(parse_placeholders("SELECT {}, {1:UInt8}, {a}"), vec![]);
+
+        assert_eq!(
+            parse_placeholders("SELECT * FROM {tbl:Identifier} WHERE {b: Bool}"),
+            vec![
+                (
+                    "tbl".to_owned(),
+                    ParamType::Untyped("Identifier".to_owned())
+                ),
+                ("b".to_owned(), ParamType::Untyped("Bool".to_owned())),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bind() -> Result<(), Box<dyn std::error::Error>> {
+        let client = crate::test_helpers::MockTransport::ok().client();
+
+        let query = client
+            .query("SELECT {id:UInt64}, {names:Array(String)}, {id:UInt64}")?
+            .bind("id", 1)?;
+        assert_eq!(query.params.len(), 2);
+        assert!(matches!(
+            query.get_settings(None),
+            Err(QueryError::ParamUnbound(name)) if name == "names"
+        ));
+
+        let query = query.bind("names", vec!["a", "b"])?;
+        assert_eq!(
+            query.get_settings(Some(vec![("max_threads", "1")]))?,
+            vec![
+                ("max_threads", "1"),
+                ("param_id", "1"),
+                ("param_names", "['a','b']")
+            ]
+        );
+
+        assert!(matches!(
+            client.query("SELECT 1")?.bind("id", 1),
+            Err(QueryError::ParamNotFound(_))
+        ));
+        assert!(matches!(
+            client.query("SELECT {id:UInt8}")?.bind("id", 256),
+            Err(QueryError::ParamValueInvalid(_))
+        ));
+        assert!(matches!(
+            client.query("SELECT {id:UInt8}, {id:String}"),
+            Err(QueryError::ParamTypeNameInvalid(_))
+        ));
+
+        let query = client
+            .query("SELECT * FROM {tbl:Identifier} WHERE d = {d:Date32} AND b = {b:Bool}")?
+            .bind("tbl", "t\tx")?
+            .bind("d", "2020-01-01")?
+            .bind("b", true)?;
+        assert_eq!(
+            query.get_settings(None)?,
+            vec![
+                ("param_tbl", "t\\tx"),
+                ("param_d", "2020-01-01"),
+                ("param_b", "true")
+            ]
+        );
+        assert!(matches!(
+            client
+                .query("SELECT {tbl:Identifier}")?
+                .bind("tbl", vec!["a"]),
+            Err(QueryError::ParamValueInvalid(_))
+        ));
+
+        Ok(())
+    }
+}
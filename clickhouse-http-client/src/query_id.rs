@@ -0,0 +1,33 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::{
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+static SEQ: AtomicUsize = AtomicUsize::new(0);
+
+// Also used for session_id.
+pub fn generate_query_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_nanos())
+        .unwrap_or_default();
+    let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+
+    format!("{:x}-{:x}-{:x}", nanos, process::id(), seq)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_generate_query_id() {
+        let query_ids = (0..100)
+            .map(|_| generate_query_id())
+            .collect::<HashSet<_>>();
+        assert_eq!(query_ids.len(), 100);
+    }
+}
@@ -0,0 +1,762 @@
+use core::fmt::{Display, Write as _};
+use std::borrow::Cow;
+
+use clickhouse_data_type::type_name::TypeName;
+use serde::ser::{self, Serialize};
+
+use crate::error::QueryError;
+
+// https://clickhouse.com/docs/en/interfaces/cli#cli-queries-with-parameters
+pub fn to_string<T>(value: &T, type_name: &TypeName) -> Result<String, QueryError>
+where
+    T: Serialize + ?Sized,
+{
+    let mut output = String::new();
+    value.serialize(ParamSerializer {
+        output: &mut output,
+        type_name,
+        is_nested: false,
+    })?;
+    Ok(output)
+}
+
+/// For a type not known to `TypeName`, so the value is not checked against it.
+/// Strings are escaped, numbers and bools written as is, nested values are rejected.
+pub(crate) fn to_untyped_string<T>(value: &T) -> Result<String, QueryError>
+where
+    T: Serialize + ?Sized,
+{
+    let value = serde_json::to_value(value)
+        .map_err(|err| QueryError::ParamValueInvalid(err.to_string()))?;
+
+    let mut output = String::new();
+    match value {
+        serde_json::Value::Null => output.push_str("\\N"),
+        serde_json::Value::Bool(v) => write!(output, "{v}").map_err(ser::Error::custom)?,
+        serde_json::Value::Number(v) => write!(output, "{v}").map_err(ser::Error::custom)?,
+        serde_json::Value::String(v) => write_escaped(&mut output, &v),
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+            return Err(QueryError::ParamValueInvalid(
+                "nested value of a param without known type".to_owned(),
+            ));
+        }
+    }
+    Ok(output)
+}
+
+struct ParamSerializer<'a> {
+    output: &'a mut String,
+    type_name: &'a TypeName,
+    is_nested: bool,
+}
+
+impl ParamSerializer<'_> {
+    fn mismatch(&self, value: impl Display) -> QueryError {
+        QueryError::ParamValueInvalid(format!("{value} mismatch {:?}", self.type_name))
+    }
+
+    fn value_type_name(&self) -> Result<Cow<'_, TypeName>, QueryError> {
+        value_type_name(self.type_name)
+    }
+
+    fn serialize_integer<V>(self, v: V) -> Result<(), QueryError>
+    where
+        V: Copy + Display,
+        i8: TryFrom<V>,
+        i16: TryFrom<V>,
+        i32: TryFrom<V>,
+        i64: TryFrom<V>,
+        i128: TryFrom<V>,
+        u8: TryFrom<V>,
+        u16: TryFrom<V>,
+        u32: TryFrom<V>,
+        u64: TryFrom<V>,
+        u128: TryFrom<V>,
+    {
+        let is_valid = match self.value_type_name()?.as_ref() {
+            TypeName::UInt8 => u8::try_from(v).is_ok(),
+            TypeName::UInt16 => u16::try_from(v).is_ok(),
+            TypeName::UInt32 | TypeName::DateTime(_) => u32::try_from(v).is_ok(),
+            TypeName::UInt64 => u64::try_from(v).is_ok(),
+            TypeName::UInt256 => u128::try_from(v).is_ok(),
+            TypeName::Int8 => i8::try_from(v).is_ok(),
+            TypeName::Int16 => i16::try_from(v).is_ok(),
+            TypeName::Int32 => i32::try_from(v).is_ok(),
+            TypeName::Int64 | TypeName::DateTime64(_, _) => i64::try_from(v).is_ok(),
+            TypeName::Int128 => i128::try_from(v).is_ok(),
+            TypeName::Int256 => true,
+            TypeName::Float32 | TypeName::Float64 | TypeName::Decimal(_, _) => true,
+            TypeName::Enum8(inner) => i8::try_from(v)
+                .map(|v| inner.values().any(|x| *x == v))
+                .unwrap_or(false),
+            TypeName::Enum16(inner) => i16::try_from(v)
+                .map(|v| inner.values().any(|x| *x == v))
+                .unwrap_or(false),
+            _ => false,
+        };
+        if !is_valid {
+            return Err(self.mismatch(v));
+        }
+
+        write!(self.output, "{v}").map_err(ser::Error::custom)
+    }
+
+    fn serialize_float<V>(
+        self,
+        v: V,
+        is_nan: bool,
+        is_infinite: bool,
+        is_negative: bool,
+    ) -> Result<(), QueryError>
+    where
+        V: Display,
+    {
+        match self.value_type_name()?.as_ref() {
+            TypeName::Float32 | TypeName::Float64 => {}
+            TypeName::Decimal(_, _) if !is_nan && !is_infinite => {}
+            _ => return Err(self.mismatch(v)),
+        }
+
+        if is_nan {
+            self.output.push_str("nan");
+        } else if is_infinite {
+            self.output
+                .push_str(if is_negative { "-inf" } else { "inf" });
+        } else {
+            write!(self.output, "{v}").map_err(ser::Error::custom)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ser::Serializer for ParamSerializer<'a> {
+    type Ok = ();
+    type Error = QueryError;
+
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = ser::Impossible<(), QueryError>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = ser::Impossible<(), QueryError>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), QueryError> {
+        match self.value_type_name()?.as_ref() {
+            TypeName::UInt8
+            | TypeName::UInt16
+            | TypeName::UInt32
+            | TypeName::UInt64
+            | TypeName::UInt256
+            | TypeName::Int8
+            | TypeName::Int16
+            | TypeName::Int32
+            | TypeName::Int64
+            | TypeName::Int128
+            | TypeName::Int256 => {}
+            _ => return Err(self.mismatch(v)),
+        }
+
+        self.output.push(if v { '1' } else { '0' });
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), QueryError> {
+        self.serialize_integer(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), QueryError> {
+        self.serialize_integer(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), QueryError> {
+        self.serialize_integer(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), QueryError> {
+        self.serialize_integer(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), QueryError> {
+        self.serialize_integer(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), QueryError> {
+        self.serialize_integer(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), QueryError> {
+        self.serialize_integer(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), QueryError> {
+        self.serialize_integer(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), QueryError> {
+        self.serialize_integer(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), QueryError> {
+        self.serialize_integer(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), QueryError> {
+        self.serialize_float(v, v.is_nan(), v.is_infinite(), v.is_sign_negative())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), QueryError> {
+        self.serialize_float(v, v.is_nan(), v.is_infinite(), v.is_sign_negative())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), QueryError> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), QueryError> {
+        let is_number = match self.value_type_name()?.as_ref() {
+            TypeName::String | TypeName::Uuid | TypeName::Ipv4 | TypeName::Ipv6 => false,
+            TypeName::Date | TypeName::DateTime(_) | TypeName::DateTime64(_, _) => false,
+            TypeName::FixedString(n) if v.len() <= n.0 => false,
+            TypeName::Enum8(inner) if inner.contains_key(v) => false,
+            TypeName::Enum16(inner) if inner.contains_key(v) => false,
+            TypeName::UInt8
+            | TypeName::UInt16
+            | TypeName::UInt32
+            | TypeName::UInt64
+            | TypeName::UInt256
+            | TypeName::Int8
+            | TypeName::Int16
+            | TypeName::Int32
+            | TypeName::Int64
+            | TypeName::Int128
+            | TypeName::Int256
+            | TypeName::Float32
+            | TypeName::Float64
+            | TypeName::Decimal(_, _)
+                if is_number_literal(v) =>
+            {
+                true
+            }
+            _ => return Err(self.mismatch(format_args!("{v:?}"))),
+        };
+
+        if is_number {
+            self.output.push_str(v);
+        } else if self.is_nested {
+            write_quoted(self.output, v);
+        } else {
+            write_escaped(self.output, v);
+        }
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), QueryError> {
+        match core::str::from_utf8(v) {
+            Ok(v) => self.serialize_str(v),
+            Err(err) => Err(QueryError::ParamValueInvalid(err.to_string())),
+        }
+    }
+
+    fn serialize_none(self) -> Result<(), QueryError> {
+        if !is_nullable(self.type_name) {
+            return Err(self.mismatch("NULL"));
+        }
+
+        self.output
+            .push_str(if self.is_nested { "NULL" } else { "\\N" });
+        Ok(())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), QueryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), QueryError> {
+        self.serialize_none()
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), QueryError> {
+        self.serialize_none()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), QueryError> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<(), QueryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _value: &T,
+    ) -> Result<(), QueryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(self.mismatch(format_args!("{name}::{variant}")))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Compound<'a>, QueryError> {
+        let kind = match self.value_type_name()?.as_ref() {
+            TypeName::Array(type_name) => CompoundKind::Array(type_name.as_ref().to_owned()),
+            TypeName::Tuple(type_names) => CompoundKind::Tuple(type_names.to_owned()),
+            _ => return Err(self.mismatch("Array")),
+        };
+
+        Ok(Compound::new(self.output, kind))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Compound<'a>, QueryError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>, QueryError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, QueryError> {
+        Err(self.mismatch(format_args!("{name}::{variant}")))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Compound<'a>, QueryError> {
+        let kind = match self.value_type_name()?.as_ref() {
+            TypeName::Map(map_key, map_value) => {
+                CompoundKind::Map(map_key.to_owned().into(), map_value.to_owned().into())
+            }
+            _ => return Err(self.mismatch("Map")),
+        };
+
+        Ok(Compound::new(self.output, kind))
+    }
+
+    fn serialize_struct(self, name: &'static str, _len: usize) -> Result<Compound<'a>, QueryError> {
+        let kind = match self.value_type_name()?.as_ref() {
+            TypeName::Tuple(type_names) => CompoundKind::Tuple(type_names.to_owned()),
+            _ => return Err(self.mismatch(name)),
+        };
+
+        Ok(Compound::new(self.output, kind))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, QueryError> {
+        Err(self.mismatch(format_args!("{name}::{variant}")))
+    }
+}
+
+enum CompoundKind {
+    Array(TypeName),
+    Tuple(Vec<TypeName>),
+    Map(TypeName, TypeName),
+}
+
+struct Compound<'a> {
+    output: &'a mut String,
+    kind: CompoundKind,
+    len: usize,
+}
+
+impl<'a> Compound<'a> {
+    fn new(output: &'a mut String, kind: CompoundKind) -> Self {
+        output.push(match kind {
+            CompoundKind::Array(_) => '[',
+            CompoundKind::Tuple(_) => '(',
+            CompoundKind::Map(_, _) => '{',
+        });
+
+        Self {
+            output,
+            kind,
+            len: 0,
+        }
+    }
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), QueryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let type_name = match &self.kind {
+            CompoundKind::Array(type_name) => type_name,
+            CompoundKind::Tuple(type_names) => type_names.get(self.len).ok_or_else(|| {
+                QueryError::ParamValueInvalid(format!(
+                    "too many elements for Tuple of {}",
+                    type_names.len()
+                ))
+            })?,
+            CompoundKind::Map(type_name, _) => type_name,
+        };
+
+        if self.len > 0 {
+            self.output.push(',');
+        }
+        self.len += 1;
+
+        value.serialize(ParamSerializer {
+            output: self.output,
+            type_name,
+            is_nested: true,
+        })
+    }
+
+    fn serialize_map_value<T>(&mut self, value: &T) -> Result<(), QueryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let type_name = match &self.kind {
+            CompoundKind::Map(_, type_name) => type_name,
+            _ => return Err(ser::Error::custom("unexpected map value")),
+        };
+
+        self.output.push(':');
+
+        value.serialize(ParamSerializer {
+            output: self.output,
+            type_name,
+            is_nested: true,
+        })
+    }
+
+    fn end(self) -> Result<(), QueryError> {
+        match &self.kind {
+            CompoundKind::Array(_) => self.output.push(']'),
+            CompoundKind::Tuple(type_names) => {
+                if self.len != type_names.len() {
+                    return Err(QueryError::ParamValueInvalid(format!(
+                        "{} elements mismatch Tuple of {}",
+                        self.len,
+                        type_names.len()
+                    )));
+                }
+                self.output.push(')')
+            }
+            CompoundKind::Map(_, _) => self.output.push('}'),
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+    type Ok = ();
+    type Error = QueryError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), QueryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        Compound::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), QueryError> {
+        Compound::end(self)
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+    type Ok = ();
+    type Error = QueryError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), QueryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        Compound::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), QueryError> {
+        Compound::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+    type Ok = ();
+    type Error = QueryError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), QueryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        Compound::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), QueryError> {
+        Compound::end(self)
+    }
+}
+
+impl ser::SerializeMap for Compound<'_> {
+    type Ok = ();
+    type Error = QueryError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), QueryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        Compound::serialize_element(self, key)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), QueryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        Compound::serialize_map_value(self, value)
+    }
+
+    fn end(self) -> Result<(), QueryError> {
+        Compound::end(self)
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_> {
+    type Ok = ();
+    type Error = QueryError;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<(), QueryError>
+    where
+        T: Serialize + ?Sized,
+    {
+        Compound::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), QueryError> {
+        Compound::end(self)
+    }
+}
+
+//
+//
+//
+fn is_nullable(type_name: &TypeName) -> bool {
+    match type_name {
+        TypeName::Nullable(_) => true,
+        TypeName::LowCardinality(data_type) => is_nullable(&TypeName::from(data_type.to_owned())),
+        _ => false,
+    }
+}
+
+fn value_type_name(type_name: &TypeName) -> Result<Cow<'_, TypeName>, QueryError> {
+    match type_name {
+        TypeName::Nullable(type_name) => TypeName::try_from(type_name.to_owned())
+            .map(Cow::Owned)
+            .map_err(|err| QueryError::ParamValueInvalid(err.to_string())),
+        TypeName::LowCardinality(data_type) => {
+            value_type_name(&TypeName::from(data_type.to_owned()))
+                .map(|x| Cow::Owned(x.into_owned()))
+        }
+        TypeName::Point => Ok(Cow::Owned(TypeName::Tuple(vec![
+            TypeName::Float64,
+            TypeName::Float64,
+        ]))),
+        TypeName::Ring => Ok(Cow::Owned(TypeName::Array(TypeName::Point.into()))),
+        TypeName::Polygon => Ok(Cow::Owned(TypeName::Array(TypeName::Ring.into()))),
+        TypeName::MultiPolygon => Ok(Cow::Owned(TypeName::Array(TypeName::Polygon.into()))),
+        _ => Ok(Cow::Borrowed(type_name)),
+    }
+}
+
+fn is_number_literal(s: &str) -> bool {
+    let s = s.strip_prefix('-').unwrap_or(s);
+    let mut parts = s.splitn(2, '.');
+    let integer_part = parts.next().unwrap_or_default();
+    let fractional_part = parts.next();
+
+    !integer_part.is_empty()
+        && integer_part.chars().all(|c| c.is_ascii_digit())
+        && fractional_part
+            .map(|x| !x.is_empty() && x.chars().all(|c| c.is_ascii_digit()))
+            .unwrap_or(true)
+}
+
+fn write_escaped_char(output: &mut String, c: char) -> bool {
+    match c {
+        '\\' => output.push_str("\\\\"),
+        '\t' => output.push_str("\\t"),
+        '\n' => output.push_str("\\n"),
+        '\r' => output.push_str("\\r"),
+        '\0' => output.push_str("\\0"),
+        '\x08' => output.push_str("\\b"),
+        '\x0c' => output.push_str("\\f"),
+        _ => return false,
+    }
+    true
+}
+
+fn write_escaped(output: &mut String, s: &str) {
+    for c in s.chars() {
+        if !write_escaped_char(output, c) {
+            output.push(c);
+        }
+    }
+}
+
+pub(crate) fn write_quoted(output: &mut String, s: &str) {
+    output.push('\'');
+    for c in s.chars() {
+        if c == '\'' {
+            output.push_str("\\'");
+        } else if !write_escaped_char(output, c) {
+            output.push(c);
+        }
+    }
+    output.push('\'');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    fn ser<T: Serialize + ?Sized>(value: &T, type_name: &str) -> Result<String, QueryError> {
+        to_string(value, &type_name.parse().unwrap())
+    }
+
+    #[test]
+    fn test_int_uint() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(ser(&1_u8, "UInt8")?, "1");
+        assert_eq!(ser(&-1_i64, "Int8")?, "-1");
+        assert_eq!(ser(&u128::MAX, "UInt256")?, u128::MAX.to_string());
+        assert_eq!(ser(&i128::MIN, "Int256")?, i128::MIN.to_string());
+        assert_eq!(
+            ser("123456789012345678901234567890", "UInt256")?,
+            "123456789012345678901234567890"
+        );
+        assert_eq!(ser(&true, "UInt8")?, "1");
+
+        assert!(ser(&256_u32, "UInt8").is_err());
+        assert!(ser(&-1_i32, "UInt64").is_err());
+        assert!(ser("1; DROP TABLE t", "UInt64").is_err());
+        assert!(ser(&1, "String").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_float_and_decimal() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(ser(&1.5_f64, "Float64")?, "1.5");
+        assert_eq!(ser(&f64::NAN, "Float64")?, "nan");
+        assert_eq!(ser(&f32::NEG_INFINITY, "Float32")?, "-inf");
+        assert_eq!(ser(&1, "Float32")?, "1");
+        assert_eq!(ser("-12.345", "Decimal(9, 3)")?, "-12.345");
+
+        assert!(ser(&f64::NAN, "Decimal(9, 3)").is_err());
+        assert!(ser(&1.5_f64, "Int32").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_string() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(ser("a'b\tc\\d\ne", "String")?, "a'b\\tc\\\\d\\ne");
+        assert_eq!(ser("ab", "FixedString(2)")?, "ab");
+        assert_eq!(ser(&'a', "LowCardinality(String)")?, "a");
+        assert_eq!(
+            ser("61f0c404-5cb3-11e7-907b-a6006ad3dba0", "UUID")?,
+            "61f0c404-5cb3-11e7-907b-a6006ad3dba0"
+        );
+        assert_eq!(ser("2021-01-01", "Date")?, "2021-01-01");
+        assert_eq!(
+            ser("2021-01-01 00:00:00", "DateTime('UTC')")?,
+            "2021-01-01 00:00:00"
+        );
+        assert_eq!(ser(&1609459200, "DateTime")?, "1609459200");
+        assert_eq!(ser("127.0.0.1", "IPv4")?, "127.0.0.1");
+        assert_eq!(ser("a", "Enum8('a' = 1, 'b' = 2)")?, "a");
+        assert_eq!(ser(&2, "Enum8('a' = 1, 'b' = 2)")?, "2");
+
+        assert!(ser("abc", "FixedString(2)").is_err());
+        assert!(ser("c", "Enum8('a' = 1, 'b' = 2)").is_err());
+        assert!(ser(&3, "Enum16('a' = 1, 'b' = 2)").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_nullable() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(ser(&Option::<u8>::None, "Nullable(UInt8)")?, "\\N");
+        assert_eq!(ser(&Some(1_u8), "Nullable(UInt8)")?, "1");
+        assert_eq!(ser(&1_u8, "Nullable(UInt8)")?, "1");
+        assert_eq!(
+            ser(&Option::<&str>::None, "LowCardinality(Nullable(String))")?,
+            "\\N"
+        );
+        assert_eq!(
+            ser(&vec![Some("a"), None], "Array(Nullable(String))")?,
+            "['a',NULL]"
+        );
+
+        assert!(ser(&Option::<u8>::None, "UInt8").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_array_tuple_map() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(ser(&vec![1, 2], "Array(UInt8)")?, "[1,2]");
+        assert_eq!(ser(&Vec::<u8>::new(), "Array(UInt8)")?, "[]");
+        assert_eq!(ser(&["a'", "b"], "Array(String)")?, "['a\\'','b']");
+        assert_eq!(
+            ser(&vec![vec!["a"], vec![]], "Array(Array(String))")?,
+            "[['a'],[]]"
+        );
+        assert_eq!(ser(&(1, "a"), "Tuple(UInt8, String)")?, "(1,'a')");
+        assert_eq!(
+            ser(&(1, None::<&str>), "Tuple(UInt8, Nullable(String))")?,
+            "(1,NULL)"
+        );
+        assert_eq!(ser(&(1.0, 2.5), "Point")?, "(1,2.5)");
+        assert_eq!(
+            ser(
+                &vec![("1", "Ready"), ("2", "Steady")]
+                    .into_iter()
+                    .collect::<BTreeMap<_, _>>(),
+                "Map(String, String)"
+            )?,
+            "{'1':'Ready','2':'Steady'}"
+        );
+        assert_eq!(
+            ser(
+                &vec![(1, vec!["a"])].into_iter().collect::<BTreeMap<_, _>>(),
+                "Map(UInt8, Array(String))"
+            )?,
+            "{1:['a']}"
+        );
+
+        assert!(ser(&(1, "a", 2), "Tuple(UInt8, String)").is_err());
+        assert!(ser(&(1,), "Tuple(UInt8, String)").is_err());
+        assert!(ser(&vec![256], "Array(UInt8)").is_err());
+        assert!(ser(&vec![1], "String").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+use core::time::Duration;
+
+use crate::client::Settings;
+
+// https://clickhouse.com/docs/en/operations/settings/settings
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QuerySettings {
+    inner: Vec<(String, String)>,
+}
+
+impl QuerySettings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.inner
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.inner.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// For settings without a typed setter. The key is not checked.
+    pub fn set_raw(&mut self, key: impl Into<String>, val: impl ToString) -> &mut Self {
+        let key = key.into();
+        let val = val.to_string();
+        match self.inner.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => *v = val,
+            None => self.inner.push((key, val)),
+        }
+        self
+    }
+    fn set_bool(&mut self, key: &str, val: bool) -> &mut Self {
+        self.set_raw(key, u8::from(val))
+    }
+
+    //
+    //
+    //
+    /// Fractional seconds are kept, as 0 means no limit.
+    pub fn set_max_execution_time(&mut self, val: Duration) -> &mut Self {
+        self.set_raw("max_execution_time", val.as_secs_f64())
+    }
+    pub fn set_max_memory_usage(&mut self, val: u64) -> &mut Self {
+        self.set_raw("max_memory_usage", val)
+    }
+    pub fn set_max_threads(&mut self, val: u64) -> &mut Self {
+        self.set_raw("max_threads", val)
+    }
+    pub fn set_max_block_size(&mut self, val: u64) -> &mut Self {
+        self.set_raw("max_block_size", val)
+    }
+    pub fn set_max_result_rows(&mut self, val: u64) -> &mut Self {
+        self.set_raw("max_result_rows", val)
+    }
+    pub fn set_max_result_bytes(&mut self, val: u64) -> &mut Self {
+        self.set_raw("max_result_bytes", val)
+    }
+    pub fn set_readonly(&mut self, val: Readonly) -> &mut Self {
+        self.set_raw("readonly", val as u8)
+    }
+    pub fn set_send_progress_in_http_headers(&mut self, val: bool) -> &mut Self {
+        self.set_bool("send_progress_in_http_headers", val)
+    }
+    pub fn set_http_headers_progress_interval(&mut self, val: Duration) -> &mut Self {
+        self.set_raw("http_headers_progress_interval_ms", val.as_millis())
+    }
+
+    //
+    //
+    //
+    pub fn set_output_format_json_quote_64bit_integers(&mut self, val: bool) -> &mut Self {
+        self.set_bool("output_format_json_quote_64bit_integers", val)
+    }
+    pub fn set_output_format_json_quote_denormals(&mut self, val: bool) -> &mut Self {
+        self.set_bool("output_format_json_quote_denormals", val)
+    }
+    pub fn set_output_format_json_named_tuples_as_objects(&mut self, val: bool) -> &mut Self {
+        self.set_bool("output_format_json_named_tuples_as_objects", val)
+    }
+    pub fn set_output_format_tsv_crlf_end_of_line(&mut self, val: bool) -> &mut Self {
+        self.set_bool("output_format_tsv_crlf_end_of_line", val)
+    }
+    pub fn set_format_csv_delimiter(&mut self, val: char) -> &mut Self {
+        self.set_raw("format_csv_delimiter", val)
+    }
+    pub fn set_format_csv_null_representation(&mut self, val: impl Into<String>) -> &mut Self {
+        self.set_raw("format_csv_null_representation", val.into())
+    }
+
+    //
+    //
+    //
+    pub fn set_input_format_skip_unknown_fields(&mut self, val: bool) -> &mut Self {
+        self.set_bool("input_format_skip_unknown_fields", val)
+    }
+    pub fn set_input_format_null_as_default(&mut self, val: bool) -> &mut Self {
+        self.set_bool("input_format_null_as_default", val)
+    }
+    pub fn set_input_format_allow_errors_num(&mut self, val: u64) -> &mut Self {
+        self.set_raw("input_format_allow_errors_num", val)
+    }
+    pub fn set_input_format_allow_errors_ratio(&mut self, val: f64) -> &mut Self {
+        self.set_raw("input_format_allow_errors_ratio", val)
+    }
+    pub fn set_input_format_defaults_for_omitted_fields(&mut self, val: bool) -> &mut Self {
+        self.set_bool("input_format_defaults_for_omitted_fields", val)
+    }
+
+    //
+    //
+    //
+    pub fn set_insert_quorum(&mut self, val: u64) -> &mut Self {
+        self.set_raw("insert_quorum", val)
+    }
+    pub fn set_insert_quorum_timeout(&mut self, val: Duration) -> &mut Self {
+        self.set_raw("insert_quorum_timeout", val.as_millis())
+    }
+    pub fn set_insert_quorum_parallel(&mut self, val: bool) -> &mut Self {
+        self.set_bool("insert_quorum_parallel", val)
+    }
+    pub fn set_insert_deduplicate(&mut self, val: bool) -> &mut Self {
+        self.set_bool("insert_deduplicate", val)
+    }
+}
+
+impl<'a> From<&'a QuerySettings> for Settings<'a> {
+    fn from(settings: &'a QuerySettings) -> Self {
+        settings.iter().collect()
+    }
+}
+impl<'a> From<&'a QuerySettings> for Option<Settings<'a>> {
+    fn from(settings: &'a QuerySettings) -> Self {
+        Some(settings.into())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Readonly {
+    /// All queries are allowed.
+    Off = 0,
+    /// Only read queries are allowed.
+    ReadOnly = 1,
+    /// Read queries and changing settings are allowed.
+    ReadOnlyAllowSettings = 2,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simple() {
+        let mut settings = QuerySettings::new();
+        assert!(settings.is_empty());
+
+        settings
+            .set_max_execution_time(Duration::from_secs(30))
+            .set_max_threads(4)
+            .set_readonly(Readonly::ReadOnlyAllowSettings)
+            .set_output_format_json_quote_64bit_integers(false)
+            .set_input_format_allow_errors_ratio(0.1)
+            .set_insert_quorum_timeout(Duration::from_secs(2))
+            .set_raw("max_threads", 8)
+            .set_raw("join_use_nulls", "1");
+
+        assert_eq!(settings.get("max_threads"), Some("8"));
+        assert_eq!(settings.get("max_memory_usage"), None);
+        assert_eq!(
+            Settings::from(&settings),
+            vec![
+                ("max_execution_time", "30"),
+                ("max_threads", "8"),
+                ("readonly", "2"),
+                ("output_format_json_quote_64bit_integers", "0"),
+                ("input_format_allow_errors_ratio", "0.1"),
+                ("insert_quorum_timeout", "2000"),
+                ("join_use_nulls", "1"),
+            ]
+        );
+
+        settings.set_max_execution_time(Duration::from_millis(500));
+        assert_eq!(settings.get("max_execution_time"), Some("0.5"));
+        settings.set_max_execution_time(Duration::from_millis(1500));
+        assert_eq!(settings.get("max_execution_time"), Some("1.5"));
+    }
+}
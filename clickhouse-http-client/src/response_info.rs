@@ -0,0 +1,135 @@
+use clickhouse_format::progress::JsonEachRowProgress;
+use http::header::HeaderMap;
+use serde::Deserialize;
+use serde_aux::field_attributes::deserialize_option_number_from_string;
+
+use crate::{
+    async_insert::AsyncInsertMode,
+    client_config::{PROGRESS_KEY_HEADER, QUERY_ID_KEY_HEADER, SUMMARY_KEY_HEADER},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct QueryResponseInfo {
+    pub query_id: Option<String>,
+    pub summary: Option<QuerySummary>,
+    /// Only set by the async insert methods, see `AsyncInsertOptions::acknowledged_mode`.
+    pub async_insert_mode: Option<AsyncInsertMode>,
+}
+impl QueryResponseInfo {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let query_id = headers
+            .get(QUERY_ID_KEY_HEADER)
+            .and_then(|x| x.to_str().ok())
+            .map(ToOwned::to_owned);
+
+        let summary = headers
+            .get(SUMMARY_KEY_HEADER)
+            .and_then(|x| serde_json::from_slice(x.as_bytes()).ok());
+
+        Self {
+            query_id,
+            summary,
+            async_insert_mode: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct QuerySummary {
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub read_rows: Option<usize>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub read_bytes: Option<usize>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub written_rows: Option<usize>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub written_bytes: Option<usize>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub total_rows_to_read: Option<usize>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub result_rows: Option<usize>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub result_bytes: Option<usize>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub elapsed_ns: Option<usize>,
+}
+
+/// Sent with `send_progress_in_http_headers=1`, every `http_headers_progress_interval_ms`.
+pub type Progress = JsonEachRowProgress;
+
+pub(crate) fn get_progresses(headers: &HeaderMap) -> Vec<Progress> {
+    headers
+        .get_all(PROGRESS_KEY_HEADER)
+        .iter()
+        .filter_map(|x| serde_json::from_slice(x.as_bytes()).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simple() {
+        let mut headers = HeaderMap::new();
+        headers.insert(QUERY_ID_KEY_HEADER, "foo".parse().unwrap());
+        headers.insert(
+            SUMMARY_KEY_HEADER,
+            r#"{"read_rows":"0","read_bytes":"0","written_rows":"2","written_bytes":"16","total_rows_to_read":"0","result_rows":"2","result_bytes":"16","elapsed_ns":"1157417"}"#
+                .parse()
+                .unwrap(),
+        );
+
+        let info = QueryResponseInfo::from_headers(&headers);
+        assert_eq!(info.query_id, Some("foo".to_owned()));
+        let summary = info.summary.unwrap();
+        assert_eq!(summary.written_rows, Some(2));
+        assert_eq!(summary.result_rows, Some(2));
+        assert_eq!(summary.elapsed_ns, Some(1157417));
+
+        // Older servers only send a subset of the fields.
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            SUMMARY_KEY_HEADER,
+            r#"{"read_rows":"1","read_bytes":"1","written_rows":"0","written_bytes":"0","total_rows_to_read":"1"}"#
+                .parse()
+                .unwrap(),
+        );
+        let info = QueryResponseInfo::from_headers(&headers);
+        assert_eq!(info.query_id, None);
+        let summary = info.summary.unwrap();
+        assert_eq!(summary.read_rows, Some(1));
+        assert_eq!(summary.result_rows, None);
+
+        let info = QueryResponseInfo::from_headers(&HeaderMap::new());
+        assert!(info.query_id.is_none());
+        assert!(info.summary.is_none());
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            PROGRESS_KEY_HEADER,
+            r#"{"read_rows":"65409","read_bytes":"523272","written_rows":"0","written_bytes":"0","total_rows_to_read":"1000000","elapsed_ns":"1120443"}"#
+                .parse()
+                .unwrap(),
+        );
+        headers.append(
+            PROGRESS_KEY_HEADER,
+            r#"{"read_rows":"1000000","read_bytes":"8000000","written_rows":"0","written_bytes":"0","total_rows_to_read":"1000000"}"#
+                .parse()
+                .unwrap(),
+        );
+
+        let progresses = get_progresses(&headers);
+        assert_eq!(progresses.len(), 2);
+        assert_eq!(progresses[0].read_rows, Some(65409));
+        assert_eq!(progresses[0].total_rows_to_read, Some(1000000));
+        assert_eq!(progresses[0].elapsed_ns, Some(1120443));
+        assert_eq!(progresses[1].read_rows, Some(1000000));
+        assert_eq!(progresses[1].elapsed_ns, None);
+
+        assert!(get_progresses(&HeaderMap::new()).is_empty());
+    }
+}
@@ -0,0 +1,271 @@
+use core::{
+    future::Future,
+    hash::{BuildHasher as _, Hasher as _},
+    time::Duration,
+};
+use std::collections::hash_map::RandomState;
+
+use futures_timer::Delay;
+
+use crate::error::{
+    ClientExecuteError, ClientInsertWithFormatError, ClientSelectWithFormatError, Error,
+    RETRYABLE_CODES,
+};
+
+const MAX_ATTEMPTS_DEFAULT: usize = 3;
+const INITIAL_BACKOFF_DEFAULT: Duration = Duration::from_millis(100);
+const MAX_BACKOFF_DEFAULT: Duration = Duration::from_secs(10);
+
+/// Selects and pings are retried, and inserts when they send an `insert_deduplication_token`.
+/// `execute` is only retried with `set_retry_execute`, as its statement may not be idempotent,
+/// e.g. `INSERT INTO ... SELECT` or `ALTER TABLE ... DELETE`.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    is_jitter: bool,
+    is_retry_on_connection_error: bool,
+    is_retry_on_server_error: bool,
+    retryable_codes: Vec<i32>,
+    is_insert_deduplication: bool,
+    is_retry_execute: bool,
+}
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: MAX_ATTEMPTS_DEFAULT,
+            initial_backoff: INITIAL_BACKOFF_DEFAULT,
+            max_backoff: MAX_BACKOFF_DEFAULT,
+            is_jitter: true,
+            is_retry_on_connection_error: true,
+            is_retry_on_server_error: true,
+            retryable_codes: RETRYABLE_CODES.to_vec(),
+            is_insert_deduplication: true,
+            is_retry_execute: false,
+        }
+    }
+}
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_max_attempts(&mut self, val: usize) -> &mut Self {
+        self.max_attempts = val.max(1);
+        self
+    }
+    pub fn set_initial_backoff(&mut self, val: Duration) -> &mut Self {
+        self.initial_backoff = val;
+        self
+    }
+    pub fn set_max_backoff(&mut self, val: Duration) -> &mut Self {
+        self.max_backoff = val;
+        self
+    }
+    pub fn set_jitter(&mut self, val: bool) -> &mut Self {
+        self.is_jitter = val;
+        self
+    }
+    pub fn set_retry_on_connection_error(&mut self, val: bool) -> &mut Self {
+        self.is_retry_on_connection_error = val;
+        self
+    }
+    /// 5xx responses without a ClickHouse exception, e.g. from a proxy.
+    pub fn set_retry_on_server_error(&mut self, val: bool) -> &mut Self {
+        self.is_retry_on_server_error = val;
+        self
+    }
+    pub fn set_retryable_codes(&mut self, val: impl IntoIterator<Item = i32>) -> &mut Self {
+        self.retryable_codes = val.into_iter().collect();
+        self
+    }
+    /// Send the same `insert_deduplication_token` on every attempt of an insert.
+    pub fn set_insert_deduplication(&mut self, val: bool) -> &mut Self {
+        self.is_insert_deduplication = val;
+        self
+    }
+    pub fn set_retry_execute(&mut self, val: bool) -> &mut Self {
+        self.is_retry_execute = val;
+        self
+    }
+
+    pub fn is_retryable(&self, err: &Error) -> bool {
+        let status = match err {
+            Error::TransportError(err) => {
+                return self.is_retry_on_connection_error && (err.is_network() || err.is_timeout());
+            }
+            Error::ServerException(exception) => {
+                return self.retryable_codes.contains(&exception.code);
+            }
+            Error::ClientExecuteError(ClientExecuteError::StatusCodeMismatch(status)) => status,
+            Error::ClientInsertWithFormatError(
+                ClientInsertWithFormatError::StatusCodeMismatch(status),
+            ) => status,
+            Error::ClientSelectWithFormatError(
+                ClientSelectWithFormatError::StatusCodeMismatch(status),
+            ) => status,
+            _ => return false,
+        };
+
+        self.is_retry_on_server_error && status.is_server_error()
+    }
+
+    pub fn backoff(&self, attempt: usize) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2_u32.saturating_pow(attempt.min(u32::MAX as usize) as u32))
+            .min(self.max_backoff);
+
+        if self.is_jitter {
+            // Equal jitter, between half and all of the backoff.
+            backoff / 2 + backoff.mul_f64(random_fraction() / 2.0)
+        } else {
+            backoff
+        }
+    }
+
+    pub(crate) fn is_insert_deduplication(&self) -> bool {
+        self.is_insert_deduplication
+    }
+    pub(crate) fn is_retry_execute(&self) -> bool {
+        self.is_retry_execute
+    }
+
+    pub(crate) async fn retry<T, F, Fut>(&self, mut f: F) -> Result<T, Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match f().await {
+                Err(err) if attempt + 1 < self.max_attempts && self.is_retryable(&err) => {
+                    Delay::new(self.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                ret => return ret,
+            }
+        }
+    }
+}
+
+fn random_fraction() -> f64 {
+    // RandomState is seeded randomly per instance.
+    let n = RandomState::new().build_hasher().finish();
+    (n >> 11) as f64 / (1_u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use http::StatusCode;
+
+    use crate::error::{ServerException, TIMEOUT_EXCEEDED, TOO_MANY_SIMULTANEOUS_QUERIES};
+
+    fn server_exception(code: i32) -> Error {
+        ServerException {
+            code,
+            name: None,
+            message: "".to_owned(),
+            query_id: None,
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        let mut policy = RetryPolicy::default();
+        assert!(policy.is_retryable(&server_exception(TOO_MANY_SIMULTANEOUS_QUERIES)));
+        assert!(!policy.is_retryable(&server_exception(60)));
+        assert!(!policy.is_retryable(&server_exception(TIMEOUT_EXCEEDED)));
+        assert!(
+            policy.is_retryable(
+                &ClientExecuteError::StatusCodeMismatch(StatusCode::BAD_GATEWAY).into()
+            )
+        );
+        assert!(
+            !policy.is_retryable(
+                &ClientExecuteError::StatusCodeMismatch(StatusCode::NOT_FOUND).into()
+            )
+        );
+        assert!(
+            !policy
+                .is_retryable(&ClientInsertWithFormatError::FormatSerError("".to_owned()).into())
+        );
+
+        policy
+            .set_retryable_codes(vec![60])
+            .set_retry_on_server_error(false);
+        assert!(!policy.is_retryable(&server_exception(TOO_MANY_SIMULTANEOUS_QUERIES)));
+        assert!(policy.is_retryable(&server_exception(60)));
+        assert!(!policy.is_retryable(
+            &ClientSelectWithFormatError::StatusCodeMismatch(StatusCode::BAD_GATEWAY).into()
+        ));
+    }
+
+    #[test]
+    fn test_backoff() {
+        let mut policy = RetryPolicy::default();
+        policy
+            .set_initial_backoff(Duration::from_millis(100))
+            .set_max_backoff(Duration::from_millis(500))
+            .set_jitter(false);
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff(3), Duration::from_millis(500));
+        assert_eq!(policy.backoff(usize::MAX), Duration::from_millis(500));
+
+        policy.set_jitter(true);
+        for attempt in 0..5 {
+            let backoff = policy.backoff(attempt);
+            assert!(backoff >= Duration::from_millis(50));
+            assert!(backoff <= Duration::from_millis(500));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry() {
+        let mut policy = RetryPolicy::default();
+        policy
+            .set_max_attempts(3)
+            .set_initial_backoff(Duration::from_millis(1));
+
+        let attempts = AtomicUsize::new(0);
+        let ret = policy
+            .retry(|| async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(server_exception(TOO_MANY_SIMULTANEOUS_QUERIES))
+            })
+            .await;
+        assert!(ret.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        let attempts = AtomicUsize::new(0);
+        let ret = policy
+            .retry(|| async {
+                if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Err(server_exception(TOO_MANY_SIMULTANEOUS_QUERIES))
+                } else {
+                    Ok(1)
+                }
+            })
+            .await;
+        assert_eq!(ret.unwrap(), 1);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+        let attempts = AtomicUsize::new(0);
+        let ret = policy
+            .retry(|| async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(server_exception(60))
+            })
+            .await;
+        assert!(ret.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,494 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use clickhouse_format::{
+    exception::{
+        ends_with_exception_frame, is_exception_marker_line, is_exception_start_line,
+        is_exception_tag_line, split_exception,
+    },
+    output::StreamOutput,
+};
+use futures_util::{
+    io::{AsyncBufRead, BufReader},
+    lock::OwnedMutexGuard,
+    ready,
+    stream::Stream,
+};
+use http::HeaderMap;
+
+use crate::{
+    error::{ClientSelectWithFormatError, Error, exception_in_body, get_exception_tag},
+    response_info::QueryResponseInfo,
+    transport::Body,
+};
+
+// At most this much of the body is read for a trailing exception, in case a row was taken for
+// its start.
+const EXCEPTION_BUF_MAX_LEN: usize = 16 * 1024;
+
+pub struct SelectStream<O: StreamOutput> {
+    body: BufReader<Body>,
+    output: O,
+    line_buf: Vec<u8>,
+    is_finished: bool,
+    guard: Option<OwnedMutexGuard<()>>,
+    headers: HeaderMap,
+    // A marker line, until the next line tells if it starts an exception or is a row.
+    marker_line: Option<Vec<u8>>,
+    // A plain text exception line, until the end of the body tells it is the exception.
+    exception_line: Option<Vec<u8>>,
+    // The second row, when a held back marker line and the line after it are both rows.
+    next_row: Option<O::Row>,
+    // Everything from the start of a trailing exception.
+    exception_buf: Option<Vec<u8>>,
+}
+impl<O: StreamOutput> Unpin for SelectStream<O> {}
+
+impl<O> SelectStream<O>
+where
+    O: StreamOutput,
+{
+    pub(crate) fn new(body: Body, output: O, headers: HeaderMap) -> Self {
+        Self {
+            body: BufReader::new(body),
+            output,
+            line_buf: Vec::with_capacity(4096),
+            is_finished: false,
+            guard: None,
+            headers,
+            marker_line: None,
+            exception_line: None,
+            next_row: None,
+            exception_buf: None,
+        }
+    }
+
+    pub(crate) fn with_guard(mut self, guard: OwnedMutexGuard<()>) -> Self {
+        self.guard = Some(guard);
+        self
+    }
+
+    pub fn info(&self) -> Option<O::Info> {
+        self.output.info()
+    }
+
+    /// The query id and summary of the response head.
+    pub fn response_info(&self) -> QueryResponseInfo {
+        QueryResponseInfo::from_headers(&self.headers)
+    }
+
+    fn deserialize_line(&mut self) -> Result<Option<O::Row>, Error> {
+        let tag = get_exception_tag(&self.headers);
+
+        if let Some(marker_line) = self.marker_line.take() {
+            if is_exception_tag_line(&self.line_buf[..], tag) {
+                let mut exception_buf = marker_line;
+                exception_buf.push(b'\n');
+                exception_buf.extend_from_slice(&self.line_buf[..]);
+                exception_buf.push(b'\n');
+                self.line_buf.clear();
+                self.exception_buf = Some(exception_buf);
+                return Ok(None);
+            }
+
+            let row = deserialize_row(&mut self.output, &marker_line[..])?;
+            return self.with_next_row(row);
+        }
+
+        if let Some(exception_line) = self.exception_line.take() {
+            match deserialize_row(&mut self.output, &exception_line[..]) {
+                Ok(row) => return self.with_next_row(row),
+                // Not a row either, so the exception goes on over more lines.
+                Err(_) => {
+                    let mut exception_buf = exception_line;
+                    exception_buf.push(b'\n');
+                    exception_buf.extend_from_slice(&self.line_buf[..]);
+                    exception_buf.push(b'\n');
+                    exception_buf.truncate(EXCEPTION_BUF_MAX_LEN);
+                    self.line_buf.clear();
+                    self.exception_buf = Some(exception_buf);
+                    return Ok(None);
+                }
+            }
+        }
+
+        if tag.is_some() && is_exception_marker_line(&self.line_buf[..]) {
+            self.marker_line = Some(self.line_buf.to_owned());
+            self.line_buf.clear();
+            return Ok(None);
+        }
+        if is_exception_start_line(&self.line_buf[..], tag) {
+            self.exception_line = Some(core::mem::take(&mut self.line_buf));
+            return Ok(None);
+        }
+
+        let ret = deserialize_row(&mut self.output, &self.line_buf[..]);
+        self.line_buf.clear();
+        ret
+    }
+
+    // Deserializes the current line after the row of a held back line.
+    fn with_next_row(&mut self, row: Option<O::Row>) -> Result<Option<O::Row>, Error> {
+        match (row, self.deserialize_line()?) {
+            (Some(row), Some(next_row)) => {
+                self.next_row = Some(next_row);
+                Ok(Some(row))
+            }
+            (row, next_row) => Ok(row.or(next_row)),
+        }
+    }
+
+    fn take_exception(&mut self) -> Error {
+        self.is_finished = true;
+        let exception_buf = self.exception_buf.take().unwrap_or_default();
+        let tag = get_exception_tag(&self.headers);
+        let message = split_exception(&exception_buf, tag)
+            .1
+            .unwrap_or_else(|| String::from_utf8_lossy(&exception_buf).trim().to_owned());
+        exception_in_body(&self.headers, message)
+    }
+}
+
+fn deserialize_row<O: StreamOutput>(output: &mut O, line: &[u8]) -> Result<Option<O::Row>, Error> {
+    output
+        .deserialize_line(line)
+        .map_err(|err| ClientSelectWithFormatError::FormatDeError(err.to_string()).into())
+}
+
+impl<O> Stream for SelectStream<O>
+where
+    O: StreamOutput,
+{
+    type Item = Result<O::Row, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(row) = this.next_row.take() {
+                return Poll::Ready(Some(Ok(row)));
+            }
+            // The exception ends at its closing frame, or at the end of the body.
+            if let Some(exception_buf) = this.exception_buf.as_ref() {
+                let tag = get_exception_tag(&this.headers);
+                if this.is_finished
+                    || exception_buf.len() >= EXCEPTION_BUF_MAX_LEN
+                    || tag.is_some_and(|tag| ends_with_exception_frame(exception_buf, tag))
+                {
+                    return Poll::Ready(Some(Err(this.take_exception())));
+                }
+            }
+            if this.is_finished {
+                return Poll::Ready(None);
+            }
+
+            let buf = match ready!(Pin::new(&mut this.body).poll_fill_buf(cx)) {
+                Ok(buf) => buf,
+                Err(err) => {
+                    this.is_finished = true;
+                    return Poll::Ready(Some(Err(err.into())));
+                }
+            };
+
+            if let Some(exception_buf) = this.exception_buf.as_mut() {
+                if buf.is_empty() {
+                    return Poll::Ready(Some(Err(this.take_exception())));
+                }
+
+                let n = buf.len().min(EXCEPTION_BUF_MAX_LEN - exception_buf.len());
+                exception_buf.extend_from_slice(&buf[..n]);
+                Pin::new(&mut this.body).consume(n);
+                continue;
+            }
+
+            let ret = if buf.is_empty() {
+                this.is_finished = true;
+                let ret = if this.line_buf.is_empty() {
+                    Ok(None)
+                } else {
+                    this.deserialize_line()
+                };
+                // Nothing follows a held back plain text exception line, so it is the exception.
+                if let Some(mut exception_line) = this.exception_line.take() {
+                    exception_line.push(b'\n');
+                    this.exception_buf = Some(exception_line);
+                }
+                // Nothing follows a held back marker line, so it is a row.
+                match (ret, this.marker_line.take()) {
+                    (Ok(row), Some(marker_line)) => {
+                        deserialize_row(&mut this.output, &marker_line[..]).map(|marker_row| {
+                            match row {
+                                Some(row) => {
+                                    this.next_row = marker_row;
+                                    Some(row)
+                                }
+                                None => marker_row,
+                            }
+                        })
+                    }
+                    (ret, _) => ret,
+                }
+            } else if let Some(i) = buf.iter().position(|b| *b == b'\n') {
+                this.line_buf.extend_from_slice(&buf[..i]);
+                Pin::new(&mut this.body).consume(i + 1);
+                // e.g. a line break in a quoted CSV value.
+                if !this.output.is_complete_line(&this.line_buf[..]) {
+                    this.line_buf.push(b'\n');
+                    continue;
+                }
+                this.deserialize_line()
+            } else {
+                let n = buf.len();
+                this.line_buf.extend_from_slice(buf);
+                Pin::new(&mut this.body).consume(n);
+                continue;
+            };
+
+            match ret {
+                Ok(Some(row)) => return Poll::Ready(Some(Ok(row))),
+                Ok(None) => continue,
+                Err(err) => {
+                    this.is_finished = true;
+                    return Poll::Ready(Some(Err(err)));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "with-format-json"))]
+mod tests {
+    use super::*;
+
+    use clickhouse_format::output::JsonCompactEachRowWithNamesAndTypesOutput;
+    use futures_util::TryStreamExt as _;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug)]
+    struct Row {
+        id: u32,
+        name: String,
+    }
+
+    #[tokio::test]
+    async fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        let body =
+            Body::from("[\"id\", \"name\"]\n[\"UInt32\", \"String\"]\n[1, \"a\"]\n[2, \"b\"]");
+
+        let mut stream = SelectStream::new(
+            body,
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            HeaderMap::new(),
+        );
+        assert!(stream.info().is_none());
+
+        let row = stream.try_next().await?.unwrap();
+        assert_eq!(row.id, 1);
+        assert_eq!(row.name, "a");
+        assert_eq!(
+            stream.info().unwrap().get("name"),
+            Some(&"String".to_owned())
+        );
+
+        let row = stream.try_next().await?.unwrap();
+        assert_eq!(row.id, 2);
+        assert!(stream.try_next().await?.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn with_de_error() -> Result<(), Box<dyn std::error::Error>> {
+        let body = Body::from("[\"id\", \"name\"]\n[\"UInt32\", \"String\"]\n[\"x\", \"a\"]\n");
+
+        let mut stream = SelectStream::new(
+            body,
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            HeaderMap::new(),
+        );
+        assert!(matches!(
+            stream.try_next().await,
+            Err(Error::ClientSelectWithFormatError(
+                ClientSelectWithFormatError::FormatDeError(_)
+            ))
+        ));
+        assert!(stream.try_next().await?.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn with_exception() -> Result<(), Box<dyn std::error::Error>> {
+        let body = Body::from(
+            "[\"id\", \"name\"]\n[\"UInt32\", \"String\"]\n[1, \"a\"]\nCode: 395. DB::Exception: Value passed to 'throwIf' function is non-zero\n",
+        );
+        let mut stream = SelectStream::new(
+            body,
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            HeaderMap::new(),
+        );
+        assert_eq!(stream.try_next().await?.unwrap().id, 1);
+        match stream.try_next().await {
+            Err(Error::ServerException(exception)) => {
+                assert_eq!(exception.code, 395);
+                assert_eq!(
+                    exception.message,
+                    "Code: 395. DB::Exception: Value passed to 'throwIf' function is non-zero"
+                );
+            }
+            ret => panic!("{ret:?}"),
+        }
+        assert!(stream.try_next().await?.is_none());
+
+        let mut headers = HeaderMap::new();
+        headers.insert("X-ClickHouse-Exception-Tag", "tag1".parse()?);
+        let body = Body::from(
+            "[\"id\", \"name\"]\n[\"UInt32\", \"String\"]\n[1, \"a\"]\n__exception__\r\ntag1\r\nCode: 395. DB::Exception: foo\nbar\n32 tag1\r\n__exception__\r\n",
+        );
+        let mut stream = SelectStream::new(
+            body,
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            headers,
+        );
+        assert_eq!(stream.try_next().await?.unwrap().id, 1);
+        match stream.try_next().await {
+            Err(Error::ServerException(exception)) => {
+                assert_eq!(exception.message, "Code: 395. DB::Exception: foo\nbar");
+            }
+            ret => panic!("{ret:?}"),
+        }
+
+        // The rest of the body is not buffered without limit.
+        let body = format!(
+            "Code: 395. DB::Exception: foo\n{}",
+            "x".repeat(EXCEPTION_BUF_MAX_LEN * 4)
+        );
+        let mut stream = SelectStream::new(
+            Body::from(body),
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            HeaderMap::new(),
+        );
+        match stream.try_next().await {
+            Err(Error::ServerException(exception)) => {
+                assert_eq!(exception.code, 395);
+                assert!(exception.message.len() <= EXCEPTION_BUF_MAX_LEN);
+            }
+            ret => panic!("{ret:?}"),
+        }
+        assert!(stream.try_next().await?.is_none());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "with-format-tsv")]
+    #[tokio::test]
+    async fn with_exception_marker_as_row() -> Result<(), Box<dyn std::error::Error>> {
+        use clickhouse_format::output::TsvOutput;
+
+        let mut headers = HeaderMap::new();
+        headers.insert("X-ClickHouse-Exception-Tag", "tag1".parse()?);
+
+        let body = Body::from(
+            "a\n__exception__\nb\n__exception__\n__exception__\r\ntag1\r\nCode: 395. DB::Exception: foo\n30 tag1\r\n__exception__\r\n",
+        );
+        let mut stream = SelectStream::new(body, TsvOutput::<(String,)>::new(), headers.clone());
+        for expected in ["a", "__exception__", "b", "__exception__"] {
+            assert_eq!(stream.try_next().await?.unwrap().0, expected);
+        }
+        match stream.try_next().await {
+            Err(Error::ServerException(exception)) => {
+                assert_eq!(exception.message, "Code: 395. DB::Exception: foo");
+            }
+            ret => panic!("{ret:?}"),
+        }
+
+        for (body, expected) in [
+            ("a\n__exception__", vec!["a", "__exception__"]),
+            ("a\n__exception__\n", vec!["a", "__exception__"]),
+            (
+                "__exception__\n__exception__",
+                vec!["__exception__", "__exception__"],
+            ),
+        ] {
+            let stream = SelectStream::new(
+                Body::from(body),
+                TsvOutput::<(String,)>::new(),
+                headers.clone(),
+            );
+            assert_eq!(
+                stream.map_ok(|x| x.0).try_collect::<Vec<_>>().await?,
+                expected
+            );
+        }
+
+        // Without the tag header nothing is framed, and only the last line can be the exception.
+        let stream = SelectStream::new(
+            Body::from("a\nCode: 60. DB::Exception: Table default.t doesn't exist\nb\nc\n"),
+            TsvOutput::<(String,)>::new(),
+            HeaderMap::new(),
+        );
+        assert_eq!(
+            stream.map_ok(|x| x.0).try_collect::<Vec<_>>().await?,
+            vec![
+                "a",
+                "Code: 60. DB::Exception: Table default.t doesn't exist",
+                "b",
+                "c"
+            ]
+        );
+
+        let mut stream = SelectStream::new(
+            Body::from("a\nCode: 60. DB::Exception: foo\nCode: 60. DB::Exception: bar\n"),
+            TsvOutput::<(String,)>::new(),
+            HeaderMap::new(),
+        );
+        assert_eq!(stream.try_next().await?.unwrap().0, "a");
+        assert_eq!(
+            stream.try_next().await?.unwrap().0,
+            "Code: 60. DB::Exception: foo"
+        );
+        match stream.try_next().await {
+            Err(Error::ServerException(exception)) => {
+                assert_eq!(exception.message, "Code: 60. DB::Exception: bar");
+            }
+            ret => panic!("{ret:?}"),
+        }
+        assert!(stream.try_next().await?.is_none());
+
+        let stream = SelectStream::new(
+            Body::from("__exception__\nfoo\n"),
+            TsvOutput::<(String,)>::new(),
+            HeaderMap::new(),
+        );
+        assert_eq!(
+            stream.map_ok(|x| x.0).try_collect::<Vec<_>>().await?,
+            vec!["__exception__", "foo"]
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "with-format-csv")]
+    #[tokio::test]
+    async fn with_line_break_in_csv_value() -> Result<(), Box<dyn std::error::Error>> {
+        use clickhouse_format::output::CsvWithNamesOutput;
+
+        let body = Body::from("\"id\",\"name\"\n1,\"a\nb\"\n2,\"\"\"\n\"\"\"\n3,\"c\"\n");
+        let stream = SelectStream::new(body, CsvWithNamesOutput::<Row>::new(), HeaderMap::new());
+        assert_eq!(
+            stream
+                .map_ok(|x| (x.id, x.name))
+                .try_collect::<Vec<_>>()
+                .await?,
+            vec![
+                (1, "a\nb".to_owned()),
+                (2, "\"\n\"".to_owned()),
+                (3, "c".to_owned())
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+use core::time::Duration;
+use std::sync::Arc;
+
+use clickhouse_format::{
+    format_name::FormatName,
+    input::Input,
+    output::{Output, StreamOutput},
+};
+use futures_util::lock::Mutex;
+
+use crate::{
+    client::{Client, Settings},
+    client_config::{
+        SESSION_CHECK_KEY_URL_PARAMETER, SESSION_ID_KEY_URL_PARAMETER,
+        SESSION_TIMEOUT_KEY_URL_PARAMETER,
+    },
+    error::Error,
+    query_id::generate_query_id,
+    response_info::QueryResponseInfo,
+    select_stream::SelectStream,
+};
+
+// https://clickhouse.com/docs/en/interfaces/http#using-clickhouse-sessions-in-the-http-protocol
+#[derive(Debug, Clone)]
+pub struct Session {
+    client: Client,
+    session_id: String,
+    session_timeout: Option<String>,
+    session_check: bool,
+    lock: Arc<Mutex<()>>,
+}
+
+impl Session {
+    // The session only exists on the server that created it.
+    pub(crate) fn new(mut client: Client) -> Self {
+        client.pin_endpoint();
+
+        Self {
+            client,
+            session_id: generate_query_id(),
+            session_timeout: None,
+            session_check: false,
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    pub fn set_session_id(&mut self, val: impl Into<String>) -> &mut Self {
+        self.session_id = val.into();
+        self
+    }
+    pub fn set_session_timeout(&mut self, val: Duration) -> &mut Self {
+        self.session_timeout = Some(val.as_secs().to_string());
+        self
+    }
+    pub fn set_session_check(&mut self, val: bool) -> &mut Self {
+        self.session_check = val;
+        self
+    }
+
+    fn get_settings<'a>(&'a self, settings: Option<Settings<'a>>) -> Settings<'a> {
+        let mut session_settings = vec![(SESSION_ID_KEY_URL_PARAMETER, self.session_id.as_str())];
+        if let Some(session_timeout) = &self.session_timeout {
+            session_settings.push((SESSION_TIMEOUT_KEY_URL_PARAMETER, session_timeout.as_str()));
+        }
+        if self.session_check {
+            session_settings.push((SESSION_CHECK_KEY_URL_PARAMETER, "1"));
+        }
+
+        session_settings.extend(settings.unwrap_or_default());
+        session_settings
+    }
+
+    //
+    //
+    //
+    pub async fn execute(
+        &self,
+        sql: impl AsRef<str>,
+        settings: impl Into<Option<Settings<'_>>>,
+    ) -> Result<QueryResponseInfo, Error> {
+        let _guard = self.lock.lock().await;
+
+        self.client
+            .execute(sql, self.get_settings(settings.into()))
+            .await
+    }
+
+    pub async fn insert_with_format<I: Input>(
+        &self,
+        sql_prefix: impl AsRef<str>,
+        input: I,
+        settings: impl Into<Option<Settings<'_>>>,
+    ) -> Result<QueryResponseInfo, Error> {
+        let _guard = self.lock.lock().await;
+
+        self.client
+            .insert_with_format(sql_prefix, input, self.get_settings(settings.into()))
+            .await
+    }
+
+    pub async fn insert_with_format_bytes(
+        &self,
+        sql_prefix: impl AsRef<str>,
+        format_name: FormatName,
+        format_bytes: Vec<u8>,
+        settings: impl Into<Option<Settings<'_>>>,
+    ) -> Result<QueryResponseInfo, Error> {
+        let _guard = self.lock.lock().await;
+
+        self.client
+            .insert_with_format_bytes(
+                sql_prefix,
+                format_name,
+                format_bytes,
+                self.get_settings(settings.into()),
+            )
+            .await
+    }
+
+    pub async fn select_with_format<O: Output>(
+        &self,
+        sql: impl AsRef<str>,
+        output: O,
+        settings: impl Into<Option<Settings<'_>>>,
+    ) -> Result<(Vec<O::Row>, O::Info), Error> {
+        let _guard = self.lock.lock().await;
+
+        self.client
+            .select_with_format(sql, output, self.get_settings(settings.into()))
+            .await
+    }
+
+    /// The session stays locked until the returned stream is dropped.
+    pub async fn select_stream_with_format<O: StreamOutput>(
+        &self,
+        sql: impl AsRef<str>,
+        output: O,
+        settings: impl Into<Option<Settings<'_>>>,
+    ) -> Result<SelectStream<O>, Error> {
+        let guard = self.lock.clone().lock_owned().await;
+
+        self.client
+            .select_stream_with_format(sql, output, self.get_settings(settings.into()))
+            .await
+            .map(|stream| stream.with_guard(guard))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_settings() -> Result<(), Box<dyn std::error::Error>> {
+        let client = crate::test_helpers::MockTransport::ok().client();
+
+        let session = client.session();
+        let session_other = client.session();
+        assert_ne!(session.session_id(), session_other.session_id());
+        assert_eq!(
+            session.get_settings(None),
+            vec![("session_id", session.session_id())]
+        );
+
+        let mut session = client.session();
+        session
+            .set_session_id("foo")
+            .set_session_timeout(Duration::from_secs(120))
+            .set_session_check(true);
+        assert_eq!(
+            session.get_settings(Some(vec![("max_threads", "1")])),
+            vec![
+                ("session_id", "foo"),
+                ("session_timeout", "120"),
+                ("session_check", "1"),
+                ("max_threads", "1"),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "with-transport-isahc")]
+    #[tokio::test]
+    async fn test_pin_endpoint() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::{
+            client::ClientBuilder,
+            test_helpers::{response, spawn_server},
+        };
+
+        let servers = [
+            spawn_server(|_| response(&[], b"")),
+            spawn_server(|_| response(&[], b"")),
+        ];
+
+        let mut client_builder = ClientBuilder::new();
+        client_builder.set_urls(servers.iter().map(|x| &x.url))?;
+        let client = client_builder.build()?;
+
+        let session = client.session();
+        for _ in 0..4 {
+            session.execute("SELECT 1", None).await?;
+        }
+        client.execute("SELECT 1", None).await?;
+
+        let counts = servers
+            .iter()
+            .map(|x| x.requests.lock().unwrap().len())
+            .collect::<Vec<_>>();
+        assert_eq!(counts, vec![4, 1]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+// A local HTTP/1.1 server, to test through the real transports instead of a mock,
+// and a mock transport for the tests that must pass without any real transport.
+// Only some feature combinations have tests using them.
+#![allow(dead_code)]
+
+use core::fmt;
+use std::{
+    io::{BufRead as _, BufReader, Read as _, Write as _},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use futures_util::future::BoxFuture;
+use http::{Request, Response};
+
+use crate::{
+    client::{Client, ClientBuilder},
+    transport::{Body, Transport, TransportError, TransportErrorKind},
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct TestRequest {
+    /// The path and the query.
+    pub(crate) target: String,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: Vec<u8>,
+}
+impl TestRequest {
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub(crate) fn query_pairs(&self) -> Vec<(String, String)> {
+        url::form_urlencoded::parse(self.target.split_once('?').unwrap_or_default().1.as_bytes())
+            .into_owned()
+            .collect()
+    }
+}
+
+pub(crate) struct TestServer {
+    pub(crate) url: String,
+    pub(crate) requests: Arc<Mutex<Vec<TestRequest>>>,
+}
+
+/// `handler` returns the raw response, see `response`. Every connection gets its own thread.
+pub(crate) fn spawn_server<F>(handler: F) -> TestServer
+where
+    F: Fn(&TestRequest) -> Vec<u8> + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(vec![]));
+
+    let handler = Arc::new(handler);
+    let requests_cloned = requests.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let handler = handler.clone();
+            let requests = requests_cloned.clone();
+            thread::spawn(move || {
+                let _ = handle(stream, handler.as_ref(), &requests);
+            });
+        }
+    });
+
+    TestServer { url, requests }
+}
+
+type MockHandler = dyn Fn(&TestRequest) -> Result<Response<Body>, TransportError> + Send + Sync;
+
+/// Answers every request with `handler`, the requests are recorded like `TestServer` does.
+#[derive(Clone)]
+pub(crate) struct MockTransport {
+    handler: Arc<MockHandler>,
+    pub(crate) requests: Arc<Mutex<Vec<TestRequest>>>,
+}
+impl fmt::Debug for MockTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockTransport").finish_non_exhaustive()
+    }
+}
+impl MockTransport {
+    pub(crate) fn new<F>(handler: F) -> Self
+    where
+        F: Fn(&TestRequest) -> Result<Response<Body>, TransportError> + Send + Sync + 'static,
+    {
+        Self {
+            handler: Arc::new(handler),
+            requests: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    /// Answers every request with an empty 200.
+    pub(crate) fn ok() -> Self {
+        Self::new(|_| Ok(Response::new(Body::empty())))
+    }
+
+    pub(crate) fn client(&self) -> Client {
+        let mut client_builder = ClientBuilder::new();
+        client_builder.set_transport(self.clone());
+        client_builder.build().unwrap()
+    }
+}
+
+impl Transport for MockTransport {
+    fn send(&self, req: Request<Body>) -> BoxFuture<'_, Result<Response<Body>, TransportError>> {
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+            let body = body
+                .bytes()
+                .await
+                .map_err(|err| TransportError::new(TransportErrorKind::Network, err))?;
+            let req = TestRequest {
+                target: parts
+                    .uri
+                    .path_and_query()
+                    .map(|x| x.as_str().to_owned())
+                    .unwrap_or_default(),
+                headers: parts
+                    .headers
+                    .iter()
+                    .map(|(k, v)| {
+                        (
+                            k.as_str().to_owned(),
+                            v.to_str().unwrap_or_default().to_owned(),
+                        )
+                    })
+                    .collect(),
+                body,
+            };
+            self.requests.lock().unwrap().push(req.clone());
+
+            (self.handler)(&req)
+        })
+    }
+}
+
+pub(crate) fn response(headers: &[(&str, &str)], body: &[u8]) -> Vec<u8> {
+    let mut buf = format!(
+        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n",
+        body.len()
+    );
+    for (k, v) in headers {
+        buf.push_str(&format!("{k}: {v}\r\n"));
+    }
+    buf.push_str("\r\n");
+
+    let mut buf = buf.into_bytes();
+    buf.extend_from_slice(body);
+    buf
+}
+
+fn handle(
+    stream: TcpStream,
+    handler: &(dyn Fn(&TestRequest) -> Vec<u8> + Send + Sync),
+    requests: &Mutex<Vec<TestRequest>>,
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    // The request line, a connection may also be closed without any request.
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(());
+    }
+    let target = line.split(' ').nth(1).unwrap_or_default().to_owned();
+
+    let mut headers = vec![];
+    loop {
+        line.clear();
+        reader.read_line(&mut line)?;
+        let Some((k, v)) = line.trim_end().split_once(':') else {
+            break;
+        };
+        headers.push((k.trim().to_owned(), v.trim().to_owned()));
+    }
+    let mut req = TestRequest {
+        target,
+        headers,
+        body: vec![],
+    };
+
+    if let Some(len) = req.header("Content-Length") {
+        req.body.resize(len.parse().unwrap_or_default(), 0);
+        reader.read_exact(&mut req.body)?;
+    } else if req.header("Transfer-Encoding") == Some("chunked") {
+        loop {
+            line.clear();
+            reader.read_line(&mut line)?;
+            let len = usize::from_str_radix(line.trim(), 16).unwrap_or_default();
+            let mut chunk = vec![0; len + 2];
+            reader.read_exact(&mut chunk)?;
+            if len == 0 {
+                break;
+            }
+            req.body.extend_from_slice(&chunk[..len]);
+        }
+    }
+
+    requests.lock().unwrap().push(req.clone());
+
+    let mut stream = stream;
+    stream.write_all(&handler(&req))?;
+    stream.flush()
+}
@@ -0,0 +1,195 @@
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "with-tls-rustls")]
+use crate::error::Error;
+
+/// TLS settings for the built-in transports.
+///
+/// The files are PEM. isahc reads them through libcurl, hyper and reqwest need `with-tls-rustls`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TlsConfig {
+    root_ca_path: Option<PathBuf>,
+    client_cert_path: Option<(PathBuf, PathBuf)>,
+    is_danger_accept_invalid_certs: bool,
+}
+
+impl TlsConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the default roots.
+    pub fn set_root_ca_path(&mut self, val: impl Into<PathBuf>) -> &mut Self {
+        self.root_ca_path = Some(val.into());
+        self
+    }
+    /// For mTLS, `cert` may hold the whole chain.
+    pub fn set_client_cert_path(
+        &mut self,
+        cert: impl Into<PathBuf>,
+        key: impl Into<PathBuf>,
+    ) -> &mut Self {
+        self.client_cert_path = Some((cert.into(), key.into()));
+        self
+    }
+    /// Skips the server certificate and hostname checks. For development only.
+    pub fn set_danger_accept_invalid_certs(&mut self, val: bool) -> &mut Self {
+        self.is_danger_accept_invalid_certs = val;
+        self
+    }
+
+    pub fn get_root_ca_path(&self) -> Option<&Path> {
+        self.root_ca_path.as_deref()
+    }
+    pub fn get_client_cert_path(&self) -> Option<(&Path, &Path)> {
+        self.client_cert_path
+            .as_ref()
+            .map(|(cert, key)| (cert.as_path(), key.as_path()))
+    }
+    pub fn is_danger_accept_invalid_certs(&self) -> bool {
+        self.is_danger_accept_invalid_certs
+    }
+
+    #[cfg(feature = "with-tls-rustls")]
+    pub fn to_rustls_client_config(&self) -> Result<rustls::ClientConfig, Error> {
+        use std::sync::Arc;
+
+        use rustls::{ClientConfig, RootCertStore, crypto::ring::default_provider};
+        use rustls_pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject as _};
+
+        let invalid = |path: &Path, err: &dyn std::fmt::Display| {
+            Error::TlsConfigInvalid(format!("{}: {err}", path.display()))
+        };
+
+        let mut roots = RootCertStore::empty();
+        match &self.root_ca_path {
+            Some(path) => {
+                for cert in
+                    CertificateDer::pem_file_iter(path).map_err(|err| invalid(path, &err))?
+                {
+                    roots
+                        .add(cert.map_err(|err| invalid(path, &err))?)
+                        .map_err(|err| invalid(path, &err))?;
+                }
+            }
+            None => roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+        }
+
+        let builder = ClientConfig::builder_with_provider(Arc::new(default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|err| Error::TlsConfigInvalid(err.to_string()))?;
+        let builder = if self.is_danger_accept_invalid_certs {
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(danger::NoVerifier))
+        } else {
+            builder.with_root_certificates(roots)
+        };
+
+        match &self.client_cert_path {
+            Some((cert_path, key_path)) => {
+                let certs = CertificateDer::pem_file_iter(cert_path)
+                    .map_err(|err| invalid(cert_path, &err))?
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|err| invalid(cert_path, &err))?;
+                let key = PrivateKeyDer::from_pem_file(key_path)
+                    .map_err(|err| invalid(key_path, &err))?;
+
+                builder
+                    .with_client_auth_cert(certs, key)
+                    .map_err(|err| Error::TlsConfigInvalid(err.to_string()))
+            }
+            None => Ok(builder.with_no_client_auth()),
+        }
+    }
+}
+
+#[cfg(feature = "with-tls-rustls")]
+mod danger {
+    use rustls::{
+        DigitallySignedStruct, Error, SignatureScheme,
+        client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+        crypto::ring::default_provider,
+    };
+    use rustls_pki_types::{CertificateDer, ServerName, UnixTime};
+
+    #[derive(Debug)]
+    pub(super) struct NoVerifier;
+
+    impl ServerCertVerifier for NoVerifier {
+        fn verify_server_cert(
+            &self,
+            _end_entity: &CertificateDer<'_>,
+            _intermediates: &[CertificateDer<'_>],
+            _server_name: &ServerName<'_>,
+            _ocsp_response: &[u8],
+            _now: UnixTime,
+        ) -> Result<ServerCertVerified, Error> {
+            Ok(ServerCertVerified::assertion())
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            _message: &[u8],
+            _cert: &CertificateDer<'_>,
+            _dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, Error> {
+            Ok(HandshakeSignatureValid::assertion())
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            _message: &[u8],
+            _cert: &CertificateDer<'_>,
+            _dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, Error> {
+            Ok(HandshakeSignatureValid::assertion())
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+            default_provider()
+                .signature_verification_algorithms
+                .supported_schemes()
+        }
+    }
+}
+
+#[cfg(all(test, feature = "with-tls-rustls"))]
+mod tests {
+    use super::*;
+
+    fn file_path(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/files/tls")
+            .join(name)
+    }
+
+    #[test]
+    fn test_to_rustls_client_config() -> Result<(), Box<dyn std::error::Error>> {
+        let config = TlsConfig::new().to_rustls_client_config()?;
+        assert!(!config.client_auth_cert_resolver.has_certs());
+
+        let mut tls_config = TlsConfig::new();
+        tls_config
+            .set_root_ca_path(file_path("ca.pem"))
+            .set_client_cert_path(file_path("client.pem"), file_path("client.key"));
+        let config = tls_config.to_rustls_client_config()?;
+        assert!(config.client_auth_cert_resolver.has_certs());
+
+        tls_config.set_danger_accept_invalid_certs(true);
+        tls_config.to_rustls_client_config()?;
+
+        let mut tls_config = TlsConfig::new();
+        tls_config.set_root_ca_path(file_path("not_exists.pem"));
+        assert!(matches!(
+            tls_config.to_rustls_client_config(),
+            Err(Error::TlsConfigInvalid(_))
+        ));
+
+        let mut tls_config = TlsConfig::new();
+        tls_config.set_client_cert_path(file_path("client.pem"), file_path("ca.pem"));
+        assert!(tls_config.to_rustls_client_config().is_err());
+
+        Ok(())
+    }
+}
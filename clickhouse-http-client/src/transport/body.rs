@@ -0,0 +1,168 @@
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::Error as IoError;
+
+use bytes::Bytes;
+use futures_util::{
+    io::{AsyncRead, AsyncReadExt as _, Cursor},
+    stream::{self, Stream},
+};
+
+const CHUNK_SIZE: usize = 8192;
+
+/// Request and response body.
+pub struct Body(Inner);
+
+enum Inner {
+    Empty,
+    Bytes(Cursor<Bytes>),
+    Reader(Pin<Box<dyn AsyncRead + Send + Sync>>),
+}
+
+impl Body {
+    pub fn empty() -> Self {
+        Self(Inner::Empty)
+    }
+
+    pub fn from_reader<R>(reader: R) -> Self
+    where
+        R: AsyncRead + Send + Sync + 'static,
+    {
+        Self(Inner::Reader(Box::pin(reader)))
+    }
+
+    /// `None` for a reader, the length is unknown until it is read.
+    pub fn len(&self) -> Option<u64> {
+        match &self.0 {
+            Inner::Empty => Some(0),
+            Inner::Bytes(x) => Some(x.get_ref().len() as u64),
+            Inner::Reader(_) => None,
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
+    /// `Err` with the body itself for a reader.
+    pub fn try_into_bytes(self) -> Result<Bytes, Self> {
+        match self.0 {
+            Inner::Empty => Ok(Bytes::new()),
+            Inner::Bytes(x) if x.position() == 0 => Ok(x.into_inner()),
+            inner => Err(Self(inner)),
+        }
+    }
+
+    /// For transports that take the body as a stream of chunks.
+    pub fn into_stream(self) -> impl Stream<Item = Result<Vec<u8>, IoError>> + Send + Sync {
+        stream::try_unfold(self, |mut body| async move {
+            let mut buf = vec![0; CHUNK_SIZE];
+            let n = body.read(&mut buf).await?;
+            if n == 0 {
+                return Ok(None);
+            }
+            buf.truncate(n);
+            Ok(Some((buf, body)))
+        })
+    }
+
+    pub async fn bytes(mut self) -> Result<Vec<u8>, IoError> {
+        let mut buf = Vec::with_capacity(self.len().unwrap_or(4096) as usize);
+        self.read_to_end(&mut buf).await?;
+        Ok(buf)
+    }
+
+    pub async fn text(self) -> Result<String, IoError> {
+        let bytes = self.bytes().await?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+impl Default for Body {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl fmt::Debug for Body {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.len() {
+            Some(len) => write!(f, "Body({len})"),
+            None => f.write_str("Body(?)"),
+        }
+    }
+}
+
+impl From<()> for Body {
+    fn from(_: ()) -> Self {
+        Self::empty()
+    }
+}
+impl From<Bytes> for Body {
+    fn from(bytes: Bytes) -> Self {
+        Self(Inner::Bytes(Cursor::new(bytes)))
+    }
+}
+impl From<Vec<u8>> for Body {
+    fn from(bytes: Vec<u8>) -> Self {
+        Bytes::from(bytes).into()
+    }
+}
+impl From<&'static [u8]> for Body {
+    fn from(bytes: &'static [u8]) -> Self {
+        Bytes::from_static(bytes).into()
+    }
+}
+impl From<String> for Body {
+    fn from(s: String) -> Self {
+        s.into_bytes().into()
+    }
+}
+impl From<&'static str> for Body {
+    fn from(s: &'static str) -> Self {
+        s.as_bytes().into()
+    }
+}
+
+impl AsyncRead for Body {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, IoError>> {
+        match &mut self.get_mut().0 {
+            Inner::Empty => Poll::Ready(Ok(0)),
+            Inner::Bytes(x) => Pin::new(x).poll_read(cx, buf),
+            Inner::Reader(x) => x.as_mut().poll_read(cx, buf),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::TryStreamExt as _;
+
+    #[tokio::test]
+    async fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(Body::empty().len(), Some(0));
+        assert!(Body::empty().bytes().await?.is_empty());
+
+        let body = Body::from("foo");
+        assert_eq!(body.len(), Some(3));
+        assert_eq!(body.text().await?, "foo");
+
+        let body = Body::from_reader(Cursor::new(vec![b'x'; CHUNK_SIZE + 1]));
+        assert_eq!(body.len(), None);
+        let chunks = body.into_stream().try_collect::<Vec<_>>().await?;
+        assert_eq!(
+            chunks.iter().map(|x| x.len()).collect::<Vec<_>>(),
+            vec![CHUNK_SIZE, 1]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+use core::fmt;
+use std::io::Error as IoError;
+
+use bytes::Bytes;
+use futures_util::{
+    future::BoxFuture,
+    stream::{StreamExt as _, TryStreamExt as _},
+};
+use http::{Request, Response};
+use http_body_util::{BodyExt as _, Full, StreamBody, combinators::BoxBody};
+use hyper::body::Frame;
+#[cfg(feature = "with-tls-rustls")]
+use hyper_rustls::HttpsConnector;
+use hyper_util::{
+    client::legacy::{
+        Client as HyperClient,
+        connect::{Connect, HttpConnector},
+    },
+    rt::TokioExecutor,
+};
+
+use super::{Body, Transport, TransportError, TransportErrorKind};
+#[cfg(feature = "with-tls-rustls")]
+use crate::{error::Error, tls::TlsConfig};
+
+type HyperBody = BoxBody<Bytes, IoError>;
+
+// The requests must run inside a tokio runtime.
+// `new` is plain http, `with_tls_config` (`with-tls-rustls`) also speaks https.
+#[derive(Debug, Clone)]
+pub struct HyperTransport<C = HttpConnector> {
+    client: HyperClient<C, HyperBody>,
+}
+
+impl Default for HyperTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HyperTransport {
+    pub fn new() -> Self {
+        HyperClient::builder(TokioExecutor::new())
+            .build_http()
+            .into()
+    }
+}
+
+#[cfg(feature = "with-tls-rustls")]
+impl HyperTransport<HttpsConnector<HttpConnector>> {
+    pub fn with_tls_config(tls_config: &TlsConfig) -> Result<Self, Error> {
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_tls_config(tls_config.to_rustls_client_config()?)
+            .https_or_http()
+            .enable_http1()
+            .build();
+
+        Ok(HyperClient::builder(TokioExecutor::new())
+            .build(connector)
+            .into())
+    }
+}
+
+impl<C> From<HyperClient<C, HyperBody>> for HyperTransport<C> {
+    fn from(client: HyperClient<C, HyperBody>) -> Self {
+        Self { client }
+    }
+}
+
+impl<C> Transport for HyperTransport<C>
+where
+    C: Connect + Clone + fmt::Debug + Send + Sync + 'static,
+{
+    fn send(&self, req: Request<Body>) -> BoxFuture<'_, Result<Response<Body>, TransportError>> {
+        Box::pin(async move {
+            let req = req.map(|body| match body.try_into_bytes() {
+                Ok(bytes) => BoxBody::new(Full::new(bytes).map_err(|err| match err {})),
+                Err(body) => BoxBody::new(StreamBody::new(
+                    body.into_stream()
+                        .map_ok(|chunk| Frame::data(Bytes::from(chunk))),
+                )),
+            });
+
+            let resp = self.client.request(req).await?;
+
+            Ok(resp.map(|body| {
+                Body::from_reader(
+                    body.into_data_stream()
+                        .map(|x| x.map_err(IoError::other))
+                        .into_async_read(),
+                )
+            }))
+        })
+    }
+
+    fn is_tokio_runtime_required(&self) -> bool {
+        true
+    }
+}
+
+impl From<hyper_util::client::legacy::Error> for TransportError {
+    fn from(err: hyper_util::client::legacy::Error) -> Self {
+        let kind = if err.is_connect() {
+            TransportErrorKind::Network
+        } else {
+            TransportErrorKind::Other
+        };
+        Self::new(kind, err)
+    }
+}
+
+#[cfg(all(test, feature = "with-tls-rustls"))]
+mod tests {
+    use super::*;
+
+    use crate::test_helpers::{response, spawn_server};
+
+    async fn send<C>(transport: HyperTransport<C>, url: &str) -> Result<(), TransportError>
+    where
+        C: Connect + Clone + fmt::Debug + Send + Sync + 'static,
+    {
+        let resp = transport
+            .send(Request::post(url).body(Body::from(b"1".as_ref())).unwrap())
+            .await?;
+        assert!(resp.status().is_success());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_send_with_tls_enabled() -> Result<(), Box<dyn std::error::Error>> {
+        let server = spawn_server(|_| response(&[], b""));
+
+        // Both stay available when with-tls-rustls is enabled.
+        let transport: HyperTransport<HttpConnector> = HyperClient::builder(TokioExecutor::new())
+            .build_http()
+            .into();
+        send(transport, &server.url).await?;
+        send(
+            HyperTransport::with_tls_config(&TlsConfig::default())?,
+            &server.url,
+        )
+        .await?;
+
+        assert_eq!(server.requests.lock().unwrap().len(), 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use ::isahc::{
+    AsyncBody, HttpClient, HttpClientBuilder,
+    config::{CaCertificate, ClientCertificate, Configurable as _, PrivateKey, SslOption},
+    http as http02,
+};
+use futures_util::future::BoxFuture;
+use http::{HeaderName, HeaderValue, Request, Response, StatusCode, Version};
+
+use super::{Body, Transport, TransportError, TransportErrorKind};
+use crate::tls::TlsConfig;
+
+// isahc is on http 0.2, requests and responses are converted field by field.
+// libcurl never decodes the body, that is done once by `compression::decode_response`.
+#[derive(Debug, Clone)]
+pub struct IsahcTransport {
+    http_client: HttpClient,
+}
+
+impl IsahcTransport {
+    pub fn new() -> Result<Self, TransportError> {
+        Ok(HttpClient::builder()
+            .automatic_decompression(false)
+            .build()?
+            .into())
+    }
+}
+
+pub(crate) fn configure_tls(
+    mut builder: HttpClientBuilder,
+    tls_config: &TlsConfig,
+) -> HttpClientBuilder {
+    if let Some(path) = tls_config.get_root_ca_path() {
+        builder = builder.ssl_ca_certificate(CaCertificate::file(path));
+    }
+    if let Some((cert, key)) = tls_config.get_client_cert_path() {
+        builder = builder.ssl_client_certificate(ClientCertificate::pem_file(
+            cert,
+            PrivateKey::pem_file(key, None),
+        ));
+    }
+    if tls_config.is_danger_accept_invalid_certs() {
+        builder = builder.ssl_options(
+            SslOption::DANGER_ACCEPT_INVALID_CERTS | SslOption::DANGER_ACCEPT_INVALID_HOSTS,
+        );
+    }
+    builder
+}
+
+impl From<HttpClient> for IsahcTransport {
+    fn from(http_client: HttpClient) -> Self {
+        Self { http_client }
+    }
+}
+
+impl Transport for IsahcTransport {
+    fn send(&self, req: Request<Body>) -> BoxFuture<'_, Result<Response<Body>, TransportError>> {
+        Box::pin(async move {
+            let req = to_isahc_request(req)?;
+            let resp = self.http_client.send_async(req).await?;
+            from_isahc_response(resp)
+        })
+    }
+}
+
+impl From<::isahc::Error> for TransportError {
+    fn from(err: ::isahc::Error) -> Self {
+        let kind = if err.is_timeout() {
+            TransportErrorKind::Timeout
+        } else if err.is_network() {
+            TransportErrorKind::Network
+        } else {
+            TransportErrorKind::Other
+        };
+        Self::new(kind, err)
+    }
+}
+
+fn to_isahc_request(req: Request<Body>) -> Result<http02::Request<AsyncBody>, TransportError> {
+    let (parts, body) = req.into_parts();
+
+    let mut builder = http02::Request::builder()
+        .method(parts.method.as_str())
+        .uri(parts.uri.to_string())
+        .automatic_decompression(false)
+        .version(match parts.version {
+            Version::HTTP_09 => http02::Version::HTTP_09,
+            Version::HTTP_10 => http02::Version::HTTP_10,
+            Version::HTTP_2 => http02::Version::HTTP_2,
+            Version::HTTP_3 => http02::Version::HTTP_3,
+            _ => http02::Version::HTTP_11,
+        });
+    for (name, value) in parts.headers.iter() {
+        builder = builder.header(name.as_str(), value.as_bytes());
+    }
+
+    let body = match body.try_into_bytes() {
+        Ok(bytes) if bytes.is_empty() => AsyncBody::empty(),
+        // Shared with the other attempts of a retry, so it is read instead of copied.
+        Ok(bytes) => {
+            let len = bytes.len() as u64;
+            AsyncBody::from_reader_sized(futures_util::io::Cursor::new(bytes), len)
+        }
+        Err(body) => AsyncBody::from_reader(body),
+    };
+
+    builder
+        .body(body)
+        .map_err(|err| TransportError::new(TransportErrorKind::Other, err))
+}
+
+fn from_isahc_response(
+    resp: http02::Response<AsyncBody>,
+) -> Result<Response<Body>, TransportError> {
+    let (parts, body) = resp.into_parts();
+
+    let mut builder = Response::builder()
+        .status(
+            StatusCode::from_u16(parts.status.as_u16())
+                .map_err(|err| TransportError::new(TransportErrorKind::Other, err))?,
+        )
+        .version(match parts.version {
+            http02::Version::HTTP_09 => Version::HTTP_09,
+            http02::Version::HTTP_10 => Version::HTTP_10,
+            http02::Version::HTTP_2 => Version::HTTP_2,
+            http02::Version::HTTP_3 => Version::HTTP_3,
+            _ => Version::HTTP_11,
+        });
+    for (name, value) in parts.headers.iter() {
+        builder = builder.header(
+            HeaderName::from_bytes(name.as_str().as_bytes())
+                .map_err(|err| TransportError::new(TransportErrorKind::Other, err))?,
+            HeaderValue::from_bytes(value.as_bytes())
+                .map_err(|err| TransportError::new(TransportErrorKind::Other, err))?,
+        );
+    }
+
+    builder
+        .body(Body::from_reader(body))
+        .map_err(|err| TransportError::new(TransportErrorKind::Other, err))
+}
+
+#[cfg(all(test, feature = "with-compression-gzip"))]
+mod tests {
+    use super::*;
+
+    use crate::{
+        client::ClientBuilder,
+        test_helpers::{response, spawn_server},
+    };
+
+    #[tokio::test]
+    async fn test_send_with_content_encoding() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::compression::Compression;
+
+        let encoded = Compression::Gzip.encode_bytes(b"Ok.\n").await?;
+        let server = spawn_server(move |_| response(&[("Content-Encoding", "gzip")], &encoded));
+
+        for transport in [IsahcTransport::new()?, HttpClient::new()?.into()] {
+            let mut client_builder = ClientBuilder::new();
+            client_builder
+                .set_url(&server.url)?
+                .set_response_compression(Compression::Gzip);
+            client_builder.set_transport(transport);
+            let client = client_builder.build()?;
+
+            assert!(client.ping().await?);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+use core::fmt;
+use std::error::Error as StdError;
+
+use futures_util::future::BoxFuture;
+use http::{Request, Response};
+
+pub mod body;
+
+#[cfg(feature = "with-transport-hyper")]
+pub mod hyper;
+#[cfg(feature = "with-transport-isahc")]
+pub mod isahc;
+#[cfg(feature = "with-transport-reqwest")]
+pub mod reqwest;
+
+pub use self::body::Body;
+#[cfg(feature = "with-transport-hyper")]
+pub use self::hyper::HyperTransport;
+#[cfg(feature = "with-transport-isahc")]
+pub use self::isahc::IsahcTransport;
+#[cfg(feature = "with-transport-reqwest")]
+pub use self::reqwest::ReqwestTransport;
+
+/// Sends requests for `Client`.
+///
+/// The response body is read as the rows are consumed, it must not be buffered up front.
+pub trait Transport: fmt::Debug + Send + Sync {
+    fn send(&self, req: Request<Body>) -> BoxFuture<'_, Result<Response<Body>, TransportError>>;
+
+    /// Whether `send` must be polled inside a tokio runtime.
+    fn is_tokio_runtime_required(&self) -> bool {
+        false
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for std::sync::Arc<T> {
+    fn send(&self, req: Request<Body>) -> BoxFuture<'_, Result<Response<Body>, TransportError>> {
+        (**self).send(req)
+    }
+
+    fn is_tokio_runtime_required(&self) -> bool {
+        (**self).is_tokio_runtime_required()
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("{inner}")]
+pub struct TransportError {
+    kind: TransportErrorKind,
+    #[source]
+    inner: Box<dyn StdError + Send + Sync>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportErrorKind {
+    /// Connecting failed or the connection broke.
+    Network,
+    Timeout,
+    Other,
+}
+
+impl TransportError {
+    pub fn new(
+        kind: TransportErrorKind,
+        inner: impl Into<Box<dyn StdError + Send + Sync>>,
+    ) -> Self {
+        Self {
+            kind,
+            inner: inner.into(),
+        }
+    }
+
+    pub fn kind(&self) -> TransportErrorKind {
+        self.kind
+    }
+    pub fn is_network(&self) -> bool {
+        self.kind == TransportErrorKind::Network
+    }
+    pub fn is_timeout(&self) -> bool {
+        self.kind == TransportErrorKind::Timeout
+    }
+
+    pub fn get_ref(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        self.inner.as_ref()
+    }
+}
+
+// The transport of `ClientBuilder::build` without `set_transport`.
+// isahc is built in `ClientBuilder::build`, as it can be configured there. Without it, hyper is
+// chosen over reqwest when both are enabled, and there is none without any of them.
+#[cfg(all(
+    not(feature = "with-transport-isahc"),
+    feature = "with-transport-hyper"
+))]
+pub(crate) fn default_transport(
+    tls_config: Option<&crate::tls::TlsConfig>,
+) -> Result<Option<std::sync::Arc<dyn Transport>>, crate::error::Error> {
+    #[cfg(feature = "with-tls-rustls")]
+    let transport = HyperTransport::with_tls_config(&tls_config.cloned().unwrap_or_default())?;
+    #[cfg(not(feature = "with-tls-rustls"))]
+    let transport = {
+        check_tls_config_unset(tls_config)?;
+        HyperTransport::new()
+    };
+
+    Ok(Some(std::sync::Arc::new(transport)))
+}
+
+#[cfg(all(
+    not(feature = "with-transport-isahc"),
+    not(feature = "with-transport-hyper"),
+    feature = "with-transport-reqwest"
+))]
+pub(crate) fn default_transport(
+    tls_config: Option<&crate::tls::TlsConfig>,
+) -> Result<Option<std::sync::Arc<dyn Transport>>, crate::error::Error> {
+    #[cfg(feature = "with-tls-rustls")]
+    let transport = ReqwestTransport::with_tls_config(&tls_config.cloned().unwrap_or_default())?;
+    #[cfg(not(feature = "with-tls-rustls"))]
+    let transport = {
+        check_tls_config_unset(tls_config)?;
+        ReqwestTransport::new()?
+    };
+
+    Ok(Some(std::sync::Arc::new(transport)))
+}
+
+#[cfg(not(any(
+    feature = "with-transport-isahc",
+    feature = "with-transport-hyper",
+    feature = "with-transport-reqwest"
+)))]
+pub(crate) fn default_transport(
+    _tls_config: Option<&crate::tls::TlsConfig>,
+) -> Result<Option<std::sync::Arc<dyn Transport>>, crate::error::Error> {
+    Ok(None)
+}
+
+#[cfg(all(
+    not(feature = "with-transport-isahc"),
+    any(feature = "with-transport-hyper", feature = "with-transport-reqwest"),
+    not(feature = "with-tls-rustls")
+))]
+fn check_tls_config_unset(
+    tls_config: Option<&crate::tls::TlsConfig>,
+) -> Result<(), crate::error::Error> {
+    match tls_config {
+        Some(_) => Err(crate::error::Error::TlsConfigInvalid(
+            "the hyper and reqwest transports require with-tls-rustls".to_owned(),
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Mutex;
+
+    use crate::client::ClientBuilder;
+
+    #[derive(Debug, Default)]
+    struct MockTransport {
+        requests: Mutex<Vec<(String, Vec<u8>)>>,
+    }
+
+    impl Transport for MockTransport {
+        fn send(
+            &self,
+            req: Request<Body>,
+        ) -> BoxFuture<'_, Result<Response<Body>, TransportError>> {
+            Box::pin(async move {
+                let (parts, body) = req.into_parts();
+                let body = body
+                    .bytes()
+                    .await
+                    .map_err(|err| TransportError::new(TransportErrorKind::Network, err))?;
+                self.requests
+                    .lock()
+                    .unwrap()
+                    .push((parts.uri.path().to_owned(), body));
+
+                Ok(Response::new(Body::from("Ok.\n")))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_transport() -> Result<(), Box<dyn std::error::Error>> {
+        let transport = std::sync::Arc::new(MockTransport::default());
+
+        let mut client_builder = ClientBuilder::new();
+        client_builder.set_transport(transport.clone());
+        let client = client_builder.build()?;
+
+        assert!(client.ping().await?);
+        client.execute("SELECT 1", None).await?;
+
+        assert_eq!(
+            *transport.requests.lock().unwrap(),
+            vec![
+                ("/ping".to_owned(), vec![]),
+                ("/".to_owned(), b"SELECT 1".to_vec())
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_tls_config_with_set_transport() {
+        let mut client_builder = ClientBuilder::new();
+        client_builder
+            .set_transport(MockTransport::default())
+            .set_tls_config(Default::default());
+        assert!(matches!(
+            client_builder.build(),
+            Err(crate::error::Error::TlsConfigInvalid(_))
+        ));
+    }
+}
@@ -0,0 +1,95 @@
+use std::io::Error as IoError;
+
+use futures_util::{
+    future::BoxFuture,
+    stream::{StreamExt as _, TryStreamExt as _},
+};
+use http::{Request, Response};
+
+use super::{Body, Transport, TransportError, TransportErrorKind};
+use crate::error::Error;
+#[cfg(feature = "with-tls-rustls")]
+use crate::tls::TlsConfig;
+
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: ::reqwest::Client,
+}
+
+impl ReqwestTransport {
+    /// With `with-tls-rustls`, the same as `with_tls_config` with the default `TlsConfig`.
+    pub fn new() -> Result<Self, Error> {
+        #[cfg(feature = "with-tls-rustls")]
+        return Self::with_tls_config(&TlsConfig::default());
+
+        #[cfg(not(feature = "with-tls-rustls"))]
+        ::reqwest::Client::builder()
+            .build()
+            .map(Into::into)
+            .map_err(|err| TransportError::from(err).into())
+    }
+
+    #[cfg(feature = "with-tls-rustls")]
+    pub fn with_tls_config(tls_config: &TlsConfig) -> Result<Self, Error> {
+        let client = ::reqwest::Client::builder()
+            .use_preconfigured_tls(tls_config.to_rustls_client_config()?)
+            .build()
+            .map_err(TransportError::from)?;
+
+        Ok(client.into())
+    }
+}
+
+impl From<::reqwest::Client> for ReqwestTransport {
+    fn from(client: ::reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, req: Request<Body>) -> BoxFuture<'_, Result<Response<Body>, TransportError>> {
+        Box::pin(async move {
+            let req = req.map(|body| match body.try_into_bytes() {
+                Ok(bytes) => ::reqwest::Body::from(bytes),
+                Err(body) => ::reqwest::Body::wrap_stream(body.into_stream()),
+            });
+            let req = ::reqwest::Request::try_from(req)?;
+
+            let resp = self.client.execute(req).await?;
+
+            let mut builder = Response::builder()
+                .status(resp.status())
+                .version(resp.version());
+            if let Some(headers) = builder.headers_mut() {
+                *headers = resp.headers().to_owned();
+            }
+
+            let body = Body::from_reader(
+                resp.bytes_stream()
+                    .map(|x| x.map_err(IoError::other))
+                    .into_async_read(),
+            );
+
+            builder
+                .body(body)
+                .map_err(|err| TransportError::new(TransportErrorKind::Other, err))
+        })
+    }
+
+    fn is_tokio_runtime_required(&self) -> bool {
+        true
+    }
+}
+
+impl From<::reqwest::Error> for TransportError {
+    fn from(err: ::reqwest::Error) -> Self {
+        let kind = if err.is_timeout() {
+            TransportErrorKind::Timeout
+        } else if err.is_connect() {
+            TransportErrorKind::Network
+        } else {
+            TransportErrorKind::Other
+        };
+        Self::new(kind, err)
+    }
+}
@@ -0,0 +1,23 @@
+#!/usr/bin/env bash
+
+set -ex
+
+# ./tests/gen_tls_files.sh
+
+script_path=$(cd $(dirname $0) ; pwd -P)
+script_path_root="${script_path}/"
+
+dir="${script_path_root}files/tls"
+mkdir -p "${dir}"
+
+openssl req -x509 -newkey ec -pkeyopt ec_paramgen_curve:prime256v1 -nodes -days 36500 \
+    -subj "/CN=clickhouse-rs testing ca" \
+    -keyout "${dir}/ca.key" -out "${dir}/ca.pem"
+
+openssl req -newkey ec -pkeyopt ec_paramgen_curve:prime256v1 -nodes \
+    -subj "/CN=default" \
+    -keyout "${dir}/client.key" -out "${dir}/client.csr"
+openssl x509 -req -days 36500 -in "${dir}/client.csr" \
+    -CA "${dir}/ca.pem" -CAkey "${dir}/ca.key" -CAcreateserial -out "${dir}/client.pem"
+
+rm -f "${dir}/client.csr" "${dir}/ca.srl" "${dir}/ca.key"
@@ -8,4 +8,49 @@ mod integration_tests {
 
     #[cfg(test)]
     mod curd;
+
+    #[cfg(test)]
+    mod query;
+
+    #[cfg(test)]
+    mod compression;
+
+    #[cfg(test)]
+    mod session;
+
+    #[cfg(test)]
+    mod cancel;
+
+    #[cfg(test)]
+    mod endpoints;
+
+    #[cfg(test)]
+    mod inserter;
+
+    #[cfg(test)]
+    mod async_insert;
+
+    #[cfg(test)]
+    mod external_data;
+
+    #[cfg(test)]
+    mod query_settings;
+
+    #[cfg(test)]
+    mod progress;
+
+    #[cfg(test)]
+    mod transport;
+
+    #[cfg(test)]
+    mod blocking;
+
+    #[cfg(test)]
+    mod row_binary;
+
+    #[cfg(test)]
+    mod native;
+
+    #[cfg(test)]
+    mod csv;
 }
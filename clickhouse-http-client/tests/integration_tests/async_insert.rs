@@ -0,0 +1,53 @@
+use clickhouse_http_client::{
+    AsyncInsertMode, AsyncInsertOptions, clickhouse_format::input::JsonCompactEachRowInput,
+};
+use serde_json::Value;
+
+use super::helpers::*;
+
+#[tokio::test]
+async fn simple() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let client = get_client()?;
+
+    client
+        .execute("DROP TABLE IF EXISTS t_testing_async_insert", None)
+        .await?;
+    client
+        .execute(
+            "CREATE TABLE t_testing_async_insert (id UInt32) ENGINE=MergeTree ORDER BY id",
+            None,
+        )
+        .await?;
+
+    let rows: Vec<Vec<Value>> = vec![vec![1.into()], vec![2.into()]];
+    let info = client
+        .async_insert_with_format(
+            "INSERT INTO t_testing_async_insert (id)",
+            JsonCompactEachRowInput::new(rows),
+            &AsyncInsertOptions::new(),
+            None,
+        )
+        .await?;
+    assert_eq!(info.async_insert_mode, Some(AsyncInsertMode::Flushed));
+
+    let mut options = AsyncInsertOptions::new();
+    options.set_wait_for_async_insert(false);
+    let rows: Vec<Vec<Value>> = vec![vec![3.into()]];
+    let info = client
+        .async_insert_with_format(
+            "INSERT INTO t_testing_async_insert (id)",
+            JsonCompactEachRowInput::new(rows),
+            &options,
+            None,
+        )
+        .await?;
+    assert_eq!(info.async_insert_mode, Some(AsyncInsertMode::Queued));
+
+    client
+        .execute("DROP TABLE t_testing_async_insert", None)
+        .await?;
+
+    Ok(())
+}
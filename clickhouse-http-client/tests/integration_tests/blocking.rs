@@ -0,0 +1,59 @@
+use clickhouse_http_client::{
+    blocking::Client,
+    clickhouse_format::{
+        input::JsonCompactEachRowInput, output::JsonCompactEachRowWithNamesAndTypesOutput,
+    },
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::helpers::*;
+
+#[derive(Deserialize, Debug)]
+pub struct Row {
+    pub id: u32,
+}
+
+#[test]
+fn simple() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let client = Client::try_from(get_client()?)?;
+
+    assert!(client.ping()?);
+
+    client.execute("DROP TABLE IF EXISTS t_testing_blocking", None)?;
+    client.execute(
+        "CREATE TABLE t_testing_blocking (id UInt32) ENGINE=Memory",
+        None,
+    )?;
+
+    let rows: Vec<Vec<Value>> = vec![vec![1.into()], vec![2.into()], vec![3.into()]];
+    let info = client.insert_with_format(
+        "INSERT INTO t_testing_blocking",
+        JsonCompactEachRowInput::new(rows),
+        None,
+    )?;
+    assert_eq!(info.summary.unwrap().written_rows, Some(3));
+
+    let (rows, _) = client.select_with_format(
+        "SELECT * FROM t_testing_blocking ORDER BY id",
+        JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+        None,
+    )?;
+    assert_eq!(rows.len(), 3);
+
+    let ids = client
+        .select_iter_with_format(
+            "SELECT * FROM t_testing_blocking ORDER BY id",
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            None,
+        )?
+        .map(|x| x.map(|row| row.id))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(ids, vec![1, 2, 3]);
+
+    client.execute("DROP TABLE t_testing_blocking", None)?;
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use core::time::Duration;
+
+use clickhouse_http_client::{
+    clickhouse_format::output::JsonCompactEachRowWithNamesAndTypesOutput,
+    query_id::generate_query_id,
+};
+use serde::Deserialize;
+
+use super::helpers::*;
+
+#[derive(Deserialize, Debug)]
+pub struct Row {
+    pub count: u64,
+}
+
+async fn is_running(
+    client: &clickhouse_http_client::Client,
+    query_id: &str,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let (rows, _) = client
+        .query("SELECT count() AS count FROM system.processes WHERE query_id = {query_id:String}")?
+        .bind("query_id", query_id)?
+        .select_with_format(
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            None,
+        )
+        .await?;
+    Ok(rows.first().map(|x| x.count > 0).unwrap_or(false))
+}
+
+#[tokio::test]
+async fn simple() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let client = get_client()?;
+
+    let future = client.cancel_on_drop(|client| async move {
+        client
+            .execute(
+                "SELECT sleepEachRow(0.1) FROM system.numbers LIMIT 100 FORMAT Null",
+                None,
+            )
+            .await
+    })?;
+    let query_id = future.query_id().to_owned();
+    let result = tokio::time::timeout(Duration::from_millis(300), future).await;
+    assert!(result.is_err());
+
+    let mut is_cancelled = false;
+    for _ in 0..20 {
+        if !is_running(&client, &query_id).await? {
+            is_cancelled = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(is_cancelled);
+
+    client.cancel(generate_query_id()).await?;
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use chrono::Utc;
+use clickhouse_http_client::{
+    Compression,
+    clickhouse_format::{
+        format_name::FormatName, input::JsonCompactEachRowInput,
+        output::JsonCompactEachRowWithNamesAndTypesOutput,
+    },
+};
+use futures_util::{TryStreamExt as _, io::Cursor};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::helpers::*;
+
+#[derive(Deserialize, Debug)]
+pub struct Event {
+    #[serde(rename = "event_id")]
+    pub id: u32,
+}
+
+#[tokio::test]
+async fn simple() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    for compression in [Compression::Gzip, Compression::Zstd, Compression::Lz4] {
+        let mut client = get_client()?;
+        client
+            .set_request_compression(compression)
+            .set_response_compression(compression);
+
+        let table = format!("t_testing_compression_{}", compression.content_encoding());
+
+        client
+            .execute(
+                format!(
+                    "CREATE TABLE {table} (event_id UInt32, created_at Datetime('UTC')) ENGINE=Memory"
+                ),
+                None,
+            )
+            .await?;
+
+        let rows: Vec<Vec<Value>> = (1..=100)
+            .map(|id: u32| vec![id.into(), Utc::now().timestamp().into()])
+            .collect();
+        let response_info = client
+            .insert_with_format(
+                format!("INSERT INTO {table} (event_id, created_at)"),
+                JsonCompactEachRowInput::new(rows),
+                None,
+            )
+            .await?;
+        assert_eq!(response_info.summary.unwrap().written_rows, Some(100));
+
+        client
+            .insert_with_format_reader(
+                format!("INSERT INTO {table} (event_id, created_at)"),
+                FormatName::JsonCompactEachRow,
+                Cursor::new(format!("[101, {}]\n", Utc::now().timestamp()).into_bytes()),
+                None,
+            )
+            .await?;
+
+        let (events, _) = client
+            .select_with_format(
+                format!("SELECT event_id FROM {table} ORDER BY event_id"),
+                JsonCompactEachRowWithNamesAndTypesOutput::<Event>::new(),
+                None,
+            )
+            .await?;
+        assert_eq!(events.len(), 101);
+
+        let events: Vec<Event> = client
+            .select_stream_with_format(
+                format!("SELECT event_id FROM {table} ORDER BY event_id"),
+                JsonCompactEachRowWithNamesAndTypesOutput::<Event>::new(),
+                None,
+            )
+            .await?
+            .try_collect()
+            .await?;
+        assert_eq!(events.last().unwrap().id, 101);
+
+        client.execute(format!("DROP TABLE {table}"), None).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use clickhouse_http_client::{
+    QuerySettings,
+    clickhouse_format::{
+        csv_options::CsvOptions,
+        input::{CsvInput, CsvWithNamesInput},
+        output::{CsvOutput, CsvWithNamesAndTypesOutput},
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use super::helpers::*;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Row {
+    pub id: u32,
+    pub name: Option<String>,
+    pub tags: String,
+}
+
+#[tokio::test]
+async fn simple() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let client = get_client()?;
+
+    client
+        .execute("DROP TABLE IF EXISTS t_testing_csv", None)
+        .await?;
+    client
+        .execute(
+            r#"
+CREATE TABLE t_testing_csv
+(
+    id UInt32,
+    name Nullable(String),
+    tags Array(String)
+) ENGINE=Memory
+            "#,
+            None,
+        )
+        .await?;
+
+    let rows = vec![
+        Row {
+            id: 1,
+            name: Some("a;b \"c\"".into()),
+            tags: "['x','y']".into(),
+        },
+        Row {
+            id: 2,
+            name: None,
+            tags: "[]".into(),
+        },
+    ];
+
+    let mut options = CsvOptions::new();
+    options.set_delimiter(b';').set_null_representation("NULL");
+    let mut settings = QuerySettings::new();
+    settings
+        .set_format_csv_delimiter(';')
+        .set_format_csv_null_representation("NULL");
+
+    let mut input = CsvInput::new(rows.iter().collect());
+    input.set_options(options.clone());
+    client
+        .insert_with_format("INSERT INTO t_testing_csv", input, &settings)
+        .await?;
+
+    let mut output = CsvOutput::<Row>::new();
+    output.set_options(options);
+    let (rows_selected, _) = client
+        .select_with_format("SELECT * FROM t_testing_csv ORDER BY id", output, &settings)
+        .await?;
+    assert_eq!(rows_selected, rows);
+
+    client.execute("TRUNCATE TABLE t_testing_csv", None).await?;
+
+    client
+        .insert_with_format(
+            "INSERT INTO t_testing_csv",
+            CsvWithNamesInput::new(
+                vec!["tags".into(), "id".into(), "name".into()],
+                rows.iter()
+                    .map(|row| (&row.tags, row.id, &row.name))
+                    .collect(),
+            ),
+            None,
+        )
+        .await?;
+
+    let (rows_selected, info) = client
+        .select_with_format(
+            "SELECT * FROM t_testing_csv ORDER BY id",
+            CsvWithNamesAndTypesOutput::<Row>::new(),
+            None,
+        )
+        .await?;
+    assert_eq!(rows_selected, rows);
+    assert_eq!(info.get("name").unwrap(), "Nullable(String)");
+
+    client.execute("DROP TABLE t_testing_csv", None).await?;
+
+    Ok(())
+}
@@ -1,7 +1,12 @@
 use chrono::{NaiveDateTime, Utc};
-use clickhouse_http_client::clickhouse_format::{
-    input::JsonCompactEachRowInput, output::JsonCompactEachRowWithNamesAndTypesOutput,
+use clickhouse_http_client::{
+    Error, QueryResponseInfo,
+    clickhouse_format::{
+        format_name::FormatName, input::JsonCompactEachRowInput,
+        output::JsonCompactEachRowWithNamesAndTypesOutput,
+    },
 };
+use futures_util::{TryStreamExt as _, io::Cursor, stream};
 use serde::Deserialize;
 use serde_json::Value;
 
@@ -39,13 +44,16 @@ CREATE TABLE t_testing_events
         vec![1.into(), Utc::now().timestamp().into()],
         vec![2.into(), Utc::now().timestamp().into()],
     ];
-    client
+    let response_info = client
         .insert_with_format(
             "INSERT INTO t_testing_events (event_id, created_at)",
             JsonCompactEachRowInput::new(rows),
             None,
         )
         .await?;
+    println!("{response_info:?}");
+    assert!(response_info.query_id.is_some());
+    assert_eq!(response_info.summary.unwrap().written_rows, Some(2));
 
     let (events, info) = client
         .select_with_format(
@@ -70,7 +78,142 @@ CREATE TABLE t_testing_events
     println!("{events:?}");
     println!("{info:?}");
 
+    let (resp_parts, (events, _)) = client
+        .internal_select_with_format(
+            "SELECT * FROM t_testing_events",
+            JsonCompactEachRowWithNamesAndTypesOutput::<Event>::new(),
+            vec![("wait_end_of_query", "1")],
+            |req| req,
+        )
+        .await?;
+    let response_info = QueryResponseInfo::from_headers(&resp_parts.headers);
+    println!("{response_info:?}");
+    assert_eq!(
+        response_info.summary.unwrap().result_rows,
+        Some(events.len())
+    );
+
+    let mut stream = client
+        .select_stream_with_format(
+            "SELECT * FROM t_testing_events",
+            JsonCompactEachRowWithNamesAndTypesOutput::<Event>::new(),
+            None,
+        )
+        .await?;
+    let event = stream.try_next().await?.unwrap();
+    assert_eq!(event.id, 1);
+    assert_eq!(
+        stream.info().unwrap().get("event_id"),
+        Some(&"UInt32".to_owned())
+    );
+    let event = stream.try_next().await?.unwrap();
+    assert_eq!(event.id, 2);
+    assert!(stream.try_next().await?.is_none());
+
+    let chunks: Vec<JsonCompactEachRowInput<Value>> = (3..=6)
+        .collect::<Vec<u32>>()
+        .chunks(2)
+        .map(|ids| {
+            JsonCompactEachRowInput::new(
+                ids.iter()
+                    .map(|id| vec![(*id).into(), Utc::now().timestamp().into()])
+                    .collect(),
+            )
+        })
+        .collect();
+    client
+        .insert_with_format_stream(
+            "INSERT INTO t_testing_events (event_id, created_at)",
+            stream::iter(chunks),
+            None,
+        )
+        .await?;
+
+    client
+        .insert_with_format_reader(
+            "INSERT INTO t_testing_events (event_id, created_at)",
+            FormatName::JsonCompactEachRow,
+            Cursor::new(format!("[7, {}]\n", Utc::now().timestamp()).into_bytes()),
+            None,
+        )
+        .await?;
+
+    let (events, _) = client
+        .select_with_format(
+            "SELECT * FROM t_testing_events ORDER BY event_id",
+            JsonCompactEachRowWithNamesAndTypesOutput::<Event>::new(),
+            None,
+        )
+        .await?;
+    assert_eq!(events.len(), 7);
+    assert_eq!(events.last().unwrap().id, 7);
+
     client.execute("DROP TABLE t_testing_events", None).await?;
 
     Ok(())
 }
+
+#[tokio::test]
+async fn with_server_exception() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let client = get_client()?;
+
+    match client
+        .execute("SELECT * FROM t_testing_not_exists", None)
+        .await
+    {
+        Err(Error::ServerException(exception)) => {
+            println!("{exception:?}");
+            assert_eq!(exception.code, 60);
+            assert!(exception.query_id.is_some());
+            assert!(!exception.is_retryable());
+        }
+        x => panic!("{x:?}"),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn with_server_exception_mid_stream() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let client = get_client()?;
+
+    let sql = "SELECT number AS event_id, now() AS created_at, throwIf(number = 100000, 'mid stream') FROM numbers(200000) SETTINGS max_block_size = 1000";
+
+    match client
+        .select_with_format(
+            sql,
+            JsonCompactEachRowWithNamesAndTypesOutput::<Event>::new(),
+            None,
+        )
+        .await
+    {
+        Err(Error::ServerException(exception)) => {
+            println!("{exception:?}");
+            assert_eq!(exception.code, 395);
+        }
+        x => panic!("{x:?}"),
+    }
+
+    let mut stream = client
+        .select_stream_with_format(
+            sql,
+            JsonCompactEachRowWithNamesAndTypesOutput::<Event>::new(),
+            None,
+        )
+        .await?;
+    let mut n = 0;
+    let err = loop {
+        match stream.try_next().await {
+            Ok(Some(_)) => n += 1,
+            Ok(None) => panic!("no exception after {n} rows"),
+            Err(err) => break err,
+        }
+    };
+    assert!(matches!(err, Error::ServerException(exception) if exception.code == 395));
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use core::time::Duration;
+use std::env;
+
+use clickhouse_http_client::{LoadBalancing, RetryPolicy};
+
+use super::helpers::*;
+
+#[tokio::test]
+async fn with_failover() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let http_url =
+        env::var("CLICKHOUSE_HTTP_URL").unwrap_or_else(|_| "http://localhost:8123/".to_owned());
+
+    let mut retry_policy = RetryPolicy::new();
+    retry_policy
+        .set_max_attempts(2)
+        .set_initial_backoff(Duration::from_millis(10));
+
+    let mut client = get_client()?;
+    client
+        .set_urls(["http://127.0.0.1:1/", http_url.as_str()])?
+        .set_load_balancing(LoadBalancing::FirstHealthy)
+        .set_retry_policy(retry_policy);
+
+    client.execute("SELECT 1", None).await?;
+    client.execute("SELECT 1", None).await?;
+
+    assert_eq!(client.check_endpoints().await, 1);
+
+    Ok(())
+}
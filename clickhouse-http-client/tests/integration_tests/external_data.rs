@@ -0,0 +1,58 @@
+use clickhouse_http_client::{
+    ExternalTable,
+    clickhouse_data_type::type_name::TypeName,
+    clickhouse_format::{
+        input::JsonCompactEachRowInput, output::JsonCompactEachRowWithNamesAndTypesOutput,
+    },
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::helpers::*;
+
+#[derive(Deserialize, Debug)]
+pub struct Row {
+    pub id: u32,
+    pub name: String,
+}
+
+#[tokio::test]
+async fn simple() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let client = get_client()?;
+
+    let ids = ExternalTable::new(
+        "ids",
+        vec![("id".to_owned(), TypeName::UInt32)],
+        JsonCompactEachRowInput::<Value>::new((1..=3).map(|i| vec![i.into()]).collect()),
+    )?;
+    let names = ExternalTable::new(
+        "names",
+        vec![
+            ("id".to_owned(), TypeName::UInt32),
+            ("name".to_owned(), TypeName::String),
+        ],
+        JsonCompactEachRowInput::<Value>::new(vec![
+            vec![1.into(), "a".into()],
+            vec![3.into(), "c".into()],
+        ]),
+    )?;
+
+    let (rows, _) = client
+        .select_with_external_data(
+            "SELECT ids.id AS id, names.name AS name FROM ids INNER JOIN names ON ids.id = names.id ORDER BY id",
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            &[ids, names],
+            None,
+        )
+        .await?;
+    assert_eq!(
+        rows.iter()
+            .map(|x| (x.id, x.name.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(1, "a"), (3, "c")]
+    );
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use core::time::Duration;
 use std::env;
 
-use clickhouse_http_client::{Client, ClientBuilder, isahc::config::Configurable as _};
+use clickhouse_http_client::{Client, ClientBuilder, Transport, isahc::config::Configurable as _};
 
 pub(super) fn get_client() -> Result<Client, Box<dyn std::error::Error>> {
     let mut client = get_anonymous_client()?;
@@ -21,6 +21,20 @@ pub(super) fn get_anonymous_client() -> Result<Client, Box<dyn std::error::Error
     Ok(client_builder.build()?)
 }
 
+pub(super) fn get_client_with_transport(
+    transport: impl Transport + 'static,
+) -> Result<Client, Box<dyn std::error::Error>> {
+    let mut client_builder = ClientBuilder::new();
+    client_builder.set_transport(transport);
+    if let Ok(http_url) = env::var("CLICKHOUSE_HTTP_URL") {
+        client_builder.set_url(http_url).unwrap();
+    }
+    client_builder.set_username_to_header("default")?;
+    client_builder.set_password_to_header("xxx")?;
+
+    Ok(client_builder.build()?)
+}
+
 pub(super) fn init_logger() {
     let _ = env_logger::builder().is_test(true).try_init();
 }
@@ -0,0 +1,68 @@
+use core::time::Duration;
+
+use clickhouse_http_client::clickhouse_format::{
+    input::{JsonCompactEachRowInput, JsonEachRowInput},
+    output::JsonCompactEachRowWithNamesAndTypesOutput,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::helpers::*;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Row {
+    pub id: u32,
+}
+
+#[tokio::test]
+async fn simple() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let client = get_client()?;
+
+    client
+        .execute("DROP TABLE IF EXISTS t_testing_inserter", None)
+        .await?;
+    client
+        .execute(
+            "CREATE TABLE t_testing_inserter (id UInt32) ENGINE=Memory",
+            None,
+        )
+        .await?;
+
+    let mut inserter =
+        client.inserter::<Vec<Value>, JsonCompactEachRowInput<Value>>("t_testing_inserter (id)");
+    inserter.set_max_rows(2);
+
+    assert!(inserter.write(vec![1.into()]).await?.is_none());
+    let stats = inserter.write(vec![2.into()]).await?.unwrap();
+    assert_eq!(stats.rows, 2);
+    assert!(inserter.write(vec![3.into()]).await?.is_none());
+    assert_eq!(inserter.end().await?.rows, 1);
+
+    let mut inserter = client.inserter::<Row, JsonEachRowInput<Row>>("t_testing_inserter");
+    inserter
+        .set_max_bytes(1024 * 1024)
+        .set_max_period(Duration::from_secs(60));
+    inserter.write(Row { id: 4 }).await?;
+    assert_eq!(inserter.pending_rows(), 1);
+    assert_eq!(inserter.end().await?.rows, 1);
+
+    let (rows, _) = client
+        .select_with_format(
+            "SELECT id FROM t_testing_inserter ORDER BY id",
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            None,
+        )
+        .await?;
+    assert_eq!(
+        rows.iter().map(|x| x.id).collect::<Vec<_>>(),
+        vec![1, 2, 3, 4]
+    );
+
+    client
+        .execute("DROP TABLE t_testing_inserter", None)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use clickhouse_http_client::clickhouse_format::{input::NativeInput, output::NativeOutput};
+use serde::{Deserialize, Serialize};
+
+use super::helpers::*;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Row {
+    pub id: u32,
+    pub name: Option<String>,
+    pub tags: Vec<String>,
+    pub status: String,
+}
+
+#[tokio::test]
+async fn simple() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let client = get_client()?;
+
+    client
+        .execute("DROP TABLE IF EXISTS t_testing_native", None)
+        .await?;
+    client
+        .execute(
+            r#"
+CREATE TABLE t_testing_native
+(
+    id UInt32,
+    name LowCardinality(Nullable(String)),
+    tags Array(LowCardinality(String)),
+    status LowCardinality(String)
+) ENGINE=Memory
+            "#,
+            None,
+        )
+        .await?;
+
+    let rows = vec![
+        Row {
+            id: 1,
+            name: Some("a".into()),
+            tags: vec!["x".into(), "y".into()],
+            status: "ok".into(),
+        },
+        Row {
+            id: 2,
+            name: None,
+            tags: vec![],
+            status: "ok".into(),
+        },
+        Row {
+            id: 3,
+            name: Some("".into()),
+            tags: vec!["y".into()],
+            status: "failed".into(),
+        },
+    ];
+
+    let columns = vec![
+        ("id".to_owned(), "UInt32".parse()?),
+        (
+            "name".to_owned(),
+            "LowCardinality(Nullable(String))".parse()?,
+        ),
+        ("tags".to_owned(), "Array(LowCardinality(String))".parse()?),
+        ("status".to_owned(), "LowCardinality(String)".parse()?),
+    ];
+    client
+        .insert_with_format(
+            "INSERT INTO t_testing_native",
+            NativeInput::new(columns, rows.iter().collect()),
+            None,
+        )
+        .await?;
+
+    let (rows_selected, info) = client
+        .select_with_format(
+            "SELECT * FROM t_testing_native ORDER BY id",
+            NativeOutput::<Row>::new(),
+            None,
+        )
+        .await?;
+    assert_eq!(rows_selected, rows);
+    assert_eq!(info.get("tags").unwrap(), "Array(LowCardinality(String))");
+
+    client.execute("DROP TABLE t_testing_native", None).await?;
+
+    Ok(())
+}
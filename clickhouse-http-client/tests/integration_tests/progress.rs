@@ -0,0 +1,70 @@
+use core::time::Duration;
+
+use clickhouse_http_client::{
+    QuerySettings, clickhouse_format::output::JsonCompactEachRowWithNamesAndTypesOutput,
+};
+use futures_util::TryStreamExt as _;
+use serde::Deserialize;
+
+use super::helpers::*;
+
+#[derive(Deserialize, Debug)]
+pub struct Row {
+    pub count: u64,
+}
+
+#[tokio::test]
+async fn simple() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let client = get_client()?;
+
+    let mut settings = QuerySettings::new();
+    settings
+        .set_http_headers_progress_interval(Duration::from_millis(100))
+        .set_max_block_size(10000);
+
+    let mut progresses = vec![];
+    let (rows, _) = client
+        .select_with_format_and_progress_headers(
+            "SELECT count() AS count FROM numbers(10000000) WHERE NOT ignore(sleepEachRow(0))",
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            &settings,
+            |progress| progresses.push(progress),
+        )
+        .await?;
+    assert_eq!(rows[0].count, 10000000);
+
+    println!("{progresses:?}");
+    if let Some(progress) = progresses.last() {
+        assert!(progress.read_rows.unwrap_or_default() > 0);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn stream() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let client = get_client()?;
+
+    let mut progresses = vec![];
+    let rows: Vec<Row> = client
+        .select_stream_with_progress(
+            "SELECT count() AS count FROM numbers(10000000) WHERE NOT ignore(sleepEachRow(0))",
+            vec![("max_block_size", "10000"), ("interactive_delay", "100000")],
+            |progress| progresses.push(progress),
+        )
+        .await?
+        .try_collect()
+        .await?;
+    assert_eq!(rows[0].count, 10000000);
+
+    println!("{progresses:?}");
+    if let Some(progress) = progresses.last() {
+        assert!(progress.read_rows.unwrap_or_default() > 0);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+
+use clickhouse_http_client::clickhouse_format::output::JsonCompactEachRowWithNamesAndTypesOutput;
+use serde::Deserialize;
+
+use super::helpers::*;
+
+#[derive(Deserialize, Debug)]
+pub struct Row {
+    pub id: u64,
+    pub name: String,
+    pub tags: Vec<Option<String>>,
+    pub attrs: HashMap<String, u8>,
+}
+
+#[tokio::test]
+async fn simple() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let client = get_client()?;
+
+    let query = client
+        .query(
+            "SELECT {id:UInt64} AS id, {name:String} AS name, {tags:Array(Nullable(String))} AS tags, {attrs:Map(String, UInt8)} AS attrs",
+        )?
+        .bind("id", 1)?
+        .bind("name", "a'b\tc\\d")?
+        .bind("tags", vec![Some("x'"), None])?
+        .bind(
+            "attrs",
+            vec![("k", 2_u8)].into_iter().collect::<HashMap<_, _>>(),
+        )?;
+
+    let (rows, _) = query
+        .select_with_format(
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            None,
+        )
+        .await?;
+    assert_eq!(rows.len(), 1);
+    let row = rows.first().unwrap();
+    assert_eq!(row.id, 1);
+    assert_eq!(row.name, "a'b\tc\\d");
+    assert_eq!(row.tags, vec![Some("x'".to_owned()), None]);
+    assert_eq!(row.attrs.get("k"), Some(&2));
+
+    Ok(())
+}
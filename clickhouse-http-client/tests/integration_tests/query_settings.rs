@@ -0,0 +1,51 @@
+use clickhouse_http_client::{
+    QuerySettings, Readonly, clickhouse_format::output::JsonCompactEachRowWithNamesAndTypesOutput,
+};
+use serde::Deserialize;
+
+use super::helpers::*;
+
+#[derive(Deserialize, Debug)]
+pub struct Row {
+    pub max_threads: u64,
+    pub readonly: u8,
+}
+
+#[tokio::test]
+async fn simple() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let mut client = get_client()?;
+
+    let mut default_settings = QuerySettings::new();
+    default_settings
+        .set_max_threads(2)
+        .set_readonly(Readonly::ReadOnlyAllowSettings);
+    client.set_default_settings(default_settings);
+
+    let sql = "SELECT toUInt64(getSetting('max_threads')) AS max_threads, toUInt8(getSetting('readonly')) AS readonly";
+
+    let (rows, _) = client
+        .select_with_format(
+            sql,
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            None,
+        )
+        .await?;
+    assert_eq!(rows[0].max_threads, 2);
+    assert_eq!(rows[0].readonly, 2);
+
+    let mut settings = QuerySettings::new();
+    settings.set_max_threads(1);
+    let (rows, _) = client
+        .select_with_format(
+            sql,
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            &settings,
+        )
+        .await?;
+    assert_eq!(rows[0].max_threads, 1);
+    assert_eq!(rows[0].readonly, 2);
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use clickhouse_http_client::{
+    Error,
+    clickhouse_format::{
+        input::RowBinaryInput,
+        output::{RowBinaryOutput, RowBinaryWithNamesAndTypesOutput},
+    },
+    error::ClientSelectWithFormatError,
+};
+use serde::{Deserialize, Serialize};
+
+use super::helpers::*;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Row {
+    pub id: u32,
+    pub name: Option<String>,
+    pub tags: Vec<String>,
+    pub big: i128,
+}
+
+#[tokio::test]
+async fn simple() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let client = get_client()?;
+
+    client
+        .execute("DROP TABLE IF EXISTS t_testing_row_binary", None)
+        .await?;
+    client
+        .execute(
+            r#"
+CREATE TABLE t_testing_row_binary
+(
+    id UInt32,
+    name Nullable(String),
+    tags Array(String),
+    big Int128
+) ENGINE=Memory
+            "#,
+            None,
+        )
+        .await?;
+
+    let rows = vec![
+        Row {
+            id: 1,
+            name: Some("a".into()),
+            tags: vec!["x".into(), "y".into()],
+            big: i128::MIN,
+        },
+        Row {
+            id: 2,
+            name: None,
+            tags: vec![],
+            big: i128::MAX,
+        },
+    ];
+
+    client
+        .insert_with_format(
+            "INSERT INTO t_testing_row_binary",
+            RowBinaryInput::new(rows.iter().collect()),
+            None,
+        )
+        .await?;
+
+    let (rows_selected, _) = client
+        .select_with_format(
+            "SELECT * FROM t_testing_row_binary ORDER BY id",
+            RowBinaryOutput::<Row>::new(),
+            None,
+        )
+        .await?;
+    assert_eq!(rows_selected, rows);
+
+    let (rows_selected, info) = client
+        .select_with_format(
+            "SELECT * FROM t_testing_row_binary ORDER BY id",
+            RowBinaryWithNamesAndTypesOutput::<Row>::new(),
+            None,
+        )
+        .await?;
+    assert_eq!(rows_selected, rows);
+    assert_eq!(info.get("name").unwrap(), "Nullable(String)");
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    pub struct RowWithoutOption {
+        pub name: String,
+    }
+    match client
+        .select_with_format(
+            "SELECT name FROM t_testing_row_binary ORDER BY id",
+            RowBinaryWithNamesAndTypesOutput::<RowWithoutOption>::new(),
+            None,
+        )
+        .await
+    {
+        Err(Error::ClientSelectWithFormatError(ClientSelectWithFormatError::FormatDeError(
+            msg,
+        ))) => assert!(msg.contains("Nullable needs an Option"), "{msg}"),
+        ret => panic!("{ret:?}"),
+    }
+
+    client
+        .execute("DROP TABLE t_testing_row_binary", None)
+        .await?;
+
+    Ok(())
+}
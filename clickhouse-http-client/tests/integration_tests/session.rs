@@ -0,0 +1,79 @@
+use core::time::Duration;
+
+use clickhouse_http_client::clickhouse_format::{
+    input::JsonCompactEachRowInput, output::JsonCompactEachRowWithNamesAndTypesOutput,
+};
+use futures_util::future;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::helpers::*;
+
+#[derive(Deserialize, Debug)]
+pub struct Row {
+    pub id: u32,
+}
+
+#[tokio::test]
+async fn simple() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let client = get_client()?;
+
+    let mut session = client.session();
+    session.set_session_timeout(Duration::from_secs(60));
+
+    session
+        .execute(
+            "CREATE TEMPORARY TABLE t_testing_session (id UInt32) ENGINE=Memory",
+            None,
+        )
+        .await?;
+    session.execute("SET max_threads = 1", None).await?;
+
+    session.set_session_check(true);
+
+    let rows: Vec<Vec<Value>> = vec![vec![1.into()], vec![2.into()]];
+    session
+        .insert_with_format(
+            "INSERT INTO t_testing_session (id)",
+            JsonCompactEachRowInput::new(rows),
+            None,
+        )
+        .await?;
+
+    let (rows, _) = session
+        .select_with_format(
+            "SELECT id FROM t_testing_session ORDER BY id",
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            None,
+        )
+        .await?;
+    assert_eq!(rows.len(), 2);
+
+    let results = future::join_all((0..4).map(|_| {
+        session.select_with_format(
+            "SELECT toUInt32(getSetting('max_threads')) AS id",
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            None,
+        )
+    }))
+    .await;
+    for result in results {
+        let (rows, _) = result?;
+        assert_eq!(rows.first().unwrap().id, 1);
+    }
+
+    assert!(
+        client
+            .select_with_format(
+                "SELECT id FROM t_testing_session",
+                JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+                None,
+            )
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use clickhouse_http_client::{
+    Client, Error,
+    clickhouse_format::{
+        format_name::FormatName, output::JsonCompactEachRowWithNamesAndTypesOutput,
+    },
+    transport::{HyperTransport, ReqwestTransport},
+};
+use futures_util::{TryStreamExt as _, io::Cursor};
+use serde::Deserialize;
+
+use super::helpers::*;
+
+#[derive(Deserialize, Debug)]
+pub struct Row {
+    pub id: u32,
+}
+
+#[tokio::test]
+async fn with_hyper() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let client = get_client_with_transport(HyperTransport::new())?;
+
+    run(&client, "t_testing_transport_hyper").await
+}
+
+#[tokio::test]
+async fn with_reqwest() -> Result<(), Box<dyn std::error::Error>> {
+    init_logger();
+
+    let client = get_client_with_transport(ReqwestTransport::new()?)?;
+
+    run(&client, "t_testing_transport_reqwest").await
+}
+
+async fn run(client: &Client, table: &str) -> Result<(), Box<dyn std::error::Error>> {
+    assert!(client.ping().await?);
+
+    client
+        .execute(format!("DROP TABLE IF EXISTS {table}"), None)
+        .await?;
+    client
+        .execute(
+            format!("CREATE TABLE {table} (id UInt32) ENGINE=Memory"),
+            None,
+        )
+        .await?;
+
+    client
+        .insert_with_format_reader(
+            format!("INSERT INTO {table}"),
+            FormatName::JsonCompactEachRow,
+            Cursor::new(b"[1]\n[2]\n[3]\n".to_vec()),
+            None,
+        )
+        .await?;
+
+    let rows: Vec<Row> = client
+        .select_stream_with_format(
+            format!("SELECT * FROM {table} ORDER BY id"),
+            JsonCompactEachRowWithNamesAndTypesOutput::<Row>::new(),
+            None,
+        )
+        .await?
+        .try_collect()
+        .await?;
+    assert_eq!(rows.iter().map(|x| x.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+    match client.execute("SELECT * FROM t_not_exists", None).await {
+        Err(Error::ServerException(exception)) => assert_eq!(exception.code, 60),
+        ret => panic!("{ret:?}"),
+    }
+
+    client.execute(format!("DROP TABLE {table}"), None).await?;
+
+    Ok(())
+}
@@ -6,17 +6,13 @@ use core::time::Duration;
 use std::env;
 
 use clickhouse_http_client::{
-    ClientBuilder, clickhouse_format::output::JsonCompactEachRowWithNamesAndTypesOutput,
+    ClientBuilder, blocking::Client,
+    clickhouse_format::output::JsonCompactEachRowWithNamesAndTypesOutput,
     isahc::config::Configurable,
 };
-use futures_executor::block_on;
 use serde::Deserialize;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    block_on(run())
-}
-
-async fn run() -> Result<(), Box<dyn std::error::Error>> {
     let url = env::args().nth(1);
     let username = env::args().nth(2);
     let password = env::args().nth(3);
@@ -36,15 +32,13 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
     if let Some(database) = database {
         client_builder.set_database_to_header(database)?;
     }
-    let client = client_builder.build()?;
-
-    let (databases, _) = client
-        .select_with_format(
-            "show databases",
-            JsonCompactEachRowWithNamesAndTypesOutput::<Database>::new(),
-            None,
-        )
-        .await?;
+    let client = Client::try_from(client_builder.build()?)?;
+
+    let (databases, _) = client.select_with_format(
+        "show databases",
+        JsonCompactEachRowWithNamesAndTypesOutput::<Database>::new(),
+        None,
+    )?;
 
     println!("databases: {:?}", databases);
 